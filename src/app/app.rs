@@ -0,0 +1,80 @@
+// App -> what a demo implements, the runner owns the window / context and the loop
+use glfw::WindowEvent;
+
+use crate::{
+    camera::camera::OrthoCamera,
+    config::config::Config,
+    context::context::GlContext,
+    input::input::Input,
+    renderer::framebuffer::Framebuffer
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Exit,
+}
+
+// What we draw into -> framebuffer size in pixels and the content scale of its monitor
+// On HiDPI screens the framebuffer is `scale` times the window size glfw reports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Surface {
+    pub width: i32,
+    pub height: i32,
+    pub scale: (f32, f32),
+}
+
+impl Surface {
+    pub fn of( context: &dyn GlContext ) -> Self {
+        let ( width, height ) = context.framebuffer_size();
+        Self { width, height, scale: context.content_scale() }
+    }
+
+    // Minimized windows have a 0x0 framebuffer
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    // Size in screen coordinates, the unit of window sizes and cursor positions
+    pub fn logical_size(&self) -> (f32, f32) {
+        ( self.width as f32 / self.scale.0, self.height as f32 / self.scale.1 )
+    }
+}
+
+// Everything that follows the surface size, the runner resizes these before on_resize()
+#[derive(Default)]
+pub struct ResizeTargets<'a> {
+    pub framebuffers: Vec<&'a mut Framebuffer>,
+    pub cameras: Vec<&'a mut OrthoCamera>,
+}
+
+pub trait App {
+    // Called once the GL context is current -> create shaders, buffers and textures here
+    fn init( context: &dyn GlContext, config: &Config ) -> Self where Self: Sized;
+
+    // Runs at the fixed rate of `timing.update_rate`, `dt` is always that step in seconds
+    // `input` holds what happened since the last update and the action bindings
+    fn update( &mut self, _dt: f64, _input: &mut Input ) -> Flow {
+        Flow::Continue
+    }
+
+    // The target is bound and the viewport covers it
+    // `alpha` 0..1 -> how far the clock is between the last update and the next, for blending previous and current state
+    fn render( &mut self, alpha: f32 );
+
+    // Raw window events, after `input` saw them -> most apps only need update()
+    fn on_event( &mut self, _event: &WindowEvent ) -> Flow {
+        Flow::Continue
+    }
+
+    // Offscreen framebuffers and cameras that should match the surface
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets::default()
+    }
+
+    // Once at the start and whenever the framebuffer size or content scale changes, never with an empty surface
+    fn on_resize( &mut self, _surface: Surface ) {}
+
+    // Last call with the context still current
+    fn shutdown( &mut self ) {}
+}
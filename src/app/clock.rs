@@ -0,0 +1,132 @@
+// Clock -> where time comes from, the wall clock for windows, a simulated one for tests and recordings
+// FixedStep -> turns whatever the frame took into updates of exactly `step` seconds
+use std::time::Instant;
+
+// A frame longer than this ( breakpoint, window drag ) is cut short instead of replayed in hundreds of steps
+pub const MAX_FRAME_TIME: f64 = 0.25;
+
+pub trait Clock {
+    // Seconds since the clock started
+    fn now(&self) -> f64;
+}
+
+pub struct RealClock {
+    start: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        Self { start: Instant::now() }
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> f64 {
+        self.start.elapsed().as_secs_f64()
+    }
+}
+
+// Only moves when told to -> the same run gives the same frames
+pub struct SimulatedClock {
+    pub time: f64,
+}
+
+impl SimulatedClock {
+    pub fn new() -> Self {
+        Self { time: 0.0 }
+    }
+
+    pub fn advance( &mut self, seconds: f64 ) {
+        self.time += seconds;
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> f64 {
+        self.time
+    }
+}
+
+pub struct FixedStep {
+    pub step: f64,
+    // Time not yet covered by an update, always below `step` after advance()
+    accumulator: f64,
+}
+
+impl FixedStep {
+    pub fn new( step: f64 ) -> Self {
+        Self { step, accumulator: 0.0 }
+    }
+
+    pub fn from_rate( updates_per_second: f64 ) -> Self {
+        Self::new(1.0 / updates_per_second)
+    }
+
+    // Adds the frame time, returns how many updates to run now
+    // Steps longer than MAX_FRAME_TIME ( slow recordings ) still get one update per step
+    pub fn advance( &mut self, frame_time: f64 ) -> u32 {
+        self.accumulator += frame_time.clamp(0.0, MAX_FRAME_TIME.max(self.step));
+        let mut steps = 0;
+        while self.accumulator >= self.step {
+            self.accumulator -= self.step;
+            steps += 1;
+        }
+        steps
+    }
+
+    // How far we are between the last update and the next one, 0..1 -> render blends previous and current state
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Steps and frame times are powers of two -> the sums are exact
+    const STEP: f64 = 1.0 / 64.0;
+
+    // Moves the clock by `seconds` and feeds the measured frame time into `step`, like the runner does
+    fn frame( clock: &mut SimulatedClock, step: &mut FixedStep, seconds: f64 ) -> u32 {
+        let before = clock.now();
+        clock.advance(seconds);
+        step.advance(clock.now() - before)
+    }
+
+    #[test]
+    fn advance_runs_one_update_per_whole_step() {
+        let ( mut clock, mut step ) = ( SimulatedClock::new(), FixedStep::new(STEP) );
+        assert_eq!(frame(&mut clock, &mut step, STEP * 3.0), 3);
+        assert_eq!(frame(&mut clock, &mut step, STEP), 1);
+        assert_eq!(frame(&mut clock, &mut step, 0.0), 0);
+        assert_eq!(clock.now(), STEP * 4.0);
+    }
+
+    #[test]
+    fn long_frames_are_clamped_to_max_frame_time() {
+        let ( mut clock, mut step ) = ( SimulatedClock::new(), FixedStep::new(STEP) );
+        assert_eq!(frame(&mut clock, &mut step, 10.0), (MAX_FRAME_TIME / STEP) as u32);
+        // A clock going backwards adds nothing
+        assert_eq!(step.advance(-1.0), 0);
+        // Steps above the limit still get their update
+        let mut slow = FixedStep::new(0.5);
+        assert_eq!(frame(&mut clock, &mut slow, 0.5), 1);
+    }
+
+    #[test]
+    fn the_remainder_carries_over_and_alpha_stays_below_one() {
+        let ( mut clock, mut step ) = ( SimulatedClock::new(), FixedStep::new(STEP) );
+        assert_eq!(frame(&mut clock, &mut step, STEP * 0.75), 0);
+        assert_eq!(step.alpha(), 0.75);
+        assert_eq!(frame(&mut clock, &mut step, STEP * 0.75), 1);
+        assert_eq!(step.alpha(), 0.5);
+        assert_eq!(frame(&mut clock, &mut step, STEP * 2.5), 3);
+        assert_eq!(step.alpha(), 0.0);
+
+        for _ in 0..100 {
+            frame(&mut clock, &mut step, STEP * 0.375);
+            assert!((0.0..1.0).contains(&step.alpha()), "alpha {}", step.alpha());
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// Frame Times -> rolling window of the last frame durations, summarized for the window title
+use std::{collections::VecDeque, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameSummary {
+    pub frames: usize,
+    pub fps: f64,
+    // Seconds
+    pub min: f64,
+    pub average: f64,
+    // 99% of the frames were at least this fast -> shows hitches the average hides
+    pub p99: f64,
+}
+
+impl fmt::Display for FrameSummary {
+    fn fmt( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        write!(
+            f, "{:.0} fps | {:.2} ms min, {:.2} avg, {:.2} p99",
+            self.fps, self.min * 1000.0, self.average * 1000.0, self.p99 * 1000.0
+        )
+    }
+}
+
+pub struct FrameTimes {
+    samples: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl FrameTimes {
+    // Keeps the last `capacity` frames
+    pub fn new( capacity: usize ) -> Self {
+        Self { samples: VecDeque::with_capacity(capacity), capacity: capacity.max(1) }
+    }
+
+    pub fn record( &mut self, seconds: f64 ) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(seconds);
+    }
+
+    pub fn clear( &mut self ) {
+        self.samples.clear();
+    }
+
+    // None until the first frame
+    pub fn summary(&self) -> Option<FrameSummary> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.iter().copied().collect::<Vec<f64>>();
+        sorted.sort_by(f64::total_cmp);
+
+        let frames = sorted.len();
+        let average = sorted.iter().sum::<f64>() / frames as f64;
+        // Nearest rank -> the smallest sample with 99% of the samples at or below it
+        let rank = ((frames as f64 * 0.99).ceil() as usize).clamp(1, frames);
+        Some(FrameSummary {
+            frames,
+            fps: if average > 0.0 { 1.0 / average } else { 0.0 },
+            min: sorted[0],
+            average,
+            p99: sorted[rank - 1],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_uses_min_average_and_nearest_rank_p99() {
+        let mut times = FrameTimes::new(200);
+        assert_eq!(times.summary(), None);
+        // 1..=100 ms, shuffled -> p99 is the 99th smallest
+        for i in 0..100 {
+            times.record((i * 37 % 100 + 1) as f64 / 1000.0);
+        }
+        let summary = times.summary().unwrap();
+        assert_eq!(summary.frames, 100);
+        assert_eq!(summary.min, 0.001);
+        assert!((summary.average - 0.0505).abs() < 1e-12);
+        assert!((summary.fps - 1.0 / 0.0505).abs() < 1e-9);
+        assert_eq!(summary.p99, 0.099);
+
+        // One sample -> every statistic is that sample
+        times.clear();
+        assert_eq!(times.summary(), None);
+        times.record(0.02);
+        let summary = times.summary().unwrap();
+        assert_eq!(( summary.frames, summary.min, summary.average, summary.p99 ), ( 1, 0.02, 0.02, 0.02 ));
+    }
+
+    #[test]
+    fn only_the_last_capacity_frames_count() {
+        let mut times = FrameTimes::new(3);
+        for seconds in [ 0.5, 0.001, 0.002, 0.003 ] {
+            times.record(seconds);
+        }
+        let summary = times.summary().unwrap();
+        assert_eq!(( summary.frames, summary.p99 ), ( 3, 0.003 ));
+    }
+}
@@ -0,0 +1,290 @@
+// Runner -> glfw init, window, gl::load_with, events and swap, written once for every demo
+// Listens to four actions itself -> "quit" ( Escape ), "toggle_wireframe" ( F1 ), "gl_debug_severity" ( F2 ) and "rebind" ( F3 )
+use glfw::WindowEvent;
+
+use crate::{
+    app::{app::{App, Flow, Surface}, clock::{Clock, FixedStep, RealClock, SimulatedClock}, frame_times::FrameTimes},
+    config::config::Config,
+    context::context::{GlContext, WindowContext},
+    input::{actions::{ActionMap, Binding}, input::Input},
+    logging::logging::{APP, GL, WINDOW},
+    renderer::gl_debug
+};
+
+// Frames the title statistics cover, and how often the title changes -> readable, and set_title isn't free
+const STATS_FRAMES: usize = 240;
+const TITLE_INTERVAL: f64 = 0.5;
+
+// Resizes the app's framebuffers and cameras, then tells the app, empty surfaces are skipped
+pub fn apply_resize( app: &mut dyn App, surface: Surface ) -> Result<(), String> {
+    if surface.is_empty() {
+        return Ok(());
+    }
+    let targets = app.resize_targets();
+    for framebuffer in targets.framebuffers {
+        framebuffer.resize(surface.width, surface.height)?;
+    }
+    for camera in targets.cameras {
+        camera.resize(surface.width, surface.height);
+    }
+    app.on_resize(surface);
+    Ok(())
+}
+
+// Where a runtime rebind is -> "rebind" ( F3 ) asks for a binding that is in use, then for the one replacing it
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Rebinding {
+    Off,
+    Old,
+    New(Binding),
+}
+
+// The next press while rebinding, None keeps waiting
+fn rebind( input: &mut Input, rebinding: Rebinding ) -> Rebinding {
+    let Some(binding) = input.first_pressed() else { return rebinding };
+    match rebinding {
+        Rebinding::Off => Rebinding::Off,
+        Rebinding::Old => {
+            let actions = input.actions.actions_for(binding);
+            if actions.is_empty() {
+                let all = input.actions.actions().collect::<Vec<&str>>();
+                log::info!(target: APP, "{} isn't bound, press a binding of one of: {}", binding, all.join(", "));
+                return Rebinding::Old;
+            }
+            log::info!(target: APP, "{} -> {}, press its replacement", binding, actions.join(", "));
+            Rebinding::New(binding)
+        },
+        Rebinding::New(old) => {
+            let conflicts = input.actions.actions_for(binding).join(", ");
+            if !conflicts.is_empty() {
+                log::warn!(target: APP, "{} already triggers {}, it keeps doing that too", binding, conflicts);
+            }
+            for action in input.actions.actions_for(old).into_iter().map(String::from).collect::<Vec<String>>() {
+                input.actions.unbind(&action, old);
+                input.actions.bind(&action, binding);
+                log::info!(target: APP, "{}: {} -> {}", action, old, binding);
+            }
+            Rebinding::Off
+        },
+    }
+}
+
+// Runner actions, checked before every update so a press counts once however many steps the frame runs
+fn runner_actions( input: &mut Input, wireframe: &mut bool, rebinding: &mut Rebinding ) -> Flow {
+    for slot in input.gamepads.connected() {
+        let name = input.gamepads.pad(*slot).map( | pad | pad.name.as_str() ).unwrap_or("");
+        log::info!(target: APP, "gamepad {} connected: {} ( {} in use )", slot, name, input.gamepads.pads().count());
+    }
+    for slot in input.gamepads.disconnected() {
+        log::info!(target: APP, "gamepad {} disconnected", slot);
+    }
+
+    if *rebinding != Rebinding::Off {
+        // Runner actions wait until the rebind is done -> Escape can be rebound too
+        *rebinding = rebind(input, *rebinding);
+        return Flow::Continue;
+    }
+    // On release -> the key that started it is up again and can be the one picked
+    if input.action_released("rebind") {
+        let all = input.actions.actions().collect::<Vec<&str>>();
+        log::info!(target: APP, "Rebinding, press a binding of one of: {}", all.join(", "));
+        *rebinding = Rebinding::Old;
+    }
+    if input.action_pressed("toggle_wireframe") {
+        *wireframe = !*wireframe;
+        unsafe { gl::PolygonMode(gl::FRONT_AND_BACK, if *wireframe { gl::LINE } else { gl::FILL }) };
+    }
+    if input.action_pressed("gl_debug_severity") {
+        let severity = gl_debug::min_severity().next();
+        gl_debug::set_min_severity(severity);
+        log::info!(target: GL, "GL debug messages from {:?} up", severity);
+    }
+    if input.action_pressed("quit") { Flow::Exit } else { Flow::Continue }
+}
+
+// Builds an app once the context is current, DemoEntry::create
+pub type AppFactory = fn(&dyn GlContext, &Config) -> Box<dyn App>;
+
+// Opens the window, runs the app from `create` until it exits or the window closes
+// Updates run at the fixed `timing.update_rate`, rendering once per frame in between
+pub fn run( config: &Config, create: AppFactory ) -> Result<(), String> {
+    let mut context = WindowContext::new(&config.window)?;
+    context.window.set_framebuffer_size_polling(true);
+    context.window.set_content_scale_polling(true);
+    context.window.set_mouse_button_polling(true);
+    context.window.set_cursor_pos_polling(true);
+    context.window.set_cursor_enter_polling(true);
+    context.window.set_scroll_polling(true);
+    context.window.set_focus_polling(true);
+
+    let mut app = create(&context, config);
+    let mut surface = Surface::of(&context);
+    apply_resize(app.as_mut(), surface)?;
+    let mut input = Input::new(config.actions.clone());
+    input.gamepads.deadzone = config.deadzone;
+    let mut wireframe = false;
+    let mut rebinding = Rebinding::Off;
+
+    let clock = RealClock::new();
+    let mut step = FixedStep::from_rate(config.timing.update_rate);
+    let mut frame_times = FrameTimes::new(STATS_FRAMES);
+    let mut last_time = clock.now();
+    let mut last_title = last_time;
+    while !context.window.should_close() {
+        // Minimized -> sleep until something happens instead of spinning
+        if surface.is_empty() {
+            context.glfw.wait_events();
+        } else {
+            context.glfw.poll_events();
+        }
+        input.gamepads.poll(&context.glfw);
+        let events = glfw::flush_messages(&context.events).map( | ( _, event ) | event ).collect::<Vec<WindowEvent>>();
+        for event in events {
+            input.handle_event(&event);
+            if app.on_event(&event) == Flow::Exit {
+                context.window.set_should_close(true);
+            }
+        }
+
+        // Checked every frame, a size event can be missed while the OS drags the window
+        let current = Surface::of(&context);
+        if current != surface {
+            surface = current;
+            let ( width, height ) = surface.logical_size();
+            log::debug!(target: WINDOW, "framebuffer {}x{} -> {}x{} screen coordinates at {:?}", surface.width, surface.height, width, height, surface.scale);
+            apply_resize(app.as_mut(), surface)?;
+        }
+        let now = clock.now();
+        let frame_time = now - last_time;
+        last_time = now;
+        if surface.is_empty() {
+            // No time passes for the app while minimized, and the wait doesn't count as a slow frame
+            frame_times.clear();
+            continue;
+        }
+        frame_times.record(frame_time);
+
+        for _ in 0..step.advance(frame_time) {
+            let flow = runner_actions(&mut input, &mut wireframe, &mut rebinding);
+            if flow == Flow::Exit || app.update(step.step, &mut input) == Flow::Exit {
+                context.window.set_should_close(true);
+                break;
+            }
+            input.begin_frame();
+        }
+
+        context.bind_target();
+        app.render(step.alpha());
+        context.present();
+
+        if config.timing.stats_in_title && now - last_title >= TITLE_INTERVAL {
+            if let Some(summary) = frame_times.summary() {
+                context.window.set_title(&format!("{} | {}", config.window.title, summary));
+            }
+            last_title = now;
+        }
+    }
+
+    app.shutdown();
+    Ok(())
+}
+
+// Without events, on a simulated clock -> headless runs, tests and recordings
+// One update of `dt` per frame, frame 0 shows the state before any update
+// `after_frame` gets the frame number once it's rendered, before present()
+pub fn run_frames<F>( context: &mut dyn GlContext, app: &mut dyn App, frames: u32, dt: f64, mut after_frame: F ) -> Result<(), String>
+where
+    F: FnMut(u32) -> Result<(), String>
+{
+    apply_resize(app, Surface::of(context))?;
+    // Nobody is typing -> the state stays empty
+    let mut input = Input::new(ActionMap::defaults());
+    let mut clock = SimulatedClock::new();
+    let mut step = FixedStep::new(dt);
+    let mut last_time = clock.now();
+    for frame in 0..frames {
+        let now = clock.now();
+        for _ in 0..step.advance(now - last_time) {
+            if app.update(step.step, &mut input) == Flow::Exit {
+                return Ok(());
+            }
+        }
+        last_time = now;
+
+        context.bind_target();
+        app.render(step.alpha());
+        after_frame(frame)?;
+        context.present();
+        clock.advance(dt);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{app::app::ResizeTargets, camera::camera::{AspectMode, OrthoCamera, Viewport}};
+
+    struct CameraApp {
+        camera: OrthoCamera,
+        resized: Vec<Surface>,
+    }
+
+    impl App for CameraApp {
+        fn init( _context: &dyn GlContext, _config: &Config ) -> Self {
+            Self { camera: OrthoCamera::new(2.0, 2.0, AspectMode::Letterbox), resized: Vec::new() }
+        }
+
+        fn render( &mut self, _alpha: f32 ) {}
+
+        fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+            ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+        }
+
+        fn on_resize( &mut self, surface: Surface ) {
+            self.resized.push(surface);
+        }
+    }
+
+    #[test]
+    fn apply_resize_resizes_cameras_then_tells_the_app() {
+        let mut app = CameraApp { camera: OrthoCamera::new(2.0, 2.0, AspectMode::Letterbox), resized: Vec::new() };
+        let surface = Surface { width: 800, height: 400, scale: ( 1.0, 1.0 ) };
+        apply_resize(&mut app, surface).unwrap();
+        // Square view in a 2:1 surface -> pillarbox, centered in the 800 pixels
+        let pillarbox = Viewport { x: 200, y: 0, width: 400, height: 400 };
+        assert_eq!(app.camera.viewport(), pillarbox);
+        assert_eq!(app.resized, vec![ surface ]);
+
+        // Minimized -> nothing changes
+        apply_resize(&mut app, Surface { width: 0, height: 0, scale: ( 1.0, 1.0 ) }).unwrap();
+        assert_eq!(app.camera.viewport(), pillarbox);
+        assert_eq!(app.resized.len(), 1);
+    }
+
+    #[test]
+    fn rebind_moves_every_action_of_the_old_binding() {
+        use glfw::Key;
+
+        let mut input = Input::new(ActionMap::defaults());
+        // Not bound to anything -> still waiting for one that is
+        input.press(Binding::Key(Key::K));
+        assert_eq!(rebind(&mut input, Rebinding::Old), Rebinding::Old);
+        input.begin_frame();
+
+        input.press(Binding::Key(Key::W));
+        let rebinding = rebind(&mut input, Rebinding::Old);
+        assert_eq!(rebinding, Rebinding::New(Binding::Key(Key::W)));
+        input.begin_frame();
+        // Nothing pressed -> nothing changes
+        assert_eq!(rebind(&mut input, rebinding), rebinding);
+
+        input.press(Binding::Key(Key::I));
+        assert_eq!(rebind(&mut input, rebinding), Rebinding::Off);
+        assert_eq!(input.actions.actions_for(Binding::Key(Key::I)), vec![ "move_forward" ]);
+        assert!(input.actions.actions_for(Binding::Key(Key::W)).is_empty());
+        // The other bindings of the action stay
+        assert!(input.actions.bindings("move_forward").contains(&Binding::Key(Key::Up)));
+    }
+}
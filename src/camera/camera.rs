@@ -0,0 +1,303 @@
+// Orthographic 2D Camera
+use glm::{mat4, vec3, Mat4, Vec2};
+
+use crate::renderer::gl_state;
+
+// How the camera reacts when the framebuffer aspect differs from the camera aspect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AspectMode {
+    // Fill the whole framebuffer, shapes get stretched ( old behaviour )
+    Stretch,
+    // Keep the aspect ratio and add bars on the top/bottom or left/right
+    Letterbox,
+    // Keep the aspect ratio and show more of the world on the longer side
+    Expand,
+}
+
+// What one unit in world space means
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraUnits {
+    // `view_width` x `view_height` world units are visible, centered on `position`
+    World,
+    // One unit is one framebuffer pixel, (0, 0) is the lower left corner
+    Pixels,
+}
+
+// Area of the framebuffer the camera renders into -> passed to gl::Viewport
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+pub struct OrthoCamera {
+    pub position: Vec2,
+    pub zoom: f32,
+    pub rotation: f32,
+    pub view_width: f32,
+    pub view_height: f32,
+    pub near: f32,
+    pub far: f32,
+    pub mode: AspectMode,
+    pub units: CameraUnits,
+    framebuffer_width: i32,
+    framebuffer_height: i32,
+}
+
+impl OrthoCamera {
+    // `view_width` x `view_height` world units visible at zoom 1.0
+    pub fn new( view_width: f32, view_height: f32, mode: AspectMode ) -> Self {
+        Self {
+            position: glm::vec2(0.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            view_width,
+            view_height,
+            near: -1.0,
+            far: 1.0,
+            mode,
+            units: CameraUnits::World,
+            framebuffer_width: view_width.max(1.0) as i32,
+            framebuffer_height: view_height.max(1.0) as i32,
+        }
+    }
+
+    // Pixel-space camera for 2D / UI work, position is the pan in pixels
+    pub fn pixel_space( framebuffer_width: i32, framebuffer_height: i32 ) -> Self {
+        let mut camera = Self::new(framebuffer_width as f32, framebuffer_height as f32, AspectMode::Stretch);
+        camera.units = CameraUnits::Pixels;
+        camera.resize(framebuffer_width, framebuffer_height);
+        camera
+    }
+
+    // Call it whenever the framebuffer size changes
+    pub fn resize( &mut self, framebuffer_width: i32, framebuffer_height: i32 ) {
+        self.framebuffer_width = framebuffer_width.max(1);
+        self.framebuffer_height = framebuffer_height.max(1);
+        if self.units == CameraUnits::Pixels {
+            self.view_width = self.framebuffer_width as f32;
+            self.view_height = self.framebuffer_height as f32;
+        }
+    }
+
+    pub fn pan( &mut self, dx: f32, dy: f32 ) {
+        self.position.x += dx;
+        self.position.y += dy;
+    }
+
+    // Zoom is clamped so we never divide by zero
+    pub fn set_zoom( &mut self, zoom: f32 ) {
+        self.zoom = zoom.max(0.0001);
+    }
+
+    pub fn zoom_by( &mut self, factor: f32 ) {
+        self.set_zoom(self.zoom * factor);
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.view_width / self.view_height
+    }
+
+    // Part of the framebuffer we draw into ( bars are left untouched in Letterbox mode )
+    pub fn viewport(&self) -> Viewport {
+        let ( fb_w, fb_h ) = ( self.framebuffer_width, self.framebuffer_height );
+        if self.mode != AspectMode::Letterbox || self.units == CameraUnits::Pixels {
+            return Viewport { x: 0, y: 0, width: fb_w, height: fb_h };
+        }
+
+        let fb_aspect = fb_w as f32 / fb_h as f32;
+        if fb_aspect > self.aspect() {
+            // Window is wider -> pillarbox ( bars left and right )
+            let width = (fb_h as f32 * self.aspect()).round() as i32;
+            Viewport { x: (fb_w - width) / 2, y: 0, width, height: fb_h }
+        } else {
+            // Window is taller -> letterbox ( bars top and bottom )
+            let height = (fb_w as f32 / self.aspect()).round() as i32;
+            Viewport { x: 0, y: (fb_h - height) / 2, width: fb_w, height }
+        }
+    }
+
+    // Visible world extent ( width, height ) after zoom and aspect correction
+    pub fn visible_size(&self) -> (f32, f32) {
+        let ( mut width, mut height ) = ( self.view_width, self.view_height );
+        if self.mode == AspectMode::Expand && self.units == CameraUnits::World {
+            let fb_aspect = self.framebuffer_width as f32 / self.framebuffer_height as f32;
+            if fb_aspect > self.aspect() {
+                width = height * fb_aspect;
+            } else {
+                height = width / fb_aspect;
+            }
+        }
+        ( width / self.zoom, height / self.zoom )
+    }
+
+    pub fn projection(&self) -> Mat4 {
+        let ( width, height ) = self.visible_size();
+        match self.units {
+            CameraUnits::World => ortho(-width / 2.0, width / 2.0, -height / 2.0, height / 2.0, self.near, self.far),
+            CameraUnits::Pixels => ortho(0.0, width, 0.0, height, self.near, self.far),
+        }
+    }
+
+    // Inverse of the camera transform ( translate by position, then rotate )
+    pub fn view(&self) -> Mat4 {
+        let identity = mat4(
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        );
+        let rotation = glm::ext::rotate(&identity, -self.rotation, vec3(0.0, 0.0, 1.0));
+        glm::ext::translate(&rotation, vec3(-self.position.x, -self.position.y, 0.0))
+    }
+
+    pub fn view_projection(&self) -> Mat4 {
+        self.projection() * self.view()
+    }
+
+    // Framebuffer pixel ( origin top left, like glfw cursor positions ) -> world position
+    pub fn screen_to_world( &self, screen_x: f32, screen_y: f32 ) -> Vec2 {
+        let viewport = self.viewport();
+        let ndc_x = (screen_x - viewport.x as f32) / viewport.width as f32 * 2.0 - 1.0;
+        let ndc_y = 1.0 - (screen_y - (self.framebuffer_height - viewport.y - viewport.height) as f32) / viewport.height as f32 * 2.0;
+        let world = glm::inverse(&self.view_projection()) * glm::vec4(ndc_x, ndc_y, 0.0, 1.0);
+        glm::vec2(world.x, world.y)
+    }
+
+    // Clears the bars black and the viewport with `clear_color`, then restricts drawing to the viewport
+    pub fn apply_viewport( &self, clear_color: [f32; 4] ) {
+        let viewport = self.viewport();
+        if viewport.width != self.framebuffer_width || viewport.height != self.framebuffer_height {
+            gl_state::clear_color([ 0.0, 0.0, 0.0, 1.0 ]);
+            unsafe { gl::Clear(gl::COLOR_BUFFER_BIT) };
+        }
+        // Clear ignores the viewport, the scissor keeps the color off the bars
+        gl_state::set_scissor_test(true);
+        gl_state::scissor(viewport.x, viewport.y, viewport.width, viewport.height);
+        gl_state::clear_color(clear_color);
+        unsafe { gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT) };
+        gl_state::set_scissor_test(false);
+        gl_state::viewport(viewport.x, viewport.y, viewport.width, viewport.height);
+    }
+
+}
+
+// Same as glOrtho -> maps the box to [-1, 1] on every axis
+pub fn ortho( left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32 ) -> Mat4 {
+    mat4(
+        2.0 / (right - left), 0.0, 0.0, 0.0,
+        0.0, 2.0 / (top - bottom), 0.0, 0.0,
+        0.0, 0.0, -2.0 / (far - near), 0.0,
+        -(right + left) / (right - left), -(top + bottom) / (top - bottom), -(far + near) / (far - near), 1.0,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // World -> framebuffer pixel with the origin top left, the inverse of screen_to_world
+    fn world_to_screen( camera: &OrthoCamera, world: Vec2, framebuffer_height: i32 ) -> ( f32, f32 ) {
+        let clip = camera.view_projection() * glm::vec4(world.x, world.y, 0.0, 1.0);
+        let viewport = camera.viewport();
+        let x = viewport.x as f32 + (clip.x / clip.w + 1.0) / 2.0 * viewport.width as f32;
+        let y = viewport.y as f32 + (clip.y / clip.w + 1.0) / 2.0 * viewport.height as f32;
+        ( x, framebuffer_height as f32 - y )
+    }
+
+    fn close( a: f32, b: f32 ) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    #[test]
+    fn stretch_fills_the_framebuffer_and_keeps_the_view() {
+        let mut camera = OrthoCamera::new(4.0, 4.0, AspectMode::Stretch);
+        camera.resize(800, 400);
+        assert_eq!(camera.viewport(), Viewport { x: 0, y: 0, width: 800, height: 400 });
+        assert_eq!(camera.visible_size(), ( 4.0, 4.0 ));
+    }
+
+    #[test]
+    fn letterbox_adds_bars_on_the_longer_side() {
+        let mut camera = OrthoCamera::new(4.0, 4.0, AspectMode::Letterbox);
+        camera.resize(800, 400);
+        assert_eq!(camera.viewport(), Viewport { x: 200, y: 0, width: 400, height: 400 });
+        camera.resize(400, 1000);
+        assert_eq!(camera.viewport(), Viewport { x: 0, y: 300, width: 400, height: 400 });
+        assert_eq!(camera.visible_size(), ( 4.0, 4.0 ));
+    }
+
+    #[test]
+    fn expand_shows_more_world_on_the_longer_side() {
+        let mut camera = OrthoCamera::new(4.0, 3.0, AspectMode::Expand);
+        camera.resize(800, 300);
+        assert_eq!(camera.viewport(), Viewport { x: 0, y: 0, width: 800, height: 300 });
+        assert_eq!(camera.visible_size(), ( 8.0, 3.0 ));
+        camera.resize(400, 400);
+        assert_eq!(camera.visible_size(), ( 4.0, 4.0 ));
+    }
+
+    #[test]
+    fn zoom_shrinks_the_view_and_never_reaches_zero() {
+        let mut camera = OrthoCamera::new(4.0, 2.0, AspectMode::Stretch);
+        camera.zoom_by(2.0);
+        assert_eq!(camera.visible_size(), ( 2.0, 1.0 ));
+        camera.set_zoom(0.0);
+        assert!(camera.zoom > 0.0 && camera.visible_size().0.is_finite());
+    }
+
+    #[test]
+    fn pan_moves_what_is_at_the_center() {
+        let mut camera = OrthoCamera::new(4.0, 4.0, AspectMode::Stretch);
+        camera.resize(400, 400);
+        camera.pan(1.0, -2.0);
+        camera.pan(0.5, 0.0);
+        let center = camera.screen_to_world(200.0, 200.0);
+        assert!(close(center.x, 1.5) && close(center.y, -2.0), "{:?}", ( center.x, center.y ));
+    }
+
+    #[test]
+    fn screen_to_world_inverts_the_view_projection() {
+        for mode in [ AspectMode::Stretch, AspectMode::Letterbox, AspectMode::Expand ] {
+            let mut camera = OrthoCamera::new(6.0, 4.0, mode);
+            camera.resize(900, 300);
+            camera.position = glm::vec2(1.0, -0.5);
+            camera.zoom = 1.5;
+            camera.rotation = 0.3;
+            for ( x, y ) in [ ( 0.0, 0.0 ), ( 1.2, -0.7 ), ( -0.4, 0.9 ) ] {
+                let ( screen_x, screen_y ) = world_to_screen(&camera, glm::vec2(x, y), 300);
+                let world = camera.screen_to_world(screen_x, screen_y);
+                assert!(close(world.x, x) && close(world.y, y), "{:?}: {:?} -> {:?}", mode, ( x, y ), ( world.x, world.y ));
+            }
+        }
+    }
+
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    #[test]
+    fn apply_viewport_goes_through_the_state_cache() {
+        use crate::context::context::GlContext;
+        let context = crate::context::headless::HeadlessContext::new(80, 40).unwrap();
+        context.bind_target();
+        let mut camera = OrthoCamera::new(4.0, 4.0, AspectMode::Letterbox);
+        camera.resize(80, 40);
+        camera.apply_viewport([ 0.2, 0.3, 0.4, 1.0 ]);
+        assert_eq!(gl_state::verify(), Ok(()));
+        // Same frame again -> only the clears reach GL, and the scissor toggling
+        let ( changes, _ ) = gl_state::counters();
+        camera.apply_viewport([ 0.2, 0.3, 0.4, 1.0 ]);
+        assert_eq!(gl_state::counters().0, changes + 4);
+        assert_eq!(gl_state::verify(), Ok(()));
+    }
+
+    #[test]
+    fn pixel_space_follows_the_framebuffer() {
+        let mut camera = OrthoCamera::pixel_space(640, 480);
+        camera.resize(320, 200);
+        assert_eq!(camera.visible_size(), ( 320.0, 200.0 ));
+        // Top left pixel of the screen is y = 200 in pixel space
+        let corner = camera.screen_to_world(0.0, 0.0);
+        assert!(close(corner.x, 0.0) && close(corner.y, 200.0));
+    }
+}
@@ -0,0 +1,212 @@
+// View Frustum -> skip objects which are completely off-screen
+use glm::{vec3, Mat4, Vec3};
+
+use crate::utils::bounds::{Aabb, BoundingSphere};
+
+// Plane as normal . point + distance = 0, the normal points inside the frustum
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vec3,
+    pub distance: f32,
+}
+
+impl Plane {
+    // Normalized so `signed_distance` returns real world units
+    pub fn new( a: f32, b: f32, c: f32, d: f32 ) -> Self {
+        let length = glm::length(vec3(a, b, c));
+        Self {
+            normal: vec3(a / length, b / length, c / length),
+            distance: d / length,
+        }
+    }
+
+    pub fn signed_distance( &self, point: Vec3 ) -> f32 {
+        glm::dot(self.normal, point) + self.distance
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    // Left, Right, Bottom, Top, Near, Far
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    // Gribb / Hartmann plane extraction, works for ortho and perspective projections
+    pub fn from_view_projection( view_projection: &Mat4 ) -> Self {
+        let m = view_projection;
+        let row = | i: usize | [ m.c0[i], m.c1[i], m.c2[i], m.c3[i] ];
+        let ( r0, r1, r2, r3 ) = ( row(0), row(1), row(2), row(3) );
+        let add = | a: [f32; 4], b: [f32; 4] | Plane::new(a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]);
+        let sub = | a: [f32; 4], b: [f32; 4] | Plane::new(a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]);
+
+        Self {
+            planes: [
+                add(r3, r0), // Left
+                sub(r3, r0), // Right
+                add(r3, r1), // Bottom
+                sub(r3, r1), // Top
+                add(r3, r2), // Near
+                sub(r3, r2), // Far
+            ]
+        }
+    }
+
+    pub fn intersects_sphere( &self, sphere: &BoundingSphere ) -> bool {
+        self.planes.iter().all( | plane | plane.signed_distance(sphere.center) >= -sphere.radius )
+    }
+
+    // Only tests the corner furthest along each plane normal ( "positive vertex" )
+    pub fn intersects_aabb( &self, aabb: &Aabb ) -> bool {
+        self.planes.iter().all( | plane | {
+            let positive = vec3(
+                if plane.normal.x >= 0.0 { aabb.max.x } else { aabb.min.x },
+                if plane.normal.y >= 0.0 { aabb.max.y } else { aabb.min.y },
+                if plane.normal.z >= 0.0 { aabb.max.z } else { aabb.min.z },
+            );
+            plane.signed_distance(positive) >= 0.0
+        })
+    }
+}
+
+// Visible / Culled counters -> reset at the start of every frame
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CullStats {
+    pub visible: u32,
+    pub culled: u32,
+}
+
+impl CullStats {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn total(&self) -> u32 {
+        self.visible + self.culled
+    }
+
+    // Returns the result so it can be used directly as `if stats.record(..) { draw }`
+    pub fn record( &mut self, visible: bool ) -> bool {
+        if visible {
+            self.visible += 1;
+        } else {
+            self.culled += 1;
+        }
+        visible
+    }
+}
+
+// Frustum + counters for one frame
+pub struct Culler {
+    pub frustum: Frustum,
+    pub stats: CullStats,
+}
+
+impl Culler {
+    pub fn new( view_projection: &Mat4 ) -> Self {
+        Self {
+            frustum: Frustum::from_view_projection(view_projection),
+            stats: CullStats::default(),
+        }
+    }
+
+    // Start a new frame with the current camera
+    pub fn begin_frame( &mut self, view_projection: &Mat4 ) {
+        self.frustum = Frustum::from_view_projection(view_projection);
+        self.stats.reset();
+    }
+
+    // `local_bounds` is in model space, `model` moves it into the world
+    pub fn test_aabb( &mut self, local_bounds: &Aabb, model: &Mat4 ) -> bool {
+        let visible = self.frustum.intersects_aabb(&local_bounds.transformed(model));
+        self.stats.record(visible)
+    }
+
+    pub fn test_sphere( &mut self, local_bounds: &BoundingSphere, model: &Mat4 ) -> bool {
+        let visible = self.frustum.intersects_sphere(&local_bounds.transformed(model));
+        self.stats.record(visible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl Frustum {
+        fn contains_point( &self, point: Vec3 ) -> bool {
+            self.planes.iter().all( | plane | plane.signed_distance(point) >= 0.0 )
+        }
+    }
+
+    use crate::{camera::camera::ortho, scene::transform::identity};
+
+    // x -2..2, y -1..1, z -1..1 in world space
+    fn frustum() -> Frustum {
+        Frustum::from_view_projection(&ortho(-2.0, 2.0, -1.0, 1.0, -1.0, 1.0))
+    }
+
+    fn assert_plane( plane: &Plane, normal: [f32; 3], distance: f32 ) {
+        let expected = vec3(normal[0], normal[1], normal[2]);
+        assert!(glm::distance(plane.normal, expected) < 1e-5, "normal {:?}, expected {:?}", plane.normal, expected);
+        assert!((plane.distance - distance).abs() < 1e-5, "distance {}, expected {}", plane.distance, distance);
+    }
+
+    #[test]
+    fn planes_come_from_the_projection() {
+        let planes = frustum().planes;
+        assert_plane(&planes[0], [ 1.0, 0.0, 0.0 ], 2.0);
+        assert_plane(&planes[1], [ -1.0, 0.0, 0.0 ], 2.0);
+        assert_plane(&planes[2], [ 0.0, 1.0, 0.0 ], 1.0);
+        assert_plane(&planes[3], [ 0.0, -1.0, 0.0 ], 1.0);
+        // glOrtho looks down -z -> near is at z = 1, far at z = -1
+        assert_plane(&planes[4], [ 0.0, 0.0, -1.0 ], 1.0);
+        assert_plane(&planes[5], [ 0.0, 0.0, 1.0 ], 1.0);
+    }
+
+    #[test]
+    fn points_inside_and_outside() {
+        let frustum = frustum();
+        assert!(frustum.contains_point(vec3(0.0, 0.0, 0.0)));
+        assert!(frustum.contains_point(vec3(1.9, -0.9, 0.5)));
+        assert!(!frustum.contains_point(vec3(2.1, 0.0, 0.0)));
+        assert!(!frustum.contains_point(vec3(0.0, 0.0, -1.5)));
+    }
+
+    #[test]
+    fn aabb_inside_outside_and_crossing() {
+        let frustum = frustum();
+        let inside = Aabb::new(vec3(-0.5, -0.5, -0.5), vec3(0.5, 0.5, 0.5));
+        let outside = Aabb::new(vec3(2.5, -0.5, -0.5), vec3(3.0, 0.5, 0.5));
+        let crossing = Aabb::new(vec3(1.5, 0.5, -0.5), vec3(2.5, 1.5, 0.5));
+        assert!(frustum.intersects_aabb(&inside));
+        assert!(!frustum.intersects_aabb(&outside));
+        assert!(frustum.intersects_aabb(&crossing));
+        // Above the top plane only
+        assert!(!frustum.intersects_aabb(&Aabb::new(vec3(-0.5, 1.1, -0.5), vec3(0.5, 2.0, 0.5))));
+    }
+
+    #[test]
+    fn sphere_inside_outside_and_crossing() {
+        let frustum = frustum();
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, 0.0, 0.0), 0.5)));
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, 2.0, 0.0), 0.5)));
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(vec3(2.3, 0.0, 0.0), 0.5)));
+        // Just past the corner along both axes -> still counts, the test is per plane
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(vec3(2.2, 1.2, 0.0), 0.3)));
+    }
+
+    #[test]
+    fn culler_counts_visible_and_culled() {
+        let mut culler = Culler::new(&ortho(-2.0, 2.0, -1.0, 1.0, -1.0, 1.0));
+        let unit = Aabb::new(vec3(-0.5, -0.5, -0.5), vec3(0.5, 0.5, 0.5));
+        let moved = glm::ext::translate(&identity(), vec3(5.0, 0.0, 0.0));
+        assert!(culler.test_aabb(&unit, &identity()));
+        assert!(!culler.test_aabb(&unit, &moved));
+        assert!(!culler.test_sphere(&BoundingSphere::new(vec3(0.0, 0.0, 0.0), 1.0), &moved));
+        assert_eq!(culler.stats, CullStats { visible: 1, culled: 2 });
+        assert_eq!(culler.stats.total(), 3);
+
+        culler.begin_frame(&ortho(-2.0, 2.0, -1.0, 1.0, -1.0, 1.0));
+        assert_eq!(culler.stats.total(), 0);
+    }
+}
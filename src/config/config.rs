@@ -0,0 +1,468 @@
+// Config -> defaults of the demo, then the config file, then command line flags, later wins
+//   cargo run -- --demo triangle --config my.toml --width 1280 --samples 4
+// See openglyt.toml for every key
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+
+use log::LevelFilter;
+
+use crate::{
+    demos::demos::{DEMOS, SHAPES},
+    input::{actions::{ActionMap, Binding}, gamepad::Deadzone},
+    logging::logging::{parse_level, TARGETS},
+    renderer::gl_debug::Severity,
+    utils::toml::Toml
+};
+
+// Read when no --config is given, a missing file there just means defaults
+pub const DEFAULT_CONFIG_PATH: &str = "openglyt.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlProfile {
+    Core,
+    Compat,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    pub title: String,
+    pub vsync: bool,
+    // MSAA samples, 0 -> off
+    pub samples: u32,
+    pub fullscreen: bool,
+    // Index into the connected monitors, only used for fullscreen
+    pub monitor: usize,
+    pub gl_version: (u32, u32),
+    pub gl_profile: GlProfile,
+    // KHR_debug messages from the driver, on in debug builds
+    pub gl_debug: bool,
+    pub gl_debug_severity: Severity,
+    // Compare the state cache with glGet* on every skipped bind -> a pipeline stall each time, for chasing cache bugs only
+    pub gl_verify_state: bool,
+}
+
+impl WindowConfig {
+    pub fn new( width: u32, height: u32, title: &str ) -> Self {
+        Self {
+            width,
+            height,
+            title: title.to_string(),
+            vsync: true,
+            samples: 0,
+            fullscreen: false,
+            monitor: 0,
+            gl_version: ( 3, 3 ),
+            gl_profile: GlProfile::Core,
+            gl_debug: cfg!(debug_assertions),
+            gl_debug_severity: Severity::Low,
+            gl_verify_state: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingConfig {
+    // Fixed updates per second, rendering runs as fast as vsync allows and interpolates in between
+    pub update_rate: f64,
+    // fps and frame times ( min / avg / p99 ) after the window title
+    pub stats_in_title: bool,
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self { update_rate: 60.0, stats_in_title: true }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogConfig {
+    pub level: LevelFilter,
+    // Overrides `level` for one target, e.g. "shader" -> Debug
+    pub targets: BTreeMap<String, LevelFilter>,
+    // Messages on stderr
+    pub console: bool,
+    // Also every message as one JSON object per line, for tooling
+    pub json_file: Option<PathBuf>,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self { level: LevelFilter::Info, targets: BTreeMap::new(), console: true, json_file: None }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub window: WindowConfig,
+    // Textures are read from here first, the copies built into the binary are the fallback
+    pub asset_root: PathBuf,
+    // Fill color by shape name ( SHAPES ), clear color by demo name ( DEMOS )
+    pub colors: BTreeMap<String, [f32; 4]>,
+    pub clear_colors: BTreeMap<String, [f32; 4]>,
+    // Defaults plus `[input]`, an action set there loses its default bindings
+    pub actions: ActionMap,
+    pub deadzone: Deadzone,
+    pub timing: TimingConfig,
+    pub log: LogConfig,
+}
+
+// Command line flag -> config key, the value is parsed like in the file
+const FLAGS: &[(&str, &str)] = &[
+    ( "--width", "window.width" ),
+    ( "--height", "window.height" ),
+    ( "--title", "window.title" ),
+    ( "--samples", "window.samples" ),
+    ( "--monitor", "window.monitor" ),
+    ( "--gl-version", "gl.version" ),
+    ( "--gl-profile", "gl.profile" ),
+    ( "--assets", "assets.root" ),
+    ( "--update-rate", "timing.update_rate" ),
+    ( "--log-level", "log.level" ),
+    ( "--log-json", "log.json_file" ),
+];
+
+// Flags without a value
+const SWITCHES: &[(&str, &str, bool)] = &[
+    ( "--vsync", "window.vsync", true ),
+    ( "--no-vsync", "window.vsync", false ),
+    ( "--fullscreen", "window.fullscreen", true ),
+    ( "--windowed", "window.fullscreen", false ),
+    ( "--gl-debug", "gl.debug", true ),
+    ( "--no-gl-debug", "gl.debug", false ),
+    ( "--verify-gl-state", "gl.verify_state", true ),
+    ( "--quiet", "log.console", false ),
+    ( "--stats", "timing.stats_in_title", true ),
+    ( "--no-stats", "timing.stats_in_title", false ),
+];
+
+// Handled by main / Config::load, listed here so apply_args can tell them from typos
+// ( flag, takes a value )
+const PROGRAM_FLAGS: &[(&str, bool)] = &[
+    ( "--config", true ),
+    ( "--demo", true ),
+    ( "--list", false ),
+    ( "--record", true ),
+    ( "--frames", true ),
+];
+
+// Golden images render offscreen -> only the headless build knows them, the windowed one says why it can't
+const HEADLESS_FLAGS: &[&str] = &[ "--golden-check", "--golden-update" ];
+
+// Every flag the binary understands, for error messages
+fn all_flags() -> Vec<&'static str> {
+    FLAGS.iter().map( | ( flag, _ ) | *flag )
+        .chain(SWITCHES.iter().map( | ( flag, _, _ ) | *flag ))
+        .chain(PROGRAM_FLAGS.iter().map( | ( flag, _ ) | *flag ))
+        .chain(HEADLESS_FLAGS.iter().copied().filter( | _ | cfg!(all(feature = "headless", target_os = "linux")) ))
+        .collect()
+}
+
+fn positive( key: &str, value: &Toml ) -> Result<u32, String> {
+    value.as_integer()
+        .filter( | number | *number > 0 && *number <= u32::MAX as i64 )
+        .map( | number | number as u32 )
+        .ok_or_else( || format!("{}: expected a positive integer, got {}", key, describe(value)) )
+}
+
+fn unsigned( key: &str, value: &Toml ) -> Result<u32, String> {
+    value.as_integer()
+        .filter( | number | *number >= 0 && *number <= u32::MAX as i64 )
+        .map( | number | number as u32 )
+        .ok_or_else( || format!("{}: expected 0 or a positive integer, got {}", key, describe(value)) )
+}
+
+// 0 <= x < 1 -> a deadzone of 1 would swallow the whole range
+fn fraction( key: &str, value: &Toml ) -> Result<f32, String> {
+    value.as_float()
+        .filter( | number | (0.0..1.0).contains(number) )
+        .map( | number | number as f32 )
+        .ok_or_else( || format!("{}: expected a number from 0 up to but not including 1, got {}", key, describe(value)) )
+}
+
+// Updates per second -> below 1 nothing moves, above 1000 a frame can't keep up with its updates
+fn rate( key: &str, value: &Toml ) -> Result<f64, String> {
+    value.as_float()
+        .filter( | number | (1.0..=1000.0).contains(number) )
+        .ok_or_else( || format!("{}: expected updates per second from 1 to 1000, got {}", key, describe(value)) )
+}
+
+fn boolean( key: &str, value: &Toml ) -> Result<bool, String> {
+    value.as_bool().ok_or_else( || format!("{}: expected true or false, got {}", key, describe(value)) )
+}
+
+fn string<'a>( key: &str, value: &'a Toml ) -> Result<&'a str, String> {
+    value.as_str().ok_or_else( || format!("{}: expected a string, got {}", key, describe(value)) )
+}
+
+// [ r, g, b ] or [ r, g, b, a ], every channel in 0..1
+fn color( key: &str, value: &Toml ) -> Result<[f32; 4], String> {
+    let error = || format!("{}: expected [ r, g, b ] or [ r, g, b, a ] with channels from 0 to 1, got {}", key, describe(value));
+    let channels = value.as_array().ok_or_else(error)?;
+    if channels.len() != 3 && channels.len() != 4 {
+        return Err(error());
+    }
+    let mut result = [ 0.0, 0.0, 0.0, 1.0 ];
+    for ( slot, channel ) in result.iter_mut().zip(channels) {
+        *slot = channel.as_float().filter( | c | (0.0..=1.0).contains(c) ).ok_or_else(error)? as f32;
+    }
+    Ok(result)
+}
+
+// "W" or [ "W", "Up" ], [] unbinds
+fn bindings( key: &str, value: &Toml ) -> Result<Vec<Binding>, String> {
+    let names = match value {
+        Toml::String(name) => vec![ name.as_str() ],
+        Toml::Array(values) => values.iter()
+            .map( | value | value.as_str().ok_or_else( || format!("{}: expected key names, got {}", key, describe(value)) ) )
+            .collect::<Result<Vec<&str>, String>>()?,
+        _ => return Err(format!("{}: expected a key name or an array of them, got {}", key, describe(value))),
+    };
+    names.into_iter().map( | name | Binding::parse(name).map_err( | err | format!("{}: {}", key, err) ) ).collect()
+}
+
+fn describe( value: &Toml ) -> String {
+    match value {
+        Toml::String(text) => format!("\"{}\"", text),
+        Toml::Integer(number) => number.to_string(),
+        Toml::Float(number) => number.to_string(),
+        Toml::Bool(flag) => flag.to_string(),
+        Toml::Array(_) => value.type_name().to_string(),
+    }
+}
+
+fn level( key: &str, value: &Toml ) -> Result<LevelFilter, String> {
+    parse_level(string(key, value)?).map_err( | err | format!("{}: {}", key, err) )
+}
+
+// "4.1" -> ( 4, 1 )
+fn gl_version( key: &str, value: &Toml ) -> Result<(u32, u32), String> {
+    let text = string(key, value)?;
+    let version = text.split_once('.')
+        .and_then( | ( major, minor ) | Some(( major.parse::<u32>().ok()?, minor.parse::<u32>().ok()? )) )
+        .ok_or_else( || format!("{}: expected \"major.minor\", e.g. \"3.3\", got \"{}\"", key, text) )?;
+    // The shaders are #version 330
+    if version < ( 3, 3 ) {
+        return Err(format!("{}: {} is too old, the shaders need 3.3 or newer", key, text));
+    }
+    Ok(version)
+}
+
+impl Config {
+    pub fn new( window: WindowConfig ) -> Self {
+        Self {
+            window,
+            asset_root: PathBuf::from("src").join("assets"),
+            colors: BTreeMap::new(),
+            clear_colors: BTreeMap::new(),
+            actions: ActionMap::defaults(),
+            deadzone: Deadzone::default(),
+            timing: TimingConfig::default(),
+            log: LogConfig::default(),
+        }
+    }
+
+    // `window` is the demo's own size and title, the file and `args` go on top
+    pub fn load( window: WindowConfig, args: &[String] ) -> Result<Self, String> {
+        let mut config = Self::new(window);
+        let explicit = args.iter().position( | arg | arg == "--config" ).map( | i | {
+            args.get(i + 1).map(PathBuf::from).ok_or_else( || "--config: expected a path".to_string() )
+        });
+        match explicit {
+            Some(path) => config.load_file(&path?)?,
+            None => {
+                let path = Path::new(DEFAULT_CONFIG_PATH);
+                if path.exists() {
+                    config.load_file(path)?;
+                }
+            },
+        }
+        config.apply_args(args)?;
+        Ok(config)
+    }
+
+    pub fn color( &self, shape: &str, default: [f32; 4] ) -> [f32; 4] {
+        self.colors.get(shape).copied().unwrap_or(default)
+    }
+
+    pub fn clear_color( &self, demo: &str, default: [f32; 4] ) -> [f32; 4] {
+        self.clear_colors.get(demo).copied().unwrap_or(default)
+    }
+
+    // Errors start with the key so the message points at the line to fix
+    pub fn set( &mut self, key: &str, value: &Toml ) -> Result<(), String> {
+        match key {
+            "window.width" => self.window.width = positive(key, value)?,
+            "window.height" => self.window.height = positive(key, value)?,
+            "window.title" => self.window.title = string(key, value)?.to_string(),
+            "window.vsync" => self.window.vsync = boolean(key, value)?,
+            "window.samples" => {
+                let samples = unsigned(key, value)?;
+                if samples > 16 {
+                    return Err(format!("{}: {} is more than the 16 samples any driver offers", key, samples));
+                }
+                self.window.samples = samples;
+            },
+            "window.fullscreen" => self.window.fullscreen = boolean(key, value)?,
+            "window.monitor" => self.window.monitor = unsigned(key, value)? as usize,
+            "gl.version" => self.window.gl_version = gl_version(key, value)?,
+            "gl.profile" => {
+                self.window.gl_profile = match string(key, value)? {
+                    "core" => GlProfile::Core,
+                    "compat" => GlProfile::Compat,
+                    other => return Err(format!("{}: expected \"core\" or \"compat\", got \"{}\"", key, other)),
+                };
+            },
+            "gl.debug" => self.window.gl_debug = boolean(key, value)?,
+            "gl.debug_severity" => {
+                self.window.gl_debug_severity = Severity::parse(string(key, value)?).map_err( | err | format!("{}: {}", key, err) )?;
+            },
+            "gl.verify_state" => self.window.gl_verify_state = boolean(key, value)?,
+            "assets.root" => self.asset_root = PathBuf::from(string(key, value)?),
+            "gamepad.stick_deadzone" => self.deadzone.stick = fraction(key, value)?,
+            "gamepad.trigger_deadzone" => self.deadzone.trigger = fraction(key, value)?,
+            "timing.update_rate" => self.timing.update_rate = rate(key, value)?,
+            "timing.stats_in_title" => self.timing.stats_in_title = boolean(key, value)?,
+            "log.level" => self.log.level = level(key, value)?,
+            "log.console" => self.log.console = boolean(key, value)?,
+            "log.json_file" => self.log.json_file = Some(PathBuf::from(string(key, value)?)),
+            _ => {
+                if let Some(shape) = key.strip_prefix("colors.") {
+                    if !SHAPES.contains(&shape) {
+                        return Err(format!("{}: unknown shape, expected one of: {}", key, SHAPES.join(", ")));
+                    }
+                    self.colors.insert(shape.to_string(), color(key, value)?);
+                } else if let Some(action) = key.strip_prefix("input.") {
+                    self.actions.rebind(action, bindings(key, value)?).map_err( | err | format!("{}: {}", key, err) )?;
+                } else if let Some(target) = key.strip_prefix("log.targets.") {
+                    if !TARGETS.contains(&target) {
+                        return Err(format!("{}: unknown target, expected one of: {}", key, TARGETS.join(", ")));
+                    }
+                    self.log.targets.insert(target.to_string(), level(key, value)?);
+                } else if let Some(demo) = key.strip_prefix("clear.") {
+                    if !DEMOS.iter().any( | entry | entry.name == demo ) {
+                        let names = DEMOS.iter().map( | entry | entry.name ).collect::<Vec<&str>>();
+                        return Err(format!("{}: unknown demo, expected one of: {}", key, names.join(", ")));
+                    }
+                    self.clear_colors.insert(demo.to_string(), color(key, value)?);
+                } else {
+                    return Err(format!("{}: unknown key", key));
+                }
+            },
+        }
+        Ok(())
+    }
+
+    pub fn load_file( &mut self, path: &Path ) -> Result<(), String> {
+        let source = fs::read_to_string(path).map_err( | err | format!("{}: {}", path.display(), err) )?;
+        let entries = Toml::parse(&source).map_err( | err | format!("{}: {}", path.display(), err) )?;
+        for entry in entries {
+            self.set(&entry.key, &entry.value).map_err( | err | format!("{}:{}: {}", path.display(), entry.line, err) )?;
+        }
+        Ok(())
+    }
+
+    // Flags from FLAGS / SWITCHES, PROGRAM_FLAGS are skipped, anything else is an error
+    // args[0] is the program
+    pub fn apply_args( &mut self, args: &[String] ) -> Result<(), String> {
+        let mut rest = args.iter().skip(1);
+        while let Some(arg) = rest.next() {
+            if let Some(( flag, key, value )) = SWITCHES.iter().find( | ( flag, _, _ ) | flag == arg ) {
+                self.set(key, &Toml::Bool(*value)).map_err( | err | format!("{}: {}", flag, err) )?;
+                continue;
+            }
+            if let Some(( _, takes_value )) = PROGRAM_FLAGS.iter().find( | ( flag, _ ) | flag == arg ) {
+                let value = if *takes_value { rest.next() } else { None };
+                // main reads it back itself, a typo must not quietly become the default
+                if let ( "--frames", Some(text) ) = ( arg.as_str(), value ) {
+                    text.parse::<u32>().ok().filter( | count | *count > 0 )
+                        .ok_or_else( || format!("{}: expected a positive integer, got \"{}\"", arg, text) )?;
+                }
+                continue;
+            }
+            if HEADLESS_FLAGS.contains(&arg.as_str()) {
+                if cfg!(all(feature = "headless", target_os = "linux")) {
+                    continue;
+                }
+                return Err(format!("{} requires the headless feature", arg));
+            }
+            let Some(( flag, key )) = FLAGS.iter().find( | ( flag, _ ) | flag == arg ) else {
+                return Err(format!("unknown flag '{}', expected one of: {}", arg, all_flags().join(", ")));
+            };
+            let text = rest.next().ok_or_else( || format!("{}: expected a value for {}", flag, key) )?;
+            // Bare words are strings, so `--title Hello` works without quotes
+            let value = Toml::parse_value(text).unwrap_or_else( | _ | Toml::String(text.clone()) );
+            let value = match ( key, value ) {
+                // `--gl-version 4.1` parses as a float
+                ( &"gl.version", Toml::Float(_) ) => Toml::String(text.clone()),
+                ( &"window.title" | &"assets.root" | &"log.json_file", value ) if value.as_str().is_none() => Toml::String(text.clone()),
+                ( _, value ) => value,
+            };
+            self.set(key, &value).map_err( | err | format!("{}: {}", flag, err) )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args( list: &[&str] ) -> Vec<String> {
+        std::iter::once("openglyt").chain(list.iter().copied()).map(String::from).collect()
+    }
+
+    #[test]
+    fn flags_override_the_defaults() {
+        let mut config = Config::new(WindowConfig::new(800, 600, "demo"));
+        config.apply_args(&args(&[ "--demo", "solids", "--width", "1280", "--no-vsync", "--title", "Hello", "--record", "out" ])).unwrap();
+        assert_eq!(( config.window.width, config.window.height ), ( 1280, 600 ));
+        assert!(!config.window.vsync);
+        assert_eq!(config.window.title, "Hello");
+    }
+
+    #[test]
+    fn unknown_flags_list_the_valid_ones() {
+        let mut config = Config::new(WindowConfig::new(800, 600, "demo"));
+        let err = config.apply_args(&args(&[ "--widht", "1280" ])).unwrap_err();
+        assert!(err.starts_with("unknown flag '--widht'"), "{}", err);
+        assert!(err.contains("--width") && err.contains("--quiet") && err.contains("--config"), "{}", err);
+        // A stray value is just as wrong
+        assert!(config.apply_args(&args(&[ "1280" ])).is_err());
+        assert!(config.apply_args(&args(&[ "--width" ])).is_err());
+    }
+
+    #[test]
+    fn program_flags_are_checked_too() {
+        let mut config = Config::new(WindowConfig::new(800, 600, "demo"));
+        config.apply_args(&args(&[ "--record", "out", "--frames", "90" ])).unwrap();
+        let err = config.apply_args(&args(&[ "--record", "out", "--frames", "abc" ])).unwrap_err();
+        assert!(err.starts_with("--frames: expected a positive integer"), "{}", err);
+        assert!(config.apply_args(&args(&[ "--frames", "0" ])).is_err());
+
+        let golden = config.apply_args(&args(&[ "--golden-check" ]));
+        if cfg!(all(feature = "headless", target_os = "linux")) {
+            assert_eq!(golden, Ok(()));
+        } else {
+            assert_eq!(golden, Err("--golden-check requires the headless feature".to_string()));
+        }
+    }
+
+    #[test]
+    fn unknown_actions_are_rejected() {
+        let mut config = Config::new(WindowConfig::new(800, 600, "demo"));
+        config.set("input.move_forward", &Toml::Array(vec![ Toml::String("I".to_string()) ])).unwrap();
+        let err = config.set("input.move_froward", &Toml::Array(Vec::new())).unwrap_err();
+        assert!(err.starts_with("input.move_froward: unknown action, expected one of: "), "{}", err);
+        assert!(err.contains("move_forward"), "{}", err);
+    }
+
+    #[test]
+    fn every_flag_is_documented_in_the_example_config() {
+        let example = include_str!("../../openglyt.toml");
+        let header = example.lines().take_while( | line | line.starts_with('#') ).collect::<Vec<&str>>().join("\n");
+        for flag in all_flags() {
+            assert!(header.contains(flag), "{} is missing from the openglyt.toml header", flag);
+        }
+    }
+}
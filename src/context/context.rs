@@ -0,0 +1,100 @@
+// GL Context -> where frames go, a glfw window or an offscreen framebuffer
+use glfw::{fail_on_errors, Context, Glfw, GlfwReceiver, PWindow, WindowEvent};
+
+use crate::{
+    config::config::{GlProfile, WindowConfig},
+    logging::logging::{GL, WINDOW},
+    renderer::{gl_debug, gl_state}
+};
+
+// Scene code only talks to this, so it runs the same with or without a window
+pub trait GlContext {
+    // Size of what we draw into, in pixels
+    fn framebuffer_size(&self) -> (i32, i32);
+
+    // HiDPI factor of the monitor, 1.0 when pixels and screen coordinates match
+    fn content_scale(&self) -> (f32, f32) {
+        ( 1.0, 1.0 )
+    }
+
+    // Makes the frame target current and sets the viewport to cover it
+    fn bind_target(&self);
+
+    // Window -> swap buffers, offscreen -> wait until the GPU is done
+    fn present( &mut self );
+}
+
+pub struct WindowContext {
+    pub glfw: Glfw,
+    pub window: PWindow,
+    pub events: GlfwReceiver<(f64, WindowEvent)>,
+}
+
+impl WindowContext {
+    // Opens the window described by `config` and loads the GL functions
+    pub fn new( config: &WindowConfig ) -> Result<Self, String> {
+        let mut glfw = glfw::init(fail_on_errors!()).map_err( | err | format!("GLFW - Iniialization Error -> {:?}", err) )?;
+        let ( major, minor ) = config.gl_version;
+        glfw.window_hint(glfw::WindowHint::ContextVersion(major, minor));
+        match config.gl_profile {
+            GlProfile::Core => {
+                glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Core));
+                glfw.window_hint(glfw::WindowHint::OpenGlForwardCompat(true));
+            },
+            GlProfile::Compat => glfw.window_hint(glfw::WindowHint::OpenGlProfile(glfw::OpenGlProfileHint::Compat)),
+        }
+        if config.samples > 0 {
+            glfw.window_hint(glfw::WindowHint::Samples(Some(config.samples)));
+        }
+        // Some drivers only send debug messages to debug contexts
+        glfw.window_hint(glfw::WindowHint::OpenGlDebugContext(config.gl_debug));
+
+        let ( mut window, events ) = if config.fullscreen {
+            glfw.with_connected_monitors( | glfw, monitors | {
+                let monitor = monitors.get(config.monitor)
+                    .ok_or_else( || format!("window.monitor: there is no monitor {}, {} connected", config.monitor, monitors.len()) )?;
+                glfw.create_window(config.width, config.height, &config.title, glfw::WindowMode::FullScreen(monitor))
+                    .ok_or_else( || "Creating Window Error!".to_string() )
+            })?
+        } else {
+            glfw.create_window(config.width, config.height, &config.title, glfw::WindowMode::Windowed)
+                .ok_or_else( || "Creating Window Error!".to_string() )?
+        };
+
+        window.make_current();
+        window.set_key_polling(true);
+        glfw.set_swap_interval(if config.vsync { glfw::SwapInterval::Sync(1) } else { glfw::SwapInterval::None });
+        gl::load_with( | ptr | window.get_proc_address(ptr) );
+        // Nothing cached so far belongs to this context
+        gl_state::invalidate();
+        let debug = gl_debug::setup(config.gl_debug, config.gl_debug_severity);
+        let mode = if config.fullscreen { format!("fullscreen on monitor {}", config.monitor) } else { "windowed".to_string() };
+        log::info!(target: WINDOW, "{}x{} \"{}\", {}, vsync {}, {} samples", config.width, config.height, config.title, mode, config.vsync, config.samples);
+        log::info!(target: GL, "{}, debug output {}", gl_debug::context_info(), if debug { "on" } else { "off" });
+        if config.samples > 0 {
+            unsafe { gl::Enable(gl::MULTISAMPLE) };
+        }
+
+        Ok(Self { glfw, window, events })
+    }
+}
+
+impl GlContext for WindowContext {
+    fn framebuffer_size(&self) -> (i32, i32) {
+        self.window.get_framebuffer_size()
+    }
+
+    fn content_scale(&self) -> (f32, f32) {
+        self.window.get_content_scale()
+    }
+
+    fn bind_target(&self) {
+        let ( width, height ) = self.framebuffer_size();
+        gl_state::bind_framebuffer(gl::FRAMEBUFFER, 0);
+        gl_state::viewport(0, 0, width, height);
+    }
+
+    fn present( &mut self ) {
+        self.window.swap_buffers();
+    }
+}
@@ -0,0 +1,102 @@
+// Headless Context -> GL 3.3 core without a window, for CI machines without a GPU
+// EGL is loaded at runtime, Mesa's llvmpipe gives us a software renderer
+use std::ptr::null;
+
+use khronos_egl as egl;
+
+use crate::{
+    context::context::GlContext,
+    logging::logging::GL,
+    renderer::{framebuffer::{ColorFormat, DepthFormat, Framebuffer, FramebufferBuilder}, gl_debug::{self, Severity}, gl_state}
+};
+
+// EGL_MESA_platform_surfaceless -> a display that needs no X11 / Wayland / GPU device
+const PLATFORM_SURFACELESS_MESA: egl::Enum = 0x31DD;
+
+pub struct HeadlessContext {
+    pub target: Framebuffer,
+    egl: egl::DynamicInstance<egl::EGL1_5>,
+    display: egl::Display,
+    context: egl::Context,
+}
+
+impl HeadlessContext {
+    // Creates the context, makes it current and renders into an RGBA8 + depth/stencil framebuffer
+    pub fn new( width: i32, height: i32 ) -> Result<Self, String> {
+        let egl = unsafe { egl::DynamicInstance::<egl::EGL1_5>::load_required() }
+            .map_err( | err | format!("Loading libEGL failed -> {}", err) )?;
+
+        let display = unsafe { egl.get_platform_display(PLATFORM_SURFACELESS_MESA, egl::DEFAULT_DISPLAY, &[ egl::ATTRIB_NONE ]) }
+            .ok()
+            .or_else( || unsafe { egl.get_display(egl::DEFAULT_DISPLAY) } )
+            .ok_or_else( || "No EGL display available".to_string() )?;
+        egl.initialize(display).map_err( | err | format!("eglInitialize failed -> {}", err) )?;
+        egl.bind_api(egl::OPENGL_API).map_err( | err | format!("Desktop OpenGL isn't supported by EGL -> {}", err) )?;
+
+        let config_attributes = [
+            egl::SURFACE_TYPE, egl::PBUFFER_BIT,
+            egl::RENDERABLE_TYPE, egl::OPENGL_BIT,
+            egl::RED_SIZE, 8,
+            egl::GREEN_SIZE, 8,
+            egl::BLUE_SIZE, 8,
+            egl::ALPHA_SIZE, 8,
+            egl::NONE,
+        ];
+        let config = egl.choose_first_config(display, &config_attributes)
+            .map_err( | err | format!("eglChooseConfig failed -> {}", err) )?
+            .ok_or_else( || "No EGL config with RGBA8 and desktop OpenGL".to_string() )?;
+
+        let context_attributes = [
+            egl::CONTEXT_MAJOR_VERSION, 3,
+            egl::CONTEXT_MINOR_VERSION, 3,
+            egl::CONTEXT_OPENGL_PROFILE_MASK, egl::CONTEXT_OPENGL_CORE_PROFILE_BIT,
+            egl::CONTEXT_OPENGL_DEBUG, cfg!(debug_assertions) as egl::Int,
+            egl::NONE,
+        ];
+        let context = egl.create_context(display, config, None, &context_attributes)
+            .map_err( | err | format!("Creating a GL 3.3 core context failed -> {}", err) )?;
+        // No surface at all ( EGL_KHR_surfaceless_context ), everything goes through our framebuffer
+        egl.make_current(display, None, None, Some(context))
+            .map_err( | err | format!("eglMakeCurrent failed -> {}", err) )?;
+
+        gl::load_with( | name | egl.get_proc_address(name).map_or(null(), | function | function as *const _) );
+        // A fresh context -> nothing we remember is true anymore
+        gl_state::invalidate();
+        let debug = gl_debug::setup(cfg!(debug_assertions), Severity::Low);
+        log::debug!(target: GL, "headless {}, debug output {}", gl_debug::context_info(), if debug { "on" } else { "off" });
+
+        let target = FramebufferBuilder::new(width, height)
+            .color(ColorFormat::Rgba8)
+            .depth(DepthFormat::Depth24Stencil8)
+            .build()?;
+
+        Ok(Self { target, egl, display, context })
+    }
+
+    pub fn resize( &mut self, width: i32, height: i32 ) -> Result<(), String> {
+        self.target.resize(width, height)
+    }
+}
+
+impl GlContext for HeadlessContext {
+    fn framebuffer_size(&self) -> (i32, i32) {
+        ( self.target.width, self.target.height )
+    }
+
+    fn bind_target(&self) {
+        self.target.bind();
+    }
+
+    fn present( &mut self ) {
+        unsafe { gl::Finish() };
+    }
+}
+
+impl Drop for HeadlessContext {
+    fn drop( &mut self ) {
+        self.target.delete();
+        let _ = self.egl.make_current(self.display, None, None, None);
+        let _ = self.egl.destroy_context(self.display, self.context);
+        let _ = self.egl.terminate(self.display);
+    }
+}
@@ -0,0 +1,1121 @@
+// Demos -> every example of the project as an App, they run with or without a window
+use std::{fs, path::Path, rc::Rc};
+
+use glfw::MouseButton;
+use glm::{vec2, vec3, Mat4, Vec2, Vec3};
+
+use crate::{
+    app::{app::{App, Flow, ResizeTargets, Surface}, runner::AppFactory},
+    camera::{camera::{AspectMode, OrthoCamera}, frustum::Culler},
+    config::config::{Config, WindowConfig},
+    context::context::GlContext,
+    input::input::Input,
+    lib::{load_gltf::{load_gltf, AlphaMode}, load_image::load_image_from_memory_sized, load_obj::load_obj},
+    logging::logging::{APP, ASSETS, TEXTURE},
+    materials::material::Material,
+    mesh::{instancing::{Instance, InstanceBuffer, InstanceLayout}, layout::{VertexAttribute, VertexLayout}, mesh::{Mesh, MeshData}},
+    other_shapes::{
+        parallelogram::parallelogram,
+        polygon::{circle, regular_polygon, ring, star},
+        rhombus::rhombus,
+        solids::{capsule, cone, cube, cylinder, icosphere, plane, torus, uv_sphere}
+    },
+    renderer::{
+        framebuffer::{blit, ColorFormat, DepthFormat, Framebuffer, FramebufferBuilder},
+        gl_state::{self, UniformValue},
+        renderer::Renderer,
+        sprite_batch::{Sprite, SpriteBatch}
+    },
+    scene::{scene::{NodeId, Scene}, transform::{identity, Quat, Transform}},
+    shaders::shader::Shader,
+    textures::texture::Texture,
+    utils::bounds::{Aabb, BoundingSphere}
+};
+
+// Positions at location 0 and texture coordinates at 2, what the demo shaders read
+fn shape_mesh( name: &str, data: &MeshData ) -> Mesh {
+    let layout = VertexLayout::new()
+        .with(VertexAttribute::Position, 0)
+        .with(VertexAttribute::Uv, 2);
+    let mesh = Mesh::new(data, &layout);
+    mesh.label(name);
+    mesh
+}
+
+// State between two fixed updates, `alpha` 0 -> previous, 1 -> current
+fn lerp( previous: f64, current: f64, alpha: f32 ) -> f64 {
+    previous + (current - previous) * alpha as f64
+}
+
+// Cursor positions are in screen coordinates, camera math wants framebuffer pixels
+fn cursor_to_world( camera: &OrthoCamera, cursor: ( f64, f64 ), scale: ( f32, f32 ) ) -> Vec2 {
+    camera.screen_to_world(cursor.0 as f32 * scale.0, cursor.1 as f32 * scale.1)
+}
+
+// From the asset root when the file is there, otherwise the copy built into the binary
+fn asset_texture( config: &Config, name: &str, built_in: &[u8] ) -> Texture {
+    let path = config.asset_root.join(name);
+    if let Ok(bytes) = fs::read(&path) {
+        match Texture::from_memory(&bytes) {
+            Ok(texture) => {
+                texture.label(&path.display().to_string());
+                return texture;
+            },
+            Err(err) => log::warn!(target: TEXTURE, "{}: {}, using the built-in copy", path.display(), err),
+        }
+    }
+    match Texture::from_memory(built_in) {
+        Ok(texture) => {
+            texture.label(&format!("built-in {}", name));
+            texture
+        },
+        Err(err) => {
+            log::error!(target: TEXTURE, "built-in {}: {}, using a placeholder", name, err);
+            let texture = Texture::from_pixels(PLACEHOLDER_SIZE as i32, PLACEHOLDER_SIZE as i32, placeholder_pixels(PLACEHOLDER_SIZE));
+            texture.label(&format!("placeholder {}", name));
+            texture
+        },
+    }
+}
+
+// One layer of a texture array, same fallback as asset_texture()
+fn asset_layer( config: &Config, name: &str, built_in: &[u8], size: u32 ) -> Vec<u8> {
+    let path = config.asset_root.join(name);
+    if let Ok(bytes) = fs::read(&path) {
+        match load_image_from_memory_sized(&bytes, size, size) {
+            Ok(pixels) => return pixels,
+            Err(err) => log::warn!(target: TEXTURE, "{}: {}, using the built-in copy", path.display(), err),
+        }
+    }
+    load_image_from_memory_sized(built_in, size, size).unwrap_or_else( | err | {
+        log::error!(target: TEXTURE, "built-in {}: {}, using a placeholder", name, err);
+        placeholder_pixels(size)
+    })
+}
+
+const PLACEHOLDER_SIZE: u32 = 8;
+
+// Magenta / black checker, RGBA -> a broken asset is obvious on screen instead of a crash
+fn placeholder_pixels( size: u32 ) -> Vec<u8> {
+    let cell = ( size / 4 ).max(1);
+    ( 0..size * size ).flat_map( | i | {
+        let ( x, y ) = ( i % size / cell, i / size / cell );
+        if ( x + y ) % 2 == 0 { [ 255, 0, 255, 255 ] } else { [ 0, 0, 0, 255 ] }
+    }).collect()
+}
+
+// Shapes with a flat fill color, `[colors]` in the config
+pub const SHAPES: &[&str] = &[ "triangle", "rectangle" ];
+
+// What the old clip space showed -> 2 x 2 units around the origin, a wider or taller window shows more instead of stretching
+fn clip_space_camera() -> OrthoCamera {
+    OrthoCamera::new(2.0, 2.0, AspectMode::Expand)
+}
+
+// transform_v.vert + color_f.frag, one flat color per material
+fn flat_color_shader( label: &str ) -> Rc<Shader> {
+    let shader = Shader::new(include_str!("../glsl/transform_v.vert"), include_str!("../glsl/color_f.frag"));
+    shader.label(label);
+    Rc::new(shader)
+}
+
+fn flat_color_material( shader: &Rc<Shader>, color: [f32; 4], model: Mat4 ) -> Material {
+    let mut material = Material::new(shader.clone());
+    material.set_vec4("color", color);
+    material.set_mat4("model", model);
+    material
+}
+
+// Was other_shapes/traingle.rs
+pub struct TriangleDemo {
+    camera: OrthoCamera,
+    material: Material,
+    mesh: Mesh,
+    clear_color: [f32; 4],
+}
+
+impl App for TriangleDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let shader = flat_color_shader("triangle");
+        Self {
+            camera: clip_space_camera(),
+            material: flat_color_material(&shader, config.color("triangle", [ 1.0, 0.5, 0.2, 1.0 ]), identity()),
+            mesh: shape_mesh("triangle", &regular_polygon(3, 0.6)),
+            clear_color: config.clear_color("triangle", [ 0.2, 0.3, 0.3, 1.0 ]),
+        }
+    }
+
+    fn render( &mut self, _alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        self.material.set_mat4("view_projection", self.camera.view_projection());
+        self.material.bind();
+        self.mesh.draw();
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+}
+
+// Was other_shapes/rectangle.rs
+pub struct RectangleDemo {
+    camera: OrthoCamera,
+    material: Material,
+    mesh: Mesh,
+    clear_color: [f32; 4],
+}
+
+impl App for RectangleDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let shader = flat_color_shader("rectangle");
+        Self {
+            camera: clip_space_camera(),
+            material: flat_color_material(&shader, config.color("rectangle", [ 0.4, 0.3, 0.1, 1.0 ]), identity()),
+            // No skew -> a rectangle
+            mesh: shape_mesh("rectangle", &parallelogram(1.0, 1.0, 0.0)),
+            clear_color: config.clear_color("rectangle", [ 0.0, 0.1, 0.2, 1.0 ]),
+        }
+    }
+
+    fn render( &mut self, _alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        self.material.set_mat4("view_projection", self.camera.view_projection());
+        self.material.bind();
+        self.mesh.draw();
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+}
+
+// Was main.rs -> wall texture on a quad that pulses with `scale`
+pub struct TexturedQuadDemo {
+    camera: OrthoCamera,
+    material: Material,
+    mesh: Mesh,
+    // Before and after the last update, render blends the two
+    previous_time: f64,
+    time: f64,
+    clear_color: [f32; 4],
+}
+
+impl App for TexturedQuadDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let shader = Shader::new(include_str!("../glsl/texture_v.vert"), include_str!("../glsl/texture_f.frag"));
+        shader.label("textured-quad");
+        let shader = Rc::new(shader);
+        let texture = asset_texture(config, "wall.jpg", include_bytes!("../assets/wall.jpg"));
+        let mut material = Material::new(shader);
+        material.set_texture("tex0", Rc::new(texture));
+
+        Self {
+            camera: clip_space_camera(),
+            material,
+            mesh: shape_mesh("textured-quad", &parallelogram(1.0, 1.0, 0.0)),
+            previous_time: 0.0,
+            time: 0.0,
+            clear_color: config.clear_color("textured-quad", [ 0.21, 0.13, 0.02, 1.0 ]),
+        }
+    }
+
+    fn update( &mut self, dt: f64, _input: &mut Input ) -> Flow {
+        self.previous_time = self.time;
+        self.time += dt;
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        let time = lerp(self.previous_time, self.time, alpha);
+        let scale = ((time.cos() / 2.0) + 0.5) as f32;
+        self.material.set_float("scale", scale);
+        self.material.set_mat4("view_projection", self.camera.view_projection());
+        self.material.bind();
+        self.mesh.draw();
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+}
+
+// Was other_shapes/bothsidebyside.rs -> rectangle, rhombus and parallelogram next to each other
+// The move_* actions ( WASD / arrows / d-pad / left stick ) pan the camera, the mouse wheel zooms
+// The shapes are children of one "row" node, the rhombus spins in place.
+// "attach" ( Space / pad A ) moves the parallelogram under the rhombus, it swings around with it until pressed again
+pub struct SideBySideDemo {
+    // The 4:3 window the demo was made for, letterboxed when the window has another shape
+    camera: OrthoCamera,
+    previous_position: Vec2,
+    renderer: Renderer,
+    scene: Scene,
+    row: NodeId,
+    rhombus: NodeId,
+    parallelogram: NodeId,
+    attached: bool,
+    // Radians before and after the last update
+    previous_spin: f32,
+    spin: f32,
+    // Wall and container, the shapes alternate between the two
+    materials: [Rc<Material>; 2],
+    // Indexed by the scene's mesh nodes
+    meshes: Vec<Rc<Mesh>>,
+    clear_color: [f32; 4],
+    // Shapes panned off-screen last frame, logged when it changes
+    culled: u32,
+}
+
+impl App for SideBySideDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let meshes = vec![
+            Rc::new(shape_mesh("side-by-side rectangle", &parallelogram(0.5, 0.5, 0.0))),
+            Rc::new(shape_mesh("side-by-side rhombus", &rhombus(0.7, 0.6))),
+            Rc::new(shape_mesh("side-by-side parallelogram", &parallelogram(0.3, 0.2, 0.2))),
+        ];
+
+        let wall = asset_texture(config, "wall.jpg", include_bytes!("../assets/wall.jpg"));
+        let container = asset_texture(config, "texture.jpg", include_bytes!("../assets/texture.jpg"));
+
+        let shader = Shader::new(include_str!("../glsl/transform_v.vert"), include_str!("../glsl/texture_f.frag"));
+        shader.label("side-by-side");
+        let shader = Rc::new(shader);
+        let materials = [ wall, container ].map( | texture | {
+            let mut material = Material::new(shader.clone());
+            material.set_texture("tex0", Rc::new(texture));
+            Rc::new(material)
+        });
+        let mut scene = Scene::new();
+        let row = scene.add_node("row", Transform::identity(), None);
+        for ( i, name ) in [ "rectangle", "rhombus", "parallelogram" ].iter().enumerate() {
+            let local = Transform::from_translation(vec3(-0.7 + i as f32 * 0.7, 0.0, 0.0));
+            scene.add_mesh_node(name, i, local, Some(row));
+        }
+        let rhombus = scene.find("rhombus").expect("added above");
+        let parallelogram = scene.find("parallelogram").expect("added above");
+        let camera = OrthoCamera::new(8.0 / 3.0, 2.0, AspectMode::Letterbox);
+        Self {
+            previous_position: camera.position,
+            camera,
+            renderer: Renderer::new(),
+            scene,
+            row,
+            rhombus,
+            parallelogram,
+            attached: false,
+            previous_spin: 0.0,
+            spin: 0.0,
+            materials,
+            meshes,
+            clear_color: config.clear_color("side-by-side", [ 0.1, 0.1, 0.1, 1.0 ]),
+            culled: 0,
+        }
+    }
+
+    fn update( &mut self, dt: f64, input: &mut Input ) -> Flow {
+        // Half the view height per second
+        let step = dt as f32 / self.camera.zoom;
+        self.previous_position = self.camera.position;
+        let axis = | negative: &str, positive: &str | input.action_value(positive) - input.action_value(negative);
+        self.camera.pan(axis("move_left", "move_right") * step, axis("move_back", "move_forward") * step);
+        self.previous_spin = self.spin;
+        self.spin += dt as f32 * 0.5;
+        let ( _, wheel ) = input.scroll();
+        if wheel != 0.0 {
+            self.camera.zoom_by(1.1_f32.powf(wheel as f32));
+        }
+        // The local transform stays -> attached, its offset from the row becomes an offset from the spinning rhombus
+        if input.action_pressed("attach") {
+            let parent = if self.attached { self.row } else { self.rhombus };
+            self.scene.reparent(self.parallelogram, Some(parent)).expect("neither parent is below the parallelogram");
+            self.attached = !self.attached;
+        }
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        // Draw at the blended position, update() keeps the real one
+        let current = self.camera.position;
+        self.camera.position = self.previous_position + (current - self.previous_position) * alpha;
+        self.renderer.begin_frame(self.camera.view_projection());
+        self.camera.position = current;
+        let spin = Quat::from_rotation_z(self.previous_spin).slerp(Quat::from_rotation_z(self.spin), alpha);
+        self.scene.update_local(self.rhombus, | local | local.rotation = spin );
+        for ( _, world, mesh ) in self.scene.visible_meshes() {
+            self.renderer.submit(self.meshes[mesh].clone(), self.materials[mesh % 2].clone(), world);
+        }
+        // Sorted by texture -> both wall shapes are drawn before the container one
+        let stats = self.renderer.end_frame();
+        if stats.culled != self.culled {
+            log::debug!(target: APP, "side-by-side: {} of {} shapes culled", stats.culled, self.renderer.culler.stats.total());
+            self.culled = stats.culled;
+        }
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+}
+
+// Was lib/my_code.rs -> a parallelogram and a rectangle, both with animated uniform colors
+pub struct TwoShapesDemo {
+    camera: OrthoCamera,
+    parallelogram_material: Material,
+    rectangle_material: Material,
+    parallelogram: Mesh,
+    rectangle: Mesh,
+    previous_time: f64,
+    time: f64,
+    clear_color: [f32; 4],
+}
+
+impl App for TwoShapesDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let shader = flat_color_shader("two-shapes");
+        // Where the hand-written corners used to be -> ( -0.6, -0.4 ) .. ( 0.3, 0.4 ) and ( 0.5, -0.5 ) .. ( 1.5, 0.5 )
+        let parallelogram_model = Transform::from_translation(vec3(-0.15, 0.0, 0.0)).matrix();
+        let rectangle_model = Transform::from_translation(vec3(1.0, 0.0, 0.0)).matrix();
+        Self {
+            // Wide enough for the rectangle that used to run off the right edge
+            camera: OrthoCamera::new(3.2, 2.0, AspectMode::Expand),
+            parallelogram_material: flat_color_material(&shader, [ 0.7, 0.0, 0.5, 1.0 ], parallelogram_model),
+            rectangle_material: flat_color_material(&shader, [ 0.5, 0.2, 0.2, 1.0 ], rectangle_model),
+            parallelogram: shape_mesh("two-shapes parallelogram", &parallelogram(0.6, 0.8, 0.3)),
+            rectangle: shape_mesh("two-shapes rectangle", &parallelogram(1.0, 1.0, 0.0)),
+            previous_time: 0.0,
+            time: 0.0,
+            clear_color: config.clear_color("two-shapes", [ 0.2, 0.2, 0.2, 1.0 ]),
+        }
+    }
+
+    fn update( &mut self, dt: f64, _input: &mut Input ) -> Flow {
+        self.previous_time = self.time;
+        self.time += dt;
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        let time = lerp(self.previous_time, self.time, alpha);
+        let view_projection = self.camera.view_projection();
+
+        let green = time.sin() as f32;
+        self.parallelogram_material.set_vec4("color", [ 0.7, green, 0.5, 1.0 ]);
+        self.parallelogram_material.set_mat4("view_projection", view_projection);
+        self.parallelogram_material.bind();
+        self.parallelogram.draw();
+
+        let red = ((time.sin() / 2.0) + 0.5) as f32;
+        self.rectangle_material.set_vec4("color", [ red, 0.2, 0.2, 1.0 ]);
+        self.rectangle_material.set_mat4("view_projection", view_projection);
+        self.rectangle_material.bind();
+        self.rectangle.draw();
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+}
+
+// The side-by-side shapes plus a circle, ring and star as a field of spinning tiles, one draw call per shape however many tiles there are
+// Wall and container are two layers of one texture array, every tile picks a layer and a tint
+// Drag with the left mouse button to pan, the mouse wheel zooms
+pub struct InstancedDemo {
+    camera: OrthoCamera,
+    // Framebuffer pixels per screen coordinate, cursor positions come in screen coordinates
+    scale: ( f32, f32 ),
+    material: Material,
+    meshes: Vec<Mesh>,
+    bounds: Vec<BoundingSphere>,
+    buffer: InstanceBuffer,
+    culler: Culler,
+    // Tiles of each mesh that are on screen, rebuilt every frame
+    visible: Vec<Vec<Instance>>,
+    previous_time: f64,
+    time: f64,
+    clear_color: [f32; 4],
+}
+
+impl InstancedDemo {
+    const COLUMNS: usize = 24;
+    const ROWS: usize = 16;
+    const SPACING: f32 = 0.75;
+    const LAYER_SIZE: u32 = 256;
+    // Meshes the tiles cycle through
+    const SHAPES: usize = 6;
+
+    // Tile `index` of the grid at `time` seconds
+    fn instance( index: usize, time: f32 ) -> ( usize, Instance ) {
+        let ( column, row ) = ( index % Self::COLUMNS, index / Self::COLUMNS );
+        let x = (column as f32 - (Self::COLUMNS - 1) as f32 / 2.0) * Self::SPACING;
+        let y = (row as f32 - (Self::ROWS - 1) as f32 / 2.0) * Self::SPACING;
+        let speed = 0.3 + 0.15 * (index % 5) as f32;
+        let model = Transform::from_translation(vec3(x, y, 0.0)).matrix() * Quat::from_rotation_z(time * speed).to_mat4();
+        let shade = 0.6 + 0.4 * (row as f32 / Self::ROWS as f32);
+        let instance = Instance::new(model)
+            .with_tint([ shade, 1.0 - 0.3 * (column as f32 / Self::COLUMNS as f32), 1.0, 1.0 ])
+            .with_layer(((column + row) % 2) as u32);
+        ( (column + row) % Self::SHAPES, instance )
+    }
+}
+
+impl App for InstancedDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let shapes: [_; Self::SHAPES] = [
+            ( "instanced rectangle", parallelogram(0.5, 0.5, 0.0) ),
+            ( "instanced rhombus", rhombus(0.7, 0.6) ),
+            ( "instanced parallelogram", parallelogram(0.3, 0.2, 0.2) ),
+            ( "instanced circle", circle(0.3, 32) ),
+            ( "instanced ring", ring(0.16, 0.32, 32) ),
+            ( "instanced star", star(5, 0.14, 0.34) ),
+        ];
+        let bounds = shapes.iter().map( | ( _, data ) | data.bounding_sphere().expect("shapes have vertices") ).collect();
+        let meshes = shapes.iter().map( | ( name, data ) | shape_mesh(name, data) ).collect();
+
+        let layers = [
+            asset_layer(config, "wall.jpg", include_bytes!("../assets/wall.jpg"), Self::LAYER_SIZE),
+            asset_layer(config, "texture.jpg", include_bytes!("../assets/texture.jpg"), Self::LAYER_SIZE),
+        ];
+        let textures = Texture::array_from_pixels(Self::LAYER_SIZE as i32, Self::LAYER_SIZE as i32, &layers);
+        textures.label("instanced wall + container");
+
+        let shader = Shader::new(include_str!("../glsl/instanced_v.vert"), include_str!("../glsl/instanced_f.frag"));
+        shader.label("instanced");
+        let mut material = Material::new(Rc::new(shader));
+        material.set_texture("textures", Rc::new(textures));
+
+        let camera = OrthoCamera::new(8.0, 6.0, AspectMode::Expand);
+        let culler = Culler::new(&camera.view_projection());
+        Self {
+            camera,
+            scale: ( 1.0, 1.0 ),
+            material,
+            meshes,
+            bounds,
+            buffer: InstanceBuffer::new(InstanceLayout::standard()),
+            culler,
+            visible: vec![ Vec::new(); Self::SHAPES ],
+            previous_time: 0.0,
+            time: 0.0,
+            clear_color: config.clear_color("instanced", [ 0.1, 0.1, 0.12, 1.0 ]),
+        }
+    }
+
+    fn update( &mut self, dt: f64, input: &mut Input ) -> Flow {
+        self.previous_time = self.time;
+        self.time += dt;
+        if input.button_down(MouseButton::Button1) {
+            // The world point under the cursor stays under it
+            let ( ( x, y ), ( dx, dy ) ) = ( input.cursor(), input.cursor_delta() );
+            let before = cursor_to_world(&self.camera, ( x - dx, y - dy ), self.scale);
+            let after = cursor_to_world(&self.camera, ( x, y ), self.scale);
+            self.camera.pan(before.x - after.x, before.y - after.y);
+        }
+        let ( _, wheel ) = input.scroll();
+        if wheel != 0.0 {
+            self.camera.zoom_by(1.1_f32.powf(wheel as f32));
+        }
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        let time = lerp(self.previous_time, self.time, alpha) as f32;
+        let view_projection = self.camera.view_projection();
+
+        self.culler.begin_frame(&view_projection);
+        self.visible.iter_mut().for_each(Vec::clear);
+        for index in 0..Self::COLUMNS * Self::ROWS {
+            let ( mesh, instance ) = Self::instance(index, time);
+            if self.culler.test_sphere(&self.bounds[mesh], &instance.model) {
+                self.visible[mesh].push(instance);
+            }
+        }
+
+        self.material.set_mat4("view_projection", view_projection);
+        self.material.bind();
+        for ( mesh, instances ) in self.meshes.iter().zip(&self.visible) {
+            self.buffer.draw_instanced(mesh, instances);
+        }
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+
+    fn on_resize( &mut self, surface: Surface ) {
+        self.scale = surface.scale;
+    }
+}
+
+struct Bouncer {
+    previous: Vec2,
+    position: Vec2,
+    // Pixels per second
+    velocity: Vec2,
+    spin: f32,
+    size: f32,
+    // Index into SpritesDemo::textures
+    texture: usize,
+    // Quarter of the texture it shows
+    uv: [f32; 4],
+    color: [f32; 4],
+}
+
+impl Bouncer {
+    // Spread by the golden ratio so any count looks random but renders the same every run
+    fn new( index: usize, position: Vec2 ) -> Self {
+        let hash = | salt: f32 | ((index as f32 + 1.0) * 0.618_034 * salt).fract();
+        let angle = hash(7.0) * std::f32::consts::TAU;
+        let ( u, v ) = ( (index % 2) as f32 * 0.5, (index / 2 % 2) as f32 * 0.5 );
+        Self {
+            previous: position,
+            position,
+            velocity: vec2(angle.cos(), angle.sin()) * (60.0 + 120.0 * hash(3.0)),
+            spin: hash(5.0) * 2.0 - 1.0,
+            size: 16.0 + 24.0 * hash(11.0),
+            texture: index % 2,
+            uv: [ u, v, u + 0.5, v + 0.5 ],
+            color: [ 0.6 + 0.4 * hash(13.0), 0.6 + 0.4 * hash(17.0), 0.6 + 0.4 * hash(19.0), 0.85 ],
+        }
+    }
+}
+
+// Bouncing sprites in pixel space -> a bigger window gives them more room, they keep their size in pixels
+// Click to add one at the cursor
+pub struct SpritesDemo {
+    camera: OrthoCamera,
+    // Framebuffer pixels per screen coordinate
+    scale: ( f32, f32 ),
+    batch: SpriteBatch,
+    // Wall and container
+    textures: [Texture; 2],
+    sprites: Vec<Bouncer>,
+    previous_time: f64,
+    time: f64,
+    clear_color: [f32; 4],
+    draw_calls: u32,
+}
+
+impl SpritesDemo {
+    const SPRITES: usize = 120;
+    // Small on purpose -> the batch flushes a few times per texture
+    const CAPACITY: usize = 64;
+}
+
+impl App for SpritesDemo {
+    fn init( context: &dyn GlContext, config: &Config ) -> Self {
+        let ( width, height ) = context.framebuffer_size();
+        let shader = Shader::new(include_str!("../glsl/sprite_v.vert"), include_str!("../glsl/sprite_f.frag"));
+        shader.label("sprites");
+        let sprites = (0..Self::SPRITES).map( | i | {
+            let spot = vec2(((i as f32 + 0.5) * 0.381_966).fract(), ((i as f32 + 0.5) * 0.754_878).fract());
+            Bouncer::new(i, vec2(spot.x * width as f32, spot.y * height as f32))
+        }).collect();
+        Self {
+            camera: OrthoCamera::pixel_space(width, height),
+            scale: ( 1.0, 1.0 ),
+            batch: SpriteBatch::new(Rc::new(shader), Self::CAPACITY),
+            textures: [
+                asset_texture(config, "wall.jpg", include_bytes!("../assets/wall.jpg")),
+                asset_texture(config, "texture.jpg", include_bytes!("../assets/texture.jpg")),
+            ],
+            sprites,
+            previous_time: 0.0,
+            time: 0.0,
+            clear_color: config.clear_color("sprites", [ 0.15, 0.1, 0.2, 1.0 ]),
+            draw_calls: 0,
+        }
+    }
+
+    fn update( &mut self, dt: f64, input: &mut Input ) -> Flow {
+        self.previous_time = self.time;
+        self.time += dt;
+        if input.button_pressed(MouseButton::Button1) {
+            let position = cursor_to_world(&self.camera, input.cursor(), self.scale);
+            self.sprites.push(Bouncer::new(self.sprites.len(), position));
+        }
+
+        let ( width, height ) = self.camera.visible_size();
+        for sprite in &mut self.sprites {
+            sprite.previous = sprite.position;
+            sprite.position = sprite.position + sprite.velocity * dt as f32;
+            let half = sprite.size / 2.0;
+            if sprite.position.x < half || sprite.position.x > width - half {
+                sprite.velocity.x = if sprite.position.x < half { sprite.velocity.x.abs() } else { -sprite.velocity.x.abs() };
+            }
+            if sprite.position.y < half || sprite.position.y > height - half {
+                sprite.velocity.y = if sprite.position.y < half { sprite.velocity.y.abs() } else { -sprite.velocity.y.abs() };
+            }
+        }
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        let time = lerp(self.previous_time, self.time, alpha) as f32;
+
+        self.batch.begin(self.camera.view_projection());
+        // One texture after the other, switching per sprite would flush every time
+        for ( index, texture ) in self.textures.iter().enumerate() {
+            for sprite in self.sprites.iter().filter( | sprite | sprite.texture == index ) {
+                let position = sprite.previous + (sprite.position - sprite.previous) * alpha;
+                let quad = Sprite::new(position, vec2(sprite.size, sprite.size))
+                    .with_rotation(sprite.spin * time)
+                    .with_uv(sprite.uv)
+                    .with_color(sprite.color);
+                self.batch.draw(texture, &quad);
+            }
+        }
+        let stats = self.batch.end();
+        if stats.draw_calls != self.draw_calls {
+            log::debug!(target: APP, "sprites: {} sprites in {} draw calls", stats.sprites, stats.draw_calls);
+            self.draw_calls = stats.draw_calls;
+        }
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+
+    fn on_resize( &mut self, surface: Surface ) {
+        self.scale = surface.scale;
+    }
+}
+
+// Every solid generator, lit and spinning -> depth test and back-face culling on, a wrong winding shows up as holes.
+// The solids render offscreen and leave a fading trail, last frame's copy is drawn behind them
+pub struct SolidsDemo {
+    camera: OrthoCamera,
+    renderer: Renderer,
+    solids: Vec<( Rc<Mesh>, Rc<Material>, Vec3 )>,
+    // ( scene, history ) -> color + depth for this frame, color only for the last one. Half floats -> fading
+    // 8 bit colors would get stuck a few steps above the background and never disappear.
+    // None when neither format builds, the solids then go straight to the window without a trail
+    targets: Option<( Framebuffer, Framebuffer )>,
+    trail_shader: Shader,
+    fullscreen: Mesh,
+    // The history is garbage until the first frame went into it
+    history_valid: bool,
+    previous_time: f64,
+    time: f64,
+    clear_color: [f32; 4],
+}
+
+impl App for SolidsDemo {
+    fn init( context: &dyn GlContext, config: &Config ) -> Self {
+        let shader = Shader::new(include_str!("../glsl/solid_v.vert"), include_str!("../glsl/solid_f.frag"));
+        shader.label("solids");
+        let shader = Rc::new(shader);
+        let shapes = [
+            ( "cube", cube(0.9, 2), [ 0.9, 0.4, 0.3, 1.0 ] ),
+            ( "uv sphere", uv_sphere(0.55, 24, 16), [ 0.3, 0.7, 0.9, 1.0 ] ),
+            ( "icosphere", icosphere(0.55, 2), [ 0.5, 0.8, 0.4, 1.0 ] ),
+            ( "cylinder", cylinder(0.45, 1.0, 24, 1), [ 0.9, 0.8, 0.3, 1.0 ] ),
+            ( "cone", cone(0.5, 1.0, 24, 1), [ 0.8, 0.5, 0.9, 1.0 ] ),
+            ( "torus", torus(0.45, 0.17, 32, 16), [ 0.9, 0.6, 0.2, 1.0 ] ),
+            ( "capsule", capsule(0.3, 0.5, 24, 8), [ 0.4, 0.9, 0.8, 1.0 ] ),
+            ( "plane", plane(1.0, 1.0, 4, 4), [ 0.8, 0.8, 0.8, 1.0 ] ),
+        ];
+        let solids = shapes.into_iter().enumerate().map( | ( i, ( name, data, color ) ) | {
+            let mesh = Mesh::new(&data, &VertexLayout::standard());
+            mesh.label(name);
+            let mut material = Material::new(shader.clone());
+            material.set_vec4("color", color);
+            // 4 x 2 grid around the origin
+            let position = vec3((i % 4) as f32 * 1.6 - 2.4, if i < 4 { 0.8 } else { -0.8 }, 0.0);
+            ( Rc::new(mesh), Rc::new(material), position )
+        }).collect();
+
+        let mut camera = OrthoCamera::new(7.0, 3.6, AspectMode::Expand);
+        // Deep enough for the solids to turn without hitting near / far
+        camera.near = -10.0;
+        camera.far = 10.0;
+
+        let ( width, height ) = context.framebuffer_size();
+        let targets = match ( trail_target("scene", width, height, Some(DepthFormat::Depth24)), trail_target("history", width, height, None) ) {
+            ( Some(scene), Some(history) ) => Some(( scene, history )),
+            ( scene, history ) => {
+                log::warn!(target: APP, "solids: no offscreen targets, rendering without the trail");
+                for mut framebuffer in scene.into_iter().chain(history) {
+                    framebuffer.delete();
+                }
+                None
+            },
+        };
+        let trail_shader = Shader::new(include_str!("../glsl/texture_v.vert"), include_str!("../glsl/trail_f.frag"));
+        trail_shader.label("solids trail");
+
+        Self {
+            camera,
+            renderer: Renderer::new(),
+            solids,
+            targets,
+            trail_shader,
+            // Covers clip space with uvs 0..1
+            fullscreen: shape_mesh("fullscreen", &parallelogram(2.0, 2.0, 0.0)),
+            history_valid: false,
+            previous_time: 0.0,
+            time: 0.0,
+            clear_color: config.clear_color("solids", [ 0.12, 0.12, 0.15, 1.0 ]),
+        }
+    }
+
+    fn update( &mut self, dt: f64, _input: &mut Input ) -> Flow {
+        self.previous_time = self.time;
+        self.time += dt;
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        let ( screen, _ ) = gl_state::bound_framebuffers();
+        if let Some(( scene, _ )) = &self.targets {
+            scene.bind();
+        }
+        self.camera.apply_viewport(self.clear_color);
+        if self.history_valid {
+            self.draw_trail();
+        }
+        let time = lerp(self.previous_time, self.time, alpha) as f32;
+        // Spin around y, then lean the top towards the camera so the caps show
+        let tilt = Quat::from_axis_angle(vec3(1.0, 0.0, 0.0), 0.5);
+        let spin = Quat::from_axis_angle(vec3(0.0, 1.0, 0.0), time * 0.8);
+
+        self.renderer.begin_frame(self.camera.view_projection());
+        for ( mesh, material, position ) in &self.solids {
+            let model = Transform { translation: *position, rotation: tilt * spin, scale: vec3(1.0, 1.0, 1.0) }.matrix();
+            self.renderer.submit(mesh.clone(), material.clone(), model);
+        }
+        gl_state::set_depth_test(true);
+        gl_state::depth_func(gl::LESS);
+        gl_state::set_cull_face(true);
+        gl_state::cull_face(gl::BACK);
+        self.renderer.end_frame();
+        // The 2D demos expect both off
+        gl_state::set_depth_test(false);
+        gl_state::set_cull_face(false);
+
+        // History has no depth -> only color is copied
+        if let Some(( scene, history )) = &self.targets {
+            scene.blit_to(history, gl::NEAREST);
+            self.history_valid = true;
+            let size = ( scene.width, scene.height );
+            blit(scene.id, size, screen, size, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+            gl_state::bind_framebuffer(gl::FRAMEBUFFER, screen);
+        }
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        let framebuffers = self.targets.iter_mut().flat_map( | ( scene, history ) | [ scene, history ] ).collect();
+        ResizeTargets { framebuffers, cameras: vec![ &mut self.camera ] }
+    }
+
+    // Resizing threw the old history away
+    fn on_resize( &mut self, _surface: Surface ) {
+        self.history_valid = false;
+    }
+
+    fn shutdown( &mut self ) {
+        if let Some(( scene, history )) = &mut self.targets {
+            scene.delete();
+            history.delete();
+        }
+    }
+}
+
+// Half floats first, 8 bit colors still give a trail that just never quite fades out
+fn trail_target( name: &str, width: i32, height: i32, depth: Option<DepthFormat> ) -> Option<Framebuffer> {
+    for format in [ ColorFormat::Rgba16F, ColorFormat::Rgba8 ] {
+        let mut builder = FramebufferBuilder::new(width, height).color(format);
+        if let Some(depth) = depth {
+            builder = builder.depth(depth);
+        }
+        match builder.build() {
+            Ok(framebuffer) => return Some(framebuffer),
+            Err(err) => log::warn!(target: APP, "solids {} target ( {:?} ): {}", name, format, err),
+        }
+    }
+    None
+}
+
+impl SolidsDemo {
+    // Last frame faded towards the clear color, depth test is still off so it stays behind everything
+    fn draw_trail(&self) {
+        let Some(history) = self.targets.as_ref().and_then( | ( _, history ) | history.color_texture(0) ) else { return };
+        let shader = &self.trail_shader;
+        gl_state::use_program(shader.id);
+        gl_state::bind_texture(0, gl::TEXTURE_2D, history.id);
+        gl_state::uniform(shader.uniform_location("tex0"), UniformValue::Int(0));
+        gl_state::uniform(shader.uniform_location("view_projection"), UniformValue::Mat4(identity()));
+        gl_state::uniform(shader.uniform_location("scale"), UniformValue::Float(0.0));
+        gl_state::uniform(shader.uniform_location("background"), UniformValue::Vec4(self.clear_color));
+        gl_state::uniform(shader.uniform_location("fade"), UniformValue::Float(0.8));
+        self.fullscreen.draw();
+    }
+}
+
+// A model part ready to submit -> where it sits inside its model ( glTF node transforms ) is in `local`
+struct ModelPart {
+    mesh: Rc<Mesh>,
+    material: Rc<Material>,
+    local: Mat4,
+}
+
+// Model shader with the diffuse / base color and its texture, plain white when there is none.
+// No highlight and no normal map until the caller sets them -> every material sets all the uniforms the shader shares
+fn model_material( shader: &Rc<Shader>, color: [f32; 4], texture: Rc<Texture> ) -> Material {
+    let mut material = Material::new(shader.clone());
+    material.set_vec4("color", color);
+    material.set_texture("tex0", texture);
+    material.set_vec4("specular", [ 0.0, 0.0, 0.0, 1.0 ]);
+    material.set_float("normal_strength", 0.0);
+    material
+}
+
+fn model_mesh( name: &str, data: &MeshData ) -> Rc<Mesh> {
+    let mesh = Mesh::new(data, &VertexLayout::standard());
+    mesh.label(name);
+    Rc::new(mesh)
+}
+
+// Every mesh of an .obj with the diffuse, specular and normal maps of its material
+fn obj_parts( path: &Path, shader: &Rc<Shader>, white: &Rc<Texture> ) -> Result<Vec<ModelPart>, String> {
+    let model = load_obj(&path.display().to_string())?;
+    // ( material, has a normal map )
+    let mut materials = Vec::new();
+    for obj in &model.materials {
+        let maps = obj.load_maps()?;
+        let [ r, g, b ] = obj.diffuse;
+        let mut material = model_material(shader, [ r, g, b, obj.opacity ], maps.diffuse.map(Rc::new).unwrap_or_else( || white.clone() ));
+        let [ r, g, b ] = obj.specular;
+        material.set_vec4("specular", [ r, g, b, obj.shininess ]);
+        material.set_texture("specular_map", maps.specular.map(Rc::new).unwrap_or_else( || white.clone() ));
+        let normal_mapped = maps.normal.is_some();
+        if let Some(normal) = maps.normal {
+            material.set_texture("normal_map", Rc::new(normal));
+            material.set_float("normal_strength", 1.0);
+        }
+        materials.push(( Rc::new(material), normal_mapped ));
+    }
+    let fallback = Rc::new(model_material(shader, [ 1.0, 1.0, 1.0, 1.0 ], white.clone()));
+    Ok(model.meshes.into_iter().map( | part | {
+        let ( material, normal_mapped ) = part.material.map( | index | materials[index].clone() ).unwrap_or(( fallback.clone(), false ));
+        let mut mesh = part.mesh;
+        // .obj has no tangents, the normal map needs them
+        if normal_mapped {
+            mesh.compute_tangents();
+        }
+        ModelPart { mesh: model_mesh(&part.name, &mesh), material, local: identity() }
+    }).collect())
+}
+
+// Every primitive of a glTF scene at its node's world matrix, with the base color factor and texture.
+// Blended materials go through the Renderer's transparent pass
+fn gltf_parts( path: &Path, shader: &Rc<Shader>, white: &Rc<Texture> ) -> Result<Vec<ModelPart>, String> {
+    let scene = load_gltf(&path.display().to_string())?;
+    let world = scene.world_matrices()?;
+    let textures = scene.load_textures()?.into_iter().map(Rc::new).collect::<Vec<Rc<Texture>>>();
+    let materials = scene.materials.iter().map( | gltf | {
+        let texture = gltf.base_color_texture.map( | reference | textures[reference.texture].clone() ).unwrap_or_else( || white.clone() );
+        let mut material = model_material(shader, gltf.base_color_factor, texture);
+        material.transparent = gltf.alpha_mode == AlphaMode::Blend;
+        Rc::new(material)
+    }).collect::<Vec<Rc<Material>>>();
+    let fallback = Rc::new(model_material(shader, [ 1.0, 1.0, 1.0, 1.0 ], white.clone()));
+    // Uploaded once, nodes that share a mesh share its buffers
+    let meshes = scene.meshes.iter().map( | mesh | {
+        mesh.primitives.iter().enumerate().map( | ( index, primitive ) | {
+            let material = primitive.material.map( | material | materials[material].clone() ).unwrap_or_else( || fallback.clone() );
+            ( model_mesh(&format!("{} {}", mesh.name, index), &primitive.mesh), material )
+        }).collect::<Vec<(Rc<Mesh>, Rc<Material>)>>()
+    }).collect::<Vec<_>>();
+
+    let mut parts = Vec::new();
+    for ( node, local ) in scene.nodes.iter().zip(world) {
+        let Some(mesh) = node.mesh else { continue };
+        parts.extend(meshes[mesh].iter().map( | ( mesh, material ) | ModelPart { mesh: mesh.clone(), material: material.clone(), local } ));
+    }
+    Ok(parts)
+}
+
+// Files from `<assets.root>/models` through the loaders, lit, textured and spinning side by side.
+// A model that fails to load is logged and left out, the others still show
+pub struct ModelsDemo {
+    camera: OrthoCamera,
+    renderer: Renderer,
+    // Parts of each model and where its center goes
+    models: Vec<( Vec<ModelPart>, Vec3, Vec3 )>,
+    previous_time: f64,
+    time: f64,
+    clear_color: [f32; 4],
+}
+
+impl ModelsDemo {
+    const GAP: f32 = 0.4;
+}
+
+impl App for ModelsDemo {
+    fn init( _context: &dyn GlContext, config: &Config ) -> Self {
+        let shader = Shader::new(include_str!("../glsl/model_v.vert"), include_str!("../glsl/model_f.frag"));
+        shader.label("models");
+        let shader = Rc::new(shader);
+        let white = Texture::from_pixels(1, 1, vec![ 255; 4 ]);
+        white.label("models white");
+        let white = Rc::new(white);
+
+        let directory = config.asset_root.join("models");
+        let loaded = [
+            ( "pyramid.obj", obj_parts(&directory.join("pyramid.obj"), &shader, &white) ),
+            ( "cube.glb", gltf_parts(&directory.join("cube.glb"), &shader, &white) ),
+            ( "quad.gltf", gltf_parts(&directory.join("quad.gltf"), &shader, &white) ),
+        ];
+        let mut models = Vec::new();
+        for ( name, parts ) in loaded {
+            match parts {
+                Ok(parts) => {
+                    let triangles = parts.iter().map( | part | part.mesh.index_count / 3 ).sum::<i32>();
+                    log::debug!(target: ASSETS, "models: {} -> {} parts, {} triangles", name, parts.len(), triangles);
+                    models.push(parts);
+                },
+                Err(err) => log::warn!(target: ASSETS, "models: {}: {}", name, err),
+            }
+        }
+
+        // Bounds of every model in its own space -> the row is as wide as the models plus the gaps
+        let bounds = models.iter().map( | parts | {
+            parts.iter()
+                .filter_map( | part | part.mesh.bounds.map( | bounds | bounds.transformed(&part.local) ) )
+                .reduce( | all, bounds | all.union(&bounds) )
+                .unwrap_or(Aabb::new(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 0.0)))
+        }).collect::<Vec<Aabb>>();
+        // Spinning around y -> the widest a model gets is its diagonal in x / z
+        let widths = bounds.iter().map( | bounds | 2.0 * bounds.extents().x.hypot(bounds.extents().z) ).collect::<Vec<f32>>();
+        let row = widths.iter().sum::<f32>() + Self::GAP * widths.len().saturating_sub(1) as f32;
+        let height = bounds.iter().map( | bounds | 2.0 * bounds.extents().y ).fold(1.0, f32::max);
+
+        let mut left = -row / 2.0;
+        let models = models.into_iter().zip(bounds.iter().zip(&widths)).map( | ( parts, ( bounds, width ) ) | {
+            let position = vec3(left + width / 2.0, 0.0, 0.0);
+            left += width + Self::GAP;
+            ( parts, bounds.center(), position )
+        }).collect();
+
+        let mut camera = OrthoCamera::new(row.max(1.0) + 2.0 * Self::GAP, height + 2.0 * Self::GAP, AspectMode::Expand);
+        camera.near = -10.0;
+        camera.far = 10.0;
+        Self {
+            camera,
+            renderer: Renderer::new(),
+            models,
+            previous_time: 0.0,
+            time: 0.0,
+            clear_color: config.clear_color("models", [ 0.1, 0.12, 0.14, 1.0 ]),
+        }
+    }
+
+    fn update( &mut self, dt: f64, _input: &mut Input ) -> Flow {
+        self.previous_time = self.time;
+        self.time += dt;
+        Flow::Continue
+    }
+
+    fn render( &mut self, alpha: f32 ) {
+        self.camera.apply_viewport(self.clear_color);
+        let time = lerp(self.previous_time, self.time, alpha) as f32;
+        let tilt = Quat::from_axis_angle(vec3(1.0, 0.0, 0.0), 0.4);
+        let spin = Quat::from_axis_angle(vec3(0.0, 1.0, 0.0), time * 0.6);
+
+        self.renderer.begin_frame(self.camera.view_projection());
+        for ( parts, center, position ) in &self.models {
+            // Turns around its own center, wherever the file put the origin
+            let placement = Transform { translation: *position, rotation: tilt * spin, scale: vec3(1.0, 1.0, 1.0) }.matrix()
+                * Transform::from_translation(-*center).matrix();
+            for part in parts {
+                self.renderer.submit(part.mesh.clone(), part.material.clone(), placement * part.local);
+            }
+        }
+        // Double sided glTF materials -> no back-face culling, depth test sorts it out
+        gl_state::set_depth_test(true);
+        gl_state::depth_func(gl::LESS);
+        self.renderer.end_frame();
+        gl_state::set_depth_test(false);
+    }
+
+    fn resize_targets( &mut self ) -> ResizeTargets<'_> {
+        ResizeTargets { cameras: vec![ &mut self.camera ], ..ResizeTargets::default() }
+    }
+}
+
+// Registry -> `--demo <name>` / `--list`, each demo keeps the window it had as a standalone example
+pub struct DemoEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub title: &'static str,
+    pub create: AppFactory,
+}
+
+impl DemoEntry {
+    pub fn window(&self) -> WindowConfig {
+        WindowConfig::new(self.width, self.height, self.title)
+    }
+}
+
+pub const DEFAULT_DEMO: &str = "textured-quad";
+
+pub const DEMOS: &[DemoEntry] = &[
+    DemoEntry {
+        name: "triangle", description: "one triangle in a flat color from `[colors]`",
+        width: 900, height: 600, title: "OpenGL",
+        create: | context, config | Box::new(TriangleDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "rectangle", description: "an indexed rectangle",
+        width: 800, height: 600, title: "Hello Rectangle",
+        create: | context, config | Box::new(RectangleDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "textured-quad", description: "wall texture on a pulsing quad",
+        width: 800, height: 800, title: "Can I get 3D",
+        create: | context, config | Box::new(TexturedQuadDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "side-by-side", description: "rectangle, rhombus and parallelogram next to each other",
+        width: 1200, height: 900, title: "Textured Shape",
+        create: | context, config | Box::new(SideBySideDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "two-shapes", description: "parallelogram and rectangle with animated colors",
+        width: 900, height: 600, title: "Shapes: llgm & Rectangle",
+        create: | context, config | Box::new(TwoShapesDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "instanced", description: "hundreds of spinning shapes, one instanced draw call per shape",
+        width: 1200, height: 900, title: "Instanced Shapes",
+        create: | context, config | Box::new(InstancedDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "sprites", description: "bouncing sprites in pixel space through a sprite batch, click to add more",
+        width: 800, height: 600, title: "Sprites",
+        create: | context, config | Box::new(SpritesDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "solids", description: "every solid generator, lit and spinning with depth test and back-face culling",
+        width: 1200, height: 600, title: "Solids",
+        create: | context, config | Box::new(SolidsDemo::init(context, config)),
+    },
+    DemoEntry {
+        name: "models", description: "the sample models through the OBJ and glTF loaders, lit and textured",
+        width: 1000, height: 600, title: "Models",
+        create: | context, config | Box::new(ModelsDemo::init(context, config)),
+    },
+];
+
+pub fn find_demo( name: &str ) -> Result<&'static DemoEntry, String> {
+    DEMOS.iter().find( | demo | demo.name == name ).ok_or_else( || {
+        let names = DEMOS.iter().map( | demo | demo.name ).collect::<Vec<&str>>();
+        format!("unknown demo '{}', expected one of: {}", name, names.join(", "))
+    })
+}
@@ -0,0 +1,201 @@
+// Golden Images -> render every demo offscreen and compare with a stored PNG
+//   cargo run --features headless -- --golden-check
+//   cargo run --features headless -- --golden-update   ( after an intended visual change )
+// The JPEG assets decode through `image` -> Cargo.lock pins its version, bumping it may need new goldens
+use std::{fs, path::{Path, PathBuf}};
+
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    app::{app::Surface, runner::apply_resize},
+    config::config::{Config, WindowConfig},
+    context::{context::GlContext, headless::HeadlessContext},
+    demos::demos::find_demo,
+    input::{actions::ActionMap, input::Input},
+    logging::logging::APP,
+    renderer::capture::{read_pixels, CaptureSource}
+};
+
+// Largest per-channel difference that still counts as equal -> absorbs rasterizer / filtering noise
+pub const DEFAULT_TOLERANCE: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenMode {
+    Check,
+    Update,
+}
+
+impl GoldenMode {
+    pub fn from_args( args: &[String] ) -> Option<Self> {
+        if args.iter().any( | arg | arg == "--golden-update" ) {
+            Some(GoldenMode::Update)
+        } else if args.iter().any( | arg | arg == "--golden-check" ) {
+            Some(GoldenMode::Check)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct GoldenCase {
+    // Demo name from the registry, also the file name of the golden
+    pub name: &'static str,
+    // Same size as the demo window so the aspect matches
+    pub width: i32,
+    pub height: i32,
+    // Animated demos get one update() of this length before the frame
+    pub time: f64,
+}
+
+pub fn cases() -> Vec<GoldenCase> {
+    vec![
+        GoldenCase { name: "triangle", width: 300, height: 200, time: 0.0 },
+        GoldenCase { name: "rectangle", width: 400, height: 300, time: 0.0 },
+        // At t = PI the scale uniform is 0 -> the quad has its original size
+        GoldenCase { name: "textured-quad", width: 400, height: 400, time: std::f64::consts::PI },
+        GoldenCase { name: "side-by-side", width: 400, height: 300, time: 1.0 },
+        GoldenCase { name: "two-shapes", width: 450, height: 300, time: 0.0 },
+        GoldenCase { name: "instanced", width: 400, height: 300, time: 1.0 },
+        GoldenCase { name: "sprites", width: 400, height: 300, time: 0.5 },
+        GoldenCase { name: "solids", width: 480, height: 240, time: 1.0 },
+        GoldenCase { name: "models", width: 500, height: 300, time: 1.0 },
+    ]
+}
+
+pub struct Comparison {
+    // Pixels with any channel off by more than the tolerance
+    pub mismatched: usize,
+    pub max_difference: u8,
+    // Mismatches in red over a faded copy of the golden
+    pub diff: RgbaImage,
+}
+
+pub fn compare( actual: &RgbaImage, expected: &RgbaImage, tolerance: u8 ) -> Result<Comparison, String> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(format!("size {:?} doesn't match the golden {:?}", actual.dimensions(), expected.dimensions()));
+    }
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    let ( mut mismatched, mut max_difference ) = ( 0, 0 );
+
+    for ( ( a, e ), d ) in actual.pixels().zip(expected.pixels()).zip(diff.pixels_mut()) {
+        let difference = a.0.iter().zip(e.0.iter()).map( | ( x, y ) | x.abs_diff(*y) ).max().unwrap_or(0);
+        max_difference = max_difference.max(difference);
+        *d = if difference > tolerance {
+            mismatched += 1;
+            Rgba([ 255, 0, 0, 255 ])
+        } else {
+            let gray = ((e[0] as u32 + e[1] as u32 + e[2] as u32) / 3 / 4) as u8;
+            Rgba([ gray, gray, gray, 255 ])
+        };
+    }
+    Ok(Comparison { mismatched, max_difference, diff })
+}
+
+pub fn render_case( context: &mut HeadlessContext, case: &GoldenCase ) -> Result<RgbaImage, String> {
+    context.resize(case.width, case.height)?;
+    // Built-in defaults only, a local openglyt.toml must not change the goldens
+    let mut config = Config::new(WindowConfig::new(case.width as u32, case.height as u32, case.name));
+    config.asset_root = Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("assets");
+    let mut app = (find_demo(case.name)?.create)(context, &config);
+    apply_resize(app.as_mut(), Surface::of(context))?;
+    app.update(case.time, &mut Input::new(ActionMap::default()));
+    context.bind_target();
+    // Right on the update, nothing to blend
+    app.render(1.0);
+    context.present();
+    app.shutdown();
+    Ok(read_pixels(CaptureSource::of(&context.target)))
+}
+
+pub fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join("assets").join("goldens")
+}
+
+// Where actual / diff images of failed cases go
+pub fn output_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("golden")
+}
+
+// Err lists every failed case, check mode only fails, it never rewrites a golden
+pub fn run( mode: GoldenMode, tolerance: u8 ) -> Result<(), String> {
+    let mut context = HeadlessContext::new(1, 1)?;
+    let mut failures = Vec::new();
+
+    for case in cases() {
+        let actual = render_case(&mut context, &case)?;
+        let golden_path = golden_dir().join(format!("{}.png", case.name));
+
+        if mode == GoldenMode::Update {
+            fs::create_dir_all(golden_dir()).map_err( | err | err.to_string() )?;
+            actual.save(&golden_path).map_err( | err | format!("{}: {}", golden_path.display(), err) )?;
+            log::info!(target: APP, "golden {} -> updated", case.name);
+            continue;
+        }
+
+        let expected = match image::open(&golden_path) {
+            Ok(expected) => expected.to_rgba8(),
+            Err(err) => {
+                failures.push(format!("{}: no golden at {} ( {} ), run with --golden-update", case.name, golden_path.display(), err));
+                continue;
+            }
+        };
+        let comparison = match compare(&actual, &expected, tolerance) {
+            Ok(comparison) => comparison,
+            Err(err) => {
+                failures.push(format!("{}: {}", case.name, err));
+                continue;
+            }
+        };
+        if comparison.mismatched == 0 {
+            log::info!(target: APP, "golden {} -> ok ( max difference {} )", case.name, comparison.max_difference);
+            continue;
+        }
+
+        fs::create_dir_all(output_dir()).map_err( | err | err.to_string() )?;
+        let actual_path = output_dir().join(format!("{}.actual.png", case.name));
+        let diff_path = output_dir().join(format!("{}.diff.png", case.name));
+        actual.save(&actual_path).map_err( | err | format!("{}: {}", actual_path.display(), err) )?;
+        comparison.diff.save(&diff_path).map_err( | err | format!("{}: {}", diff_path.display(), err) )?;
+        failures.push(format!(
+            "{}: {} pixels differ by more than {} ( max {} ), see {}",
+            case.name, comparison.mismatched, tolerance, comparison.max_difference, diff_path.display()
+        ));
+    }
+
+    if failures.is_empty() { Ok(()) } else { Err(failures.join("\n")) }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use super::*;
+
+    // cargo test --features headless -> the same check as --golden-check
+    #[test]
+    fn demos_match_goldens() {
+        if let Err(failures) = run(GoldenMode::Check, DEFAULT_TOLERANCE) {
+            panic!("golden images differ:\n{}", failures);
+        }
+    }
+
+    #[test]
+    fn compare_counts_pixels_over_the_tolerance() {
+        let expected = RgbaImage::from_pixel(4, 2, Rgba([ 100, 100, 100, 255 ]));
+        let mut actual = expected.clone();
+        actual.put_pixel(0, 0, Rgba([ 102, 100, 100, 255 ]));
+        actual.put_pixel(3, 1, Rgba([ 100, 110, 100, 255 ]));
+
+        let comparison = compare(&actual, &expected, DEFAULT_TOLERANCE).unwrap();
+        assert_eq!(comparison.mismatched, 1);
+        assert_eq!(comparison.max_difference, 10);
+        assert_eq!(*comparison.diff.get_pixel(3, 1), Rgba([ 255, 0, 0, 255 ]));
+        assert!(compare(&RgbaImage::new(2, 2), &expected, DEFAULT_TOLERANCE).is_err());
+    }
+
+    #[test]
+    fn golden_mode_from_args() {
+        let args = | list: &[&str] | list.iter().map( | arg | arg.to_string() ).collect::<Vec<String>>();
+        assert_eq!(GoldenMode::from_args(&args(&[ "openglyt", "--golden-check" ])), Some(GoldenMode::Check));
+        assert_eq!(GoldenMode::from_args(&args(&[ "openglyt", "--golden-check", "--golden-update" ])), Some(GoldenMode::Update));
+        assert_eq!(GoldenMode::from_args(&args(&[ "openglyt" ])), None);
+    }
+}
@@ -0,0 +1,13 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoord;
+in vec4 tint;
+flat in float layer;
+
+uniform sampler2DArray textures;
+
+void main() {
+    FragColor = texture( textures, vec3( TexCoord, layer ) ) * tint;
+}
@@ -0,0 +1,22 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPos;
+layout (location = 2) in vec2 aTexCoord;
+
+// Per instance -> InstanceLayout::standard()
+layout (location = 4) in mat4 aModel;
+layout (location = 8) in vec4 aTint;
+layout (location = 9) in float aLayer;
+
+out vec2 TexCoord;
+out vec4 tint;
+flat out float layer;
+
+uniform mat4 view_projection;
+
+void main() {
+    gl_Position = view_projection * aModel * vec4( aPos, 1.0 );
+    TexCoord = aTexCoord;
+    tint = aTint;
+    layer = aLayer;
+}
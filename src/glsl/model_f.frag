@@ -0,0 +1,38 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec3 normal;
+in vec4 tangent;
+in vec2 TexCoord;
+
+uniform sampler2D tex0;
+uniform sampler2D specular_map;
+uniform sampler2D normal_map;
+uniform vec4 color;
+// rgb -> specular color ( black = no highlight ), a -> shininess
+uniform vec4 specular;
+// 0 -> no normal map bound, the vertex normal is used as is
+uniform float normal_strength;
+
+const vec3 light_direction = vec3( 0.4, 0.7, 0.6 );
+// Orthographic camera looking down -z
+const vec3 view_direction = vec3( 0.0, 0.0, 1.0 );
+
+void main() {
+    vec3 n = normalize( normal );
+    if ( normal_strength > 0.0 ) {
+        // Tangent space -> world, the tangent is made orthogonal to the interpolated normal first
+        vec3 t = normalize( tangent.xyz - n * dot( n, tangent.xyz ) );
+        vec3 b = cross( n, t ) * tangent.w;
+        vec3 mapped = texture( normal_map, TexCoord ).xyz * 2.0 - 1.0;
+        n = normalize( mix( n, mat3( t, b, n ) * mapped, normal_strength ) );
+    }
+    vec3 l = normalize( light_direction );
+    // Lit from both sides, double sided faces show their back too
+    float diffuse = abs( dot( n, l ) );
+    float highlight = pow( abs( dot( n, normalize( l + view_direction ) ) ), max( specular.a, 1.0 ) );
+    vec4 base = color * texture( tex0, TexCoord );
+    vec3 shine = specular.rgb * texture( specular_map, TexCoord ).rgb * highlight;
+    FragColor = vec4( base.rgb * ( 0.25 + 0.75 * diffuse ) + shine, base.a );
+}
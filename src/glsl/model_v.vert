@@ -0,0 +1,21 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+layout (location = 2) in vec2 aTexCoord;
+layout (location = 3) in vec4 aTangent;
+
+out vec3 normal;
+out vec4 tangent;
+out vec2 TexCoord;
+
+uniform mat4 view_projection;
+uniform mat4 model;
+
+void main() {
+    gl_Position = view_projection * model * vec4( aPos, 1.0 );
+    // Rotation and uniform scale only -> the model matrix works for normals too
+    normal = mat3( model ) * aNormal;
+    tangent = vec4( mat3( model ) * aTangent.xyz, aTangent.w );
+    TexCoord = aTexCoord;
+}
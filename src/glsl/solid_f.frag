@@ -0,0 +1,14 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec3 normal;
+
+uniform vec4 color;
+
+const vec3 light_direction = vec3( 0.4, 0.7, 0.6 );
+
+void main() {
+    float diffuse = max( dot( normalize( normal ), normalize( light_direction ) ), 0.0 );
+    FragColor = vec4( color.rgb * ( 0.25 + 0.75 * diffuse ), color.a );
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aNormal;
+
+out vec3 normal;
+
+uniform mat4 view_projection;
+uniform mat4 model;
+
+void main() {
+    gl_Position = view_projection * model * vec4( aPos, 1.0 );
+    // Rotation and uniform scale only -> the model matrix works for normals too
+    normal = mat3( model ) * aNormal;
+}
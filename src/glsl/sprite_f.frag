@@ -0,0 +1,12 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec2 TexCoord;
+in vec4 color;
+
+uniform sampler2D sprite_texture;
+
+void main() {
+    FragColor = texture( sprite_texture, TexCoord ) * color;
+}
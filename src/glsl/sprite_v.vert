@@ -0,0 +1,16 @@
+#version 330 core
+
+layout (location = 0) in vec2 aPos;
+layout (location = 1) in vec4 aColor;
+layout (location = 2) in vec2 aTexCoord;
+
+out vec2 TexCoord;
+out vec4 color;
+
+uniform mat4 view_projection;
+
+void main() {
+    gl_Position = view_projection * vec4( aPos, 0.0, 1.0 );
+    color = aColor;
+    TexCoord = aTexCoord;
+}
@@ -7,10 +7,11 @@ layout (location = 2) in vec2 aTexCoord;
 out vec2 TexCoord;
 out vec3 color;
 
+uniform mat4 view_projection;
 uniform float scale;
 
 void main() {
-    gl_Position = vec4( aPos.x + (aPos.x * scale), aPos.y + (aPos.y * scale), aPos.z + (aPos.z * scale), 1.0 );
+    gl_Position = view_projection * vec4( aPos.x + (aPos.x * scale), aPos.y + (aPos.y * scale), aPos.z + (aPos.z * scale), 1.0 );
     color = aColor;
     TexCoord = aTexCoord;
-}
\ No newline at end of file
+}
@@ -0,0 +1,15 @@
+#version 330 core
+
+out vec4 FragColor;
+
+in vec3 color;
+in vec2 TexCoord;
+
+uniform sampler2D tex0;
+uniform vec4 background;
+// How much of the last frame is left, 0 -> none
+uniform float fade;
+
+void main() {
+    FragColor = mix( background, texture( tex0, TexCoord ), fade );
+}
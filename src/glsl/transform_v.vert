@@ -0,0 +1,17 @@
+#version 330 core
+
+layout (location = 0) in vec3 aPos;
+layout (location = 1) in vec3 aColor;
+layout (location = 2) in vec2 aTexCoord;
+
+out vec2 TexCoord;
+out vec3 color;
+
+uniform mat4 view_projection;
+uniform mat4 model;
+
+void main() {
+    gl_Position = view_projection * model * vec4( aPos, 1.0 );
+    color = aColor;
+    TexCoord = aTexCoord;
+}
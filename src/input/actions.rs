@@ -0,0 +1,284 @@
+// Actions -> named things the user can do, each bound to any number of keys / buttons / pad inputs
+//   [input] in openglyt.toml:  move_forward = [ "W", "Up", "PadDpadUp", "LeftY-" ]
+use std::{collections::BTreeMap, fmt};
+
+use glfw::{GamepadAxis, GamepadButton, Key, MouseButton};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AxisDirection {
+    Positive,
+    Negative,
+}
+
+impl AxisDirection {
+    pub fn sign(&self) -> f32 {
+        match self {
+            AxisDirection::Positive => 1.0,
+            AxisDirection::Negative => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Binding {
+    Key(Key),
+    Mouse(MouseButton),
+    // Any connected gamepad
+    Pad(GamepadButton),
+    // One half of a stick or a trigger, e.g. the left stick pushed left
+    Axis(GamepadAxis, AxisDirection),
+}
+
+// Every key a binding can name, the name is the glfw variant ( "W", "Up", "LeftShift", "F1", "Kp0" )
+const KEYS: &[Key] = &[
+    Key::Space, Key::Apostrophe, Key::Comma, Key::Minus, Key::Period, Key::Slash,
+    Key::Num0, Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+    Key::Semicolon, Key::Equal,
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::LeftBracket, Key::Backslash, Key::RightBracket, Key::GraveAccent, Key::World1, Key::World2,
+    Key::Escape, Key::Enter, Key::Tab, Key::Backspace, Key::Insert, Key::Delete,
+    Key::Right, Key::Left, Key::Down, Key::Up, Key::PageUp, Key::PageDown, Key::Home, Key::End,
+    Key::CapsLock, Key::ScrollLock, Key::NumLock, Key::PrintScreen, Key::Pause,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12, Key::F13,
+    Key::F14, Key::F15, Key::F16, Key::F17, Key::F18, Key::F19, Key::F20, Key::F21, Key::F22, Key::F23, Key::F24, Key::F25,
+    Key::Kp0, Key::Kp1, Key::Kp2, Key::Kp3, Key::Kp4, Key::Kp5, Key::Kp6, Key::Kp7, Key::Kp8, Key::Kp9,
+    Key::KpDecimal, Key::KpDivide, Key::KpMultiply, Key::KpSubtract, Key::KpAdd, Key::KpEnter, Key::KpEqual,
+    Key::LeftShift, Key::LeftControl, Key::LeftAlt, Key::LeftSuper,
+    Key::RightShift, Key::RightControl, Key::RightAlt, Key::RightSuper, Key::Menu,
+];
+
+const MOUSE_BUTTONS: &[(&str, MouseButton)] = &[
+    ( "MouseLeft", MouseButton::Button1 ),
+    ( "MouseRight", MouseButton::Button2 ),
+    ( "MouseMiddle", MouseButton::Button3 ),
+    ( "Mouse4", MouseButton::Button4 ),
+    ( "Mouse5", MouseButton::Button5 ),
+    ( "Mouse6", MouseButton::Button6 ),
+    ( "Mouse7", MouseButton::Button7 ),
+    ( "Mouse8", MouseButton::Button8 ),
+];
+
+const PAD_BUTTONS: &[(&str, GamepadButton)] = &[
+    ( "PadA", GamepadButton::ButtonA ),
+    ( "PadB", GamepadButton::ButtonB ),
+    ( "PadX", GamepadButton::ButtonX ),
+    ( "PadY", GamepadButton::ButtonY ),
+    ( "PadLeftBumper", GamepadButton::ButtonLeftBumper ),
+    ( "PadRightBumper", GamepadButton::ButtonRightBumper ),
+    ( "PadBack", GamepadButton::ButtonBack ),
+    ( "PadStart", GamepadButton::ButtonStart ),
+    ( "PadGuide", GamepadButton::ButtonGuide ),
+    ( "PadLeftThumb", GamepadButton::ButtonLeftThumb ),
+    ( "PadRightThumb", GamepadButton::ButtonRightThumb ),
+    ( "PadDpadUp", GamepadButton::ButtonDpadUp ),
+    ( "PadDpadRight", GamepadButton::ButtonDpadRight ),
+    ( "PadDpadDown", GamepadButton::ButtonDpadDown ),
+    ( "PadDpadLeft", GamepadButton::ButtonDpadLeft ),
+];
+
+// Sticks take a "+" or "-" after the name, y is negative when pushed up, triggers have only one direction
+const PAD_AXES: &[(&str, GamepadAxis)] = &[
+    ( "LeftX", GamepadAxis::AxisLeftX ),
+    ( "LeftY", GamepadAxis::AxisLeftY ),
+    ( "RightX", GamepadAxis::AxisRightX ),
+    ( "RightY", GamepadAxis::AxisRightY ),
+    ( "LeftTrigger", GamepadAxis::AxisLeftTrigger ),
+    ( "RightTrigger", GamepadAxis::AxisRightTrigger ),
+];
+
+fn is_trigger( axis: GamepadAxis ) -> bool {
+    matches!(axis, GamepadAxis::AxisLeftTrigger | GamepadAxis::AxisRightTrigger)
+}
+
+impl Binding {
+    // Case doesn't matter -> "w", "W" and "left_shift" / "LeftShift" all work
+    pub fn parse( name: &str ) -> Result<Binding, String> {
+        let wanted = name.replace('_', "").to_ascii_lowercase();
+        if let Some(key) = KEYS.iter().find( | key | format!("{:?}", key).to_ascii_lowercase() == wanted ) {
+            return Ok(Binding::Key(*key));
+        }
+        if let Some(( _, button )) = MOUSE_BUTTONS.iter().find( | ( button, _ ) | button.to_ascii_lowercase() == wanted ) {
+            return Ok(Binding::Mouse(*button));
+        }
+        if let Some(( _, button )) = PAD_BUTTONS.iter().find( | ( button, _ ) | button.to_ascii_lowercase() == wanted ) {
+            return Ok(Binding::Pad(*button));
+        }
+        let ( axis_name, direction ) = match wanted.strip_suffix('+') {
+            Some(axis) => ( axis, AxisDirection::Positive ),
+            None => match wanted.strip_suffix('-') {
+                Some(axis) => ( axis, AxisDirection::Negative ),
+                None => ( wanted.as_str(), AxisDirection::Positive ),
+            },
+        };
+        if let Some(( label, axis )) = PAD_AXES.iter().find( | ( axis, _ ) | axis.to_ascii_lowercase() == axis_name ) {
+            let signed = wanted.ends_with([ '+', '-' ]);
+            return match ( is_trigger(*axis), signed ) {
+                ( true, false ) => Ok(Binding::Axis(*axis, AxisDirection::Positive)),
+                ( false, true ) => Ok(Binding::Axis(*axis, direction)),
+                ( true, true ) => Err(format!("'{}': triggers have no direction, use \"{}\"", name, label)),
+                ( false, false ) => Err(format!("'{}': sticks need a direction, e.g. \"{}+\" or \"{}-\"", name, label, label)),
+            };
+        }
+        Err(format!("unknown key or button '{}', e.g. \"W\", \"Up\", \"Space\", \"F1\", \"LeftShift\", \"MouseLeft\", \"PadA\" or \"LeftX-\"", name))
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Mouse(button) => {
+                let name = MOUSE_BUTTONS.iter().find( | ( _, b ) | b == button ).map( | ( name, _ ) | *name ).unwrap_or("Mouse?");
+                write!(f, "{}", name)
+            },
+            Binding::Pad(button) => {
+                let name = PAD_BUTTONS.iter().find( | ( _, b ) | b == button ).map( | ( name, _ ) | *name ).unwrap_or("Pad?");
+                write!(f, "{}", name)
+            },
+            Binding::Axis(axis, direction) => {
+                let name = PAD_AXES.iter().find( | ( _, a ) | a == axis ).map( | ( name, _ ) | *name ).unwrap_or("Axis?");
+                match ( is_trigger(*axis), direction ) {
+                    ( true, _ ) => write!(f, "{}", name),
+                    ( false, AxisDirection::Positive ) => write!(f, "{}+", name),
+                    ( false, AxisDirection::Negative ) => write!(f, "{}-", name),
+                }
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionMap {
+    bindings: BTreeMap<String, Vec<Binding>>,
+}
+
+impl ActionMap {
+    // The runner's own actions and the movement the demos share, the config can rebind all of them
+    pub fn defaults() -> Self {
+        let mut actions = Self::default();
+        actions.bind("quit", Binding::Key(Key::Escape));
+        actions.bind("quit", Binding::Pad(GamepadButton::ButtonBack));
+        actions.bind("toggle_wireframe", Binding::Key(Key::F1));
+        actions.bind("toggle_wireframe", Binding::Pad(GamepadButton::ButtonY));
+        actions.bind("gl_debug_severity", Binding::Key(Key::F2));
+        actions.bind("rebind", Binding::Key(Key::F3));
+        actions.bind("attach", Binding::Key(Key::Space));
+        actions.bind("attach", Binding::Pad(GamepadButton::ButtonA));
+        for ( action, keys, dpad, stick ) in [
+            ( "move_forward", [ Key::W, Key::Up ], GamepadButton::ButtonDpadUp, ( GamepadAxis::AxisLeftY, AxisDirection::Negative ) ),
+            ( "move_back", [ Key::S, Key::Down ], GamepadButton::ButtonDpadDown, ( GamepadAxis::AxisLeftY, AxisDirection::Positive ) ),
+            ( "move_left", [ Key::A, Key::Left ], GamepadButton::ButtonDpadLeft, ( GamepadAxis::AxisLeftX, AxisDirection::Negative ) ),
+            ( "move_right", [ Key::D, Key::Right ], GamepadButton::ButtonDpadRight, ( GamepadAxis::AxisLeftX, AxisDirection::Positive ) ),
+        ] {
+            for key in keys {
+                actions.bind(action, Binding::Key(key));
+            }
+            actions.bind(action, Binding::Pad(dpad));
+            actions.bind(action, Binding::Axis(stick.0, stick.1));
+        }
+        actions
+    }
+
+    // Adds a binding, the ones already there stay
+    pub fn bind( &mut self, action: &str, binding: Binding ) {
+        let bindings = self.bindings.entry(action.to_string()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    pub fn unbind( &mut self, action: &str, binding: Binding ) {
+        if let Some(bindings) = self.bindings.get_mut(action) {
+            bindings.retain( | b | *b != binding );
+        }
+    }
+
+    // Replaces every binding of `action`, an empty list leaves it unbound.
+    // Only actions that already exist -> a typo in the config doesn't quietly add a new one
+    pub fn rebind( &mut self, action: &str, bindings: Vec<Binding> ) -> Result<(), String> {
+        match self.bindings.get_mut(action) {
+            Some(current) => {
+                *current = bindings;
+                Ok(())
+            },
+            None => Err(format!("unknown action, expected one of: {}", self.actions().collect::<Vec<&str>>().join(", "))),
+        }
+    }
+
+    pub fn bindings( &self, action: &str ) -> &[Binding] {
+        self.bindings.get(action).map( | bindings | bindings.as_slice() ).unwrap_or(&[])
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().map( | action | action.as_str() )
+    }
+
+    // Every action `binding` triggers -> for spotting conflicts when rebinding
+    pub fn actions_for( &self, binding: Binding ) -> Vec<&str> {
+        self.bindings.iter()
+            .filter( | ( _, bindings ) | bindings.contains(&binding) )
+            .map( | ( action, _ ) | action.as_str() )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_name_round_trips_through_display() {
+        let bindings = KEYS.iter().map( | key | Binding::Key(*key) )
+            .chain(MOUSE_BUTTONS.iter().map( | ( _, button ) | Binding::Mouse(*button) ))
+            .chain(PAD_BUTTONS.iter().map( | ( _, button ) | Binding::Pad(*button) ))
+            .chain(PAD_AXES.iter().flat_map( | ( _, axis ) | {
+                let directions = if is_trigger(*axis) { vec![ AxisDirection::Positive ] } else { vec![ AxisDirection::Positive, AxisDirection::Negative ] };
+                directions.into_iter().map( | direction | Binding::Axis(*axis, direction) )
+            }));
+        for binding in bindings {
+            assert_eq!(Binding::parse(&binding.to_string()), Ok(binding), "{}", binding);
+        }
+    }
+
+    #[test]
+    fn parse_ignores_case_and_underscores() {
+        assert_eq!(Binding::parse("left_shift"), Ok(Binding::Key(Key::LeftShift)));
+        assert_eq!(Binding::parse("w"), Ok(Binding::Key(Key::W)));
+        assert_eq!(Binding::parse("mouseleft"), Ok(Binding::Mouse(MouseButton::Button1)));
+        assert_eq!(Binding::parse("lefty-"), Ok(Binding::Axis(GamepadAxis::AxisLeftY, AxisDirection::Negative)));
+    }
+
+    #[test]
+    fn parse_explains_what_is_wrong() {
+        assert!(Binding::parse("Wx").unwrap_err().starts_with("unknown key or button 'Wx'"));
+        assert!(Binding::parse("LeftX").unwrap_err().contains("sticks need a direction"));
+        assert!(Binding::parse("LeftTrigger+").unwrap_err().contains("triggers have no direction"));
+        assert!(Binding::parse("").is_err());
+    }
+
+    #[test]
+    fn actions_for_finds_every_conflict() {
+        let mut actions = ActionMap::defaults();
+        assert_eq!(actions.actions_for(Binding::Key(Key::W)), vec![ "move_forward" ]);
+        assert!(actions.actions_for(Binding::Key(Key::I)).is_empty());
+
+        actions.bind("quit", Binding::Key(Key::W));
+        assert_eq!(actions.actions_for(Binding::Key(Key::W)), vec![ "move_forward", "quit" ]);
+        actions.unbind("move_forward", Binding::Key(Key::W));
+        assert_eq!(actions.actions_for(Binding::Key(Key::W)), vec![ "quit" ]);
+    }
+
+    #[test]
+    fn rebind_only_knows_existing_actions() {
+        let mut actions = ActionMap::defaults();
+        actions.rebind("quit", vec![ Binding::Key(Key::Q) ]).unwrap();
+        assert_eq!(actions.bindings("quit"), &[ Binding::Key(Key::Q) ]);
+        actions.rebind("quit", Vec::new()).unwrap();
+        assert!(actions.bindings("quit").is_empty());
+
+        let err = actions.rebind("qiut", vec![ Binding::Key(Key::Q) ]).unwrap_err();
+        assert!(err.starts_with("unknown action, expected one of: ") && err.contains("quit"), "{}", err);
+        assert!(actions.actions().all( | action | action != "qiut" ));
+    }
+}
@@ -0,0 +1,335 @@
+// Gamepads -> glfw's standard mapping ( Xbox layout ), up to 16 pads, hot-plug by polling every frame
+// Joysticks without a gamepad mapping are ignored, glfw ships mappings for most controllers
+use glfw::{Action, GamepadAxis, GamepadButton, GamepadState, Glfw, JoystickId};
+
+pub const MAX_PADS: usize = 16;
+pub const BUTTON_COUNT: usize = 15;
+pub const AXIS_COUNT: usize = 6;
+
+// Raw values as glfw reports them -> sticks -1..1 ( y points down ), triggers -1 at rest .. 1 pulled
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PadState {
+    pub buttons: [bool; BUTTON_COUNT],
+    pub axes: [f32; AXIS_COUNT],
+}
+
+impl PadState {
+    pub fn from_glfw( state: &GamepadState ) -> Self {
+        let mut pad = Self::default();
+        for ( i, button ) in pad.buttons.iter_mut().enumerate() {
+            let id = GamepadButton::from_i32(i as i32).expect("index below BUTTON_COUNT");
+            *button = state.get_button_state(id) != Action::Release;
+        }
+        for ( i, axis ) in pad.axes.iter_mut().enumerate() {
+            *axis = state.get_axis(GamepadAxis::from_i32(i as i32).expect("index below AXIS_COUNT"));
+        }
+        pad
+    }
+
+    // A pad at rest -> what tests start from, triggers are released at -1
+    pub fn idle() -> Self {
+        let mut pad = Self::default();
+        pad.axes[GamepadAxis::AxisLeftTrigger as usize] = -1.0;
+        pad.axes[GamepadAxis::AxisRightTrigger as usize] = -1.0;
+        pad
+    }
+
+    pub fn button( &self, button: GamepadButton ) -> bool {
+        self.buttons[button as usize]
+    }
+
+    // Deadzone applied -> sticks -1..1, triggers 0..1
+    pub fn axis( &self, axis: GamepadAxis, deadzone: Deadzone ) -> f32 {
+        let value = | axis: GamepadAxis | self.axes[axis as usize];
+        match axis {
+            GamepadAxis::AxisLeftX => stick(value(GamepadAxis::AxisLeftX), value(GamepadAxis::AxisLeftY), deadzone.stick).0,
+            GamepadAxis::AxisLeftY => stick(value(GamepadAxis::AxisLeftX), value(GamepadAxis::AxisLeftY), deadzone.stick).1,
+            GamepadAxis::AxisRightX => stick(value(GamepadAxis::AxisRightX), value(GamepadAxis::AxisRightY), deadzone.stick).0,
+            GamepadAxis::AxisRightY => stick(value(GamepadAxis::AxisRightX), value(GamepadAxis::AxisRightY), deadzone.stick).1,
+            GamepadAxis::AxisLeftTrigger | GamepadAxis::AxisRightTrigger => trigger(value(axis), deadzone.trigger),
+        }
+    }
+}
+
+// Fraction of the range that reads as 0, worn sticks never rest exactly at the center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deadzone {
+    pub stick: f32,
+    pub trigger: f32,
+}
+
+impl Default for Deadzone {
+    fn default() -> Self {
+        Self { stick: 0.2, trigger: 0.1 }
+    }
+}
+
+// Radial -> the stick is judged by its distance from the center, not per axis, so diagonals stay smooth
+// The rest is rescaled so the output starts at 0 right at the edge of the zone
+pub fn stick( x: f32, y: f32, deadzone: f32 ) -> (f32, f32) {
+    let length = (x * x + y * y).sqrt();
+    if length <= deadzone {
+        return ( 0.0, 0.0 );
+    }
+    let scale = ((length.min(1.0) - deadzone) / (1.0 - deadzone)) / length;
+    ( x * scale, y * scale )
+}
+
+// -1..1 -> 0..1 with the bottom `deadzone` cut off
+pub fn trigger( value: f32, deadzone: f32 ) -> f32 {
+    let pulled = ((value + 1.0) / 2.0).clamp(0.0, 1.0);
+    if pulled <= deadzone { 0.0 } else { (pulled - deadzone) / (1.0 - deadzone) }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gamepad {
+    pub name: String,
+    pub state: PadState,
+    pub previous: PadState,
+    // false for the one frame after unplugging -> everything it held reads as released
+    pub connected: bool,
+}
+
+pub struct Gamepads {
+    pub deadzone: Deadzone,
+    // Indexed by glfw joystick id
+    pads: Vec<Option<Gamepad>>,
+    connected: Vec<usize>,
+    disconnected: Vec<usize>,
+}
+
+impl Gamepads {
+    pub fn new() -> Self {
+        Self { deadzone: Deadzone::default(), pads: vec![ None; MAX_PADS ], connected: Vec::new(), disconnected: Vec::new() }
+    }
+
+    // Before polling a new frame
+    pub fn begin_frame( &mut self ) {
+        for slot in self.pads.iter_mut() {
+            if slot.as_ref().is_some_and( | pad | !pad.connected ) {
+                *slot = None;
+            }
+            if let Some(pad) = slot {
+                pad.previous = pad.state;
+            }
+        }
+        self.connected.clear();
+        self.disconnected.clear();
+    }
+
+    // Reads every joystick slot, plugging in / out shows up as connected() / disconnected()
+    pub fn poll( &mut self, glfw: &Glfw ) {
+        for slot in 0..MAX_PADS {
+            let joystick = glfw.get_joystick(JoystickId::from_i32(slot as i32).expect("slot below MAX_PADS"));
+            let state = if joystick.is_gamepad() { joystick.get_gamepad_state() } else { None };
+            match state {
+                Some(state) => {
+                    let name = if self.pad(slot).is_none() { joystick.get_gamepad_name().unwrap_or_default() } else { String::new() };
+                    self.set(slot, &name, Some(PadState::from_glfw(&state)));
+                },
+                None => self.set(slot, "", None),
+            }
+        }
+    }
+
+    // Same path poll() takes -> tests and replays inject pads here, `name` is only used when it connects
+    pub fn set( &mut self, slot: usize, name: &str, state: Option<PadState> ) {
+        let current = self.pads[slot].as_mut().filter( | pad | pad.connected );
+        match ( current, state ) {
+            ( Some(pad), Some(state) ) => pad.state = state,
+            ( Some(pad), None ) => {
+                pad.state = PadState::idle();
+                pad.connected = false;
+                self.disconnected.push(slot);
+            },
+            ( None, Some(state) ) => {
+                self.pads[slot] = Some(Gamepad { name: name.to_string(), state, previous: PadState::idle(), connected: true });
+                self.connected.push(slot);
+            },
+            ( None, None ) => {},
+        }
+    }
+
+    pub fn pad( &self, slot: usize ) -> Option<&Gamepad> {
+        self.pads.get(slot)?.as_ref().filter( | pad | pad.connected )
+    }
+
+    pub fn pads(&self) -> impl Iterator<Item = (usize, &Gamepad)> {
+        self.pads.iter().enumerate().filter_map( | ( slot, pad ) | Some(( slot, pad.as_ref().filter( | pad | pad.connected )? )) )
+    }
+
+    // Slots plugged in / out this frame
+    pub fn connected(&self) -> &[usize] {
+        &self.connected
+    }
+
+    pub fn disconnected(&self) -> &[usize] {
+        &self.disconnected
+    }
+
+    // Every pad counts, including one unplugged this frame
+    fn all(&self) -> impl Iterator<Item = &Gamepad> {
+        self.pads.iter().flatten()
+    }
+
+    pub fn button_down( &self, button: GamepadButton ) -> bool {
+        self.all().any( | pad | pad.state.button(button) )
+    }
+
+    pub fn button_pressed( &self, button: GamepadButton ) -> bool {
+        self.all().any( | pad | pad.state.button(button) && !pad.previous.button(button) )
+    }
+
+    // Also when the pad holding it was unplugged
+    pub fn button_released( &self, button: GamepadButton ) -> bool {
+        self.all().any( | pad | !pad.state.button(button) && pad.previous.button(button) ) && !self.button_down(button)
+    }
+
+    // The pad pushed furthest wins, two pads don't add up
+    pub fn axis( &self, axis: GamepadAxis ) -> f32 {
+        self.strongest(axis, | pad | &pad.state)
+    }
+
+    pub fn previous_axis( &self, axis: GamepadAxis ) -> f32 {
+        self.strongest(axis, | pad | &pad.previous)
+    }
+
+    fn strongest<F>( &self, axis: GamepadAxis, state: F ) -> f32
+    where
+        F: Fn(&Gamepad) -> &PadState
+    {
+        self.all()
+            .map( | pad | state(pad).axis(axis, self.deadzone) )
+            .fold(0.0, | best, value | if value.abs() > best.abs() { value } else { best })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl PadState {
+        fn with_button( mut self, button: GamepadButton, down: bool ) -> Self {
+            self.buttons[button as usize] = down;
+            self
+        }
+
+        fn with_axis( mut self, axis: GamepadAxis, value: f32 ) -> Self {
+            self.axes[axis as usize] = value;
+            self
+        }
+    }
+
+    fn close( a: f32, b: f32 ) -> bool {
+        (a - b).abs() < 1e-5
+    }
+
+    #[test]
+    fn stick_deadzone_is_radial() {
+        assert_eq!(stick(0.1, 0.1, 0.2), ( 0.0, 0.0 ));
+        // Each axis alone is inside the zone, the diagonal together is not
+        let ( x, y ) = stick(0.18, 0.18, 0.2);
+        assert!(x > 0.0 && close(x, y));
+        // Output starts at 0 at the edge and reaches 1 at full tilt, direction is kept
+        let ( x, y ) = stick(0.0, -0.6, 0.2);
+        assert!(close(x, 0.0) && close(y, -0.5));
+        let ( x, y ) = stick(0.8, 0.6, 0.2);
+        assert!(close(x, 0.8) && close(y, 0.6));
+        // Pads that report past 1 on the diagonal are clamped to the unit circle
+        let ( x, y ) = stick(1.0, 1.0, 0.2);
+        assert!(close((x * x + y * y).sqrt(), 1.0));
+    }
+
+    #[test]
+    fn triggers_map_to_zero_one() {
+        assert_eq!(trigger(-1.0, 0.1), 0.0);
+        // 0.05 pulled is inside a 0.1 deadzone
+        assert_eq!(trigger(-0.9, 0.1), 0.0);
+        assert!(close(trigger(0.0, 0.1), (0.5 - 0.1) / 0.9));
+        assert_eq!(trigger(1.0, 0.1), 1.0);
+        assert_eq!(trigger(2.0, 0.0), 1.0);
+
+        let pad = PadState::idle().with_axis(GamepadAxis::AxisRightTrigger, 1.0);
+        assert_eq!(pad.axis(GamepadAxis::AxisLeftTrigger, Deadzone::default()), 0.0);
+        assert_eq!(pad.axis(GamepadAxis::AxisRightTrigger, Deadzone::default()), 1.0);
+    }
+
+    #[test]
+    fn pressed_and_released_last_one_frame() {
+        let mut pads = Gamepads::new();
+        pads.set(0, "pad", Some(PadState::idle()));
+        pads.begin_frame();
+
+        pads.set(0, "", Some(PadState::idle().with_button(GamepadButton::ButtonA, true)));
+        assert!(pads.button_pressed(GamepadButton::ButtonA) && pads.button_down(GamepadButton::ButtonA));
+        pads.begin_frame();
+        // Still held -> down, but no new press
+        pads.set(0, "", Some(PadState::idle().with_button(GamepadButton::ButtonA, true)));
+        assert!(!pads.button_pressed(GamepadButton::ButtonA) && pads.button_down(GamepadButton::ButtonA));
+        pads.begin_frame();
+
+        pads.set(0, "", Some(PadState::idle()));
+        assert!(pads.button_released(GamepadButton::ButtonA) && !pads.button_down(GamepadButton::ButtonA));
+        pads.begin_frame();
+        pads.set(0, "", Some(PadState::idle()));
+        assert!(!pads.button_released(GamepadButton::ButtonA));
+    }
+
+    #[test]
+    fn a_second_pad_holding_the_button_blocks_the_release() {
+        let mut pads = Gamepads::new();
+        let held = PadState::idle().with_button(GamepadButton::ButtonB, true);
+        pads.set(0, "one", Some(held));
+        pads.set(1, "two", Some(held));
+        pads.begin_frame();
+        pads.set(0, "", Some(PadState::idle()));
+        pads.set(1, "", Some(held));
+        assert!(!pads.button_released(GamepadButton::ButtonB));
+
+        // The pad pushed furthest wins
+        pads.set(0, "", Some(PadState::idle().with_axis(GamepadAxis::AxisLeftX, -0.9)));
+        pads.set(1, "", Some(held.with_axis(GamepadAxis::AxisLeftX, 0.5)));
+        assert!(pads.axis(GamepadAxis::AxisLeftX) < -0.8);
+    }
+
+    #[test]
+    fn plugging_in_and_out_shows_for_one_frame() {
+        let mut pads = Gamepads::new();
+        pads.set(3, "Xbox Controller", Some(PadState::idle()));
+        assert_eq!(pads.connected(), &[ 3 ]);
+        assert_eq!(pads.pad(3).map( | pad | pad.name.as_str() ), Some("Xbox Controller"));
+        pads.begin_frame();
+        pads.set(3, "", Some(PadState::idle()));
+        assert!(pads.connected().is_empty());
+        assert_eq!(pads.pads().map( | ( slot, _ ) | slot ).collect::<Vec<usize>>(), vec![ 3 ]);
+
+        pads.set(3, "", None);
+        assert_eq!(pads.disconnected(), &[ 3 ]);
+        assert!(pads.pad(3).is_none());
+        pads.begin_frame();
+        pads.set(3, "", None);
+        assert!(pads.disconnected().is_empty());
+        assert_eq!(pads.pads().count(), 0);
+    }
+
+    #[test]
+    fn unplugging_releases_what_the_pad_held() {
+        let mut pads = Gamepads::new();
+        pads.set(0, "pad", Some(PadState::idle().with_button(GamepadButton::ButtonX, true).with_axis(GamepadAxis::AxisLeftY, 1.0)));
+        pads.begin_frame();
+        pads.set(0, "", Some(PadState::idle().with_button(GamepadButton::ButtonX, true).with_axis(GamepadAxis::AxisLeftY, 1.0)));
+        pads.begin_frame();
+
+        pads.set(0, "", None);
+        assert!(pads.button_released(GamepadButton::ButtonX));
+        assert!(!pads.button_down(GamepadButton::ButtonX));
+        assert_eq!(pads.axis(GamepadAxis::AxisLeftY), 0.0);
+        assert_eq!(pads.previous_axis(GamepadAxis::AxisLeftY), 1.0);
+
+        // Gone the frame after, a new pad in the slot starts from rest
+        pads.begin_frame();
+        pads.set(0, "other", Some(PadState::idle().with_button(GamepadButton::ButtonX, true)));
+        assert_eq!(pads.connected(), &[ 0 ]);
+        assert!(pads.button_pressed(GamepadButton::ButtonX));
+    }
+}
@@ -0,0 +1,283 @@
+// Input -> keyboard / mouse / gamepad state of the current frame, built from window events and pad polling
+// pressed / released stay true until the next update has seen them, down stays true while held
+use std::collections::HashSet;
+
+use glfw::{Action, GamepadButton, Key, MouseButton, WindowEvent};
+
+use crate::input::{actions::{ActionMap, Binding}, gamepad::{Gamepads, BUTTON_COUNT}};
+
+// A stick half or trigger counts as down past this, action_value() still gets the analog value
+pub const AXIS_PRESS: f32 = 0.5;
+
+pub struct Input {
+    pub actions: ActionMap,
+    pub gamepads: Gamepads,
+    // Keys and mouse buttons, pads are read from `gamepads`
+    down: HashSet<Binding>,
+    pressed: HashSet<Binding>,
+    released: HashSet<Binding>,
+    // Same as `pressed` but in order -> "press a key to bind" prompts take the first
+    pressed_order: Vec<Binding>,
+    // Screen coordinates, origin top left
+    cursor: (f64, f64),
+    cursor_delta: (f64, f64),
+    // The first position after entering has no previous one, so no delta
+    cursor_known: bool,
+    scroll: (f64, f64),
+}
+
+impl Input {
+    pub fn new( actions: ActionMap ) -> Self {
+        Self {
+            actions,
+            gamepads: Gamepads::new(),
+            down: HashSet::new(),
+            pressed: HashSet::new(),
+            released: HashSet::new(),
+            pressed_order: Vec::new(),
+            cursor: ( 0.0, 0.0 ),
+            cursor_delta: ( 0.0, 0.0 ),
+            cursor_known: false,
+            scroll: ( 0.0, 0.0 ),
+        }
+    }
+
+    // Once an update has seen the input -> forgets its presses, releases and deltas
+    // Frames without an update keep them, so a quick tap between two fixed steps isn't lost
+    pub fn begin_frame( &mut self ) {
+        self.gamepads.begin_frame();
+        self.pressed.clear();
+        self.released.clear();
+        self.pressed_order.clear();
+        self.cursor_delta = ( 0.0, 0.0 );
+        self.scroll = ( 0.0, 0.0 );
+    }
+
+    // Everything else is ignored, apps still get every event through on_event()
+    pub fn handle_event( &mut self, event: &WindowEvent ) {
+        match *event {
+            WindowEvent::Key(Key::Unknown, ..) => {},
+            WindowEvent::Key(key, _, Action::Press, _) => self.press(Binding::Key(key)),
+            WindowEvent::Key(key, _, Action::Release, _) => self.release(Binding::Key(key)),
+            WindowEvent::MouseButton(button, Action::Press, _) => self.press(Binding::Mouse(button)),
+            WindowEvent::MouseButton(button, Action::Release, _) => self.release(Binding::Mouse(button)),
+            WindowEvent::CursorPos(x, y) => self.move_cursor(x, y),
+            WindowEvent::CursorEnter(false) => self.cursor_known = false,
+            WindowEvent::Scroll(x, y) => {
+                self.scroll.0 += x;
+                self.scroll.1 += y;
+            },
+            // Releases would go to the other window -> nothing may stay stuck
+            WindowEvent::Focus(false) => self.release_all(),
+            _ => {},
+        }
+    }
+
+    // Also how tests and replays feed input without a window
+    pub fn press( &mut self, binding: Binding ) {
+        // Already down -> a second Press after a missed Release, not a new press
+        if self.down.insert(binding) {
+            self.pressed.insert(binding);
+            self.pressed_order.push(binding);
+        }
+    }
+
+    pub fn release( &mut self, binding: Binding ) {
+        if self.down.remove(&binding) {
+            self.released.insert(binding);
+        }
+    }
+
+    pub fn release_all( &mut self ) {
+        for binding in self.down.drain().collect::<Vec<Binding>>() {
+            self.released.insert(binding);
+        }
+    }
+
+    pub fn move_cursor( &mut self, x: f64, y: f64 ) {
+        if self.cursor_known {
+            self.cursor_delta.0 += x - self.cursor.0;
+            self.cursor_delta.1 += y - self.cursor.1;
+        }
+        self.cursor = ( x, y );
+        self.cursor_known = true;
+    }
+
+    pub fn down( &self, binding: Binding ) -> bool {
+        match binding {
+            Binding::Pad(button) => self.gamepads.button_down(button),
+            Binding::Axis(axis, direction) => self.gamepads.axis(axis) * direction.sign() > AXIS_PRESS,
+            _ => self.down.contains(&binding),
+        }
+    }
+
+    pub fn pressed( &self, binding: Binding ) -> bool {
+        match binding {
+            Binding::Pad(button) => self.gamepads.button_pressed(button),
+            Binding::Axis(axis, direction) => {
+                self.down(binding) && self.gamepads.previous_axis(axis) * direction.sign() <= AXIS_PRESS
+            },
+            _ => self.pressed.contains(&binding),
+        }
+    }
+
+    pub fn released( &self, binding: Binding ) -> bool {
+        match binding {
+            Binding::Pad(button) => self.gamepads.button_released(button),
+            Binding::Axis(axis, direction) => {
+                !self.down(binding) && self.gamepads.previous_axis(axis) * direction.sign() > AXIS_PRESS
+            },
+            _ => self.released.contains(&binding),
+        }
+    }
+
+    // 0..1 -> how far a stick half or trigger is pushed, 1 for anything else that is down
+    pub fn value( &self, binding: Binding ) -> f32 {
+        match binding {
+            Binding::Axis(axis, direction) => (self.gamepads.axis(axis) * direction.sign()).max(0.0),
+            _ => if self.down(binding) { 1.0 } else { 0.0 },
+        }
+    }
+
+    pub fn button_down( &self, button: MouseButton ) -> bool {
+        self.down(Binding::Mouse(button))
+    }
+
+    pub fn button_pressed( &self, button: MouseButton ) -> bool {
+        self.pressed(Binding::Mouse(button))
+    }
+
+    pub fn cursor(&self) -> (f64, f64) {
+        self.cursor
+    }
+
+    // Movement since the last frame
+    pub fn cursor_delta(&self) -> (f64, f64) {
+        self.cursor_delta
+    }
+
+    // Wheel / touchpad offset this frame, y is the usual wheel
+    pub fn scroll(&self) -> (f64, f64) {
+        self.scroll
+    }
+
+    // First key or button pressed this frame, keys and mouse before pad buttons
+    pub fn first_pressed(&self) -> Option<Binding> {
+        self.pressed_order.first().copied().or_else( || {
+            (0..BUTTON_COUNT as i32)
+                .filter_map(GamepadButton::from_i32)
+                .map(Binding::Pad)
+                .find( | binding | self.pressed(*binding) )
+        })
+    }
+
+    // Strongest binding wins -> keyboard gives 1, a half pushed stick 0.5
+    pub fn action_value( &self, action: &str ) -> f32 {
+        self.actions.bindings(action).iter().map( | binding | self.value(*binding) ).fold(0.0, f32::max)
+    }
+
+    pub fn action_pressed( &self, action: &str ) -> bool {
+        self.actions.bindings(action).iter().any( | binding | self.pressed(*binding) )
+    }
+
+    // Only once the last of its bindings lets go
+    pub fn action_released( &self, action: &str ) -> bool {
+        let bindings = self.actions.bindings(action);
+        bindings.iter().any( | binding | self.released(*binding) ) && !bindings.iter().any( | binding | self.down(*binding) )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use glfw::Modifiers;
+
+    fn key( key: Key, action: Action ) -> WindowEvent {
+        WindowEvent::Key(key, 0, action, Modifiers::empty())
+    }
+
+    #[test]
+    fn pressed_and_released_last_one_frame_down_lasts_while_held() {
+        let mut input = Input::new(ActionMap::defaults());
+        input.handle_event(&key(Key::W, Action::Press));
+        assert!(input.pressed(Binding::Key(Key::W)) && input.down(Binding::Key(Key::W)));
+        assert!(input.action_pressed("move_forward"));
+        assert_eq!(input.action_value("move_forward"), 1.0);
+
+        // Held -> no new press, key repeats don't count either
+        input.begin_frame();
+        input.handle_event(&key(Key::W, Action::Repeat));
+        input.handle_event(&key(Key::W, Action::Press));
+        assert!(!input.pressed(Binding::Key(Key::W)) && input.down(Binding::Key(Key::W)));
+
+        input.begin_frame();
+        input.handle_event(&key(Key::W, Action::Release));
+        assert!(input.released(Binding::Key(Key::W)) && !input.down(Binding::Key(Key::W)));
+        assert!(input.action_released("move_forward"));
+
+        input.begin_frame();
+        assert!(!input.released(Binding::Key(Key::W)));
+        assert_eq!(input.action_value("move_forward"), 0.0);
+    }
+
+    #[test]
+    fn a_tap_between_two_updates_is_not_lost() {
+        let mut input = Input::new(ActionMap::defaults());
+        input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Press, Modifiers::empty()));
+        input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Release, Modifiers::empty()));
+        assert!(input.button_pressed(MouseButton::Button1) && input.released(Binding::Mouse(MouseButton::Button1)));
+        assert!(!input.button_down(MouseButton::Button1));
+    }
+
+    #[test]
+    fn action_released_waits_for_the_last_binding() {
+        let mut input = Input::new(ActionMap::defaults());
+        input.press(Binding::Key(Key::W));
+        input.press(Binding::Key(Key::Up));
+        input.begin_frame();
+        input.release(Binding::Key(Key::W));
+        assert!(!input.action_released("move_forward"));
+        input.begin_frame();
+        input.release(Binding::Key(Key::Up));
+        assert!(input.action_released("move_forward"));
+    }
+
+    #[test]
+    fn scroll_and_cursor_delta_reset_every_frame() {
+        let mut input = Input::new(ActionMap::defaults());
+        input.handle_event(&WindowEvent::Scroll(0.0, 1.0));
+        input.handle_event(&WindowEvent::Scroll(0.5, 2.0));
+        assert_eq!(input.scroll(), ( 0.5, 3.0 ));
+        // The first position has nothing to compare with
+        input.move_cursor(10.0, 10.0);
+        input.move_cursor(13.0, 6.0);
+        assert_eq!(input.cursor_delta(), ( 3.0, -4.0 ));
+
+        input.begin_frame();
+        assert_eq!(input.scroll(), ( 0.0, 0.0 ));
+        assert_eq!(input.cursor_delta(), ( 0.0, 0.0 ));
+        assert_eq!(input.cursor(), ( 13.0, 6.0 ));
+    }
+
+    #[test]
+    fn losing_focus_releases_everything() {
+        let mut input = Input::new(ActionMap::defaults());
+        input.press(Binding::Key(Key::A));
+        input.press(Binding::Mouse(MouseButton::Button2));
+        input.begin_frame();
+        input.handle_event(&WindowEvent::Focus(false));
+        assert!(input.released(Binding::Key(Key::A)) && input.released(Binding::Mouse(MouseButton::Button2)));
+        assert!(!input.down(Binding::Key(Key::A)));
+    }
+
+    #[test]
+    fn first_pressed_keeps_the_event_order() {
+        let mut input = Input::new(ActionMap::defaults());
+        input.press(Binding::Key(Key::I));
+        input.press(Binding::Key(Key::F3));
+        assert_eq!(input.first_pressed(), Some(Binding::Key(Key::I)));
+        input.begin_frame();
+        assert_eq!(input.first_pressed(), None);
+    }
+}
@@ -0,0 +1,650 @@
+// glTF 2.0 loading -> .gltf ( + .bin or base64 buffers ) and binary .glb
+use std::{fs, path::{Path, PathBuf}};
+
+use glm::{mat4, Mat4};
+
+use crate::{
+    logging::logging::ASSETS,
+    mesh::mesh::MeshData,
+    scene::transform::{identity, Quat, Transform},
+    textures::texture::Texture,
+    utils::json::Json
+};
+
+const GLB_MAGIC: u32 = 0x4654_6C67; // "glTF"
+const CHUNK_JSON: u32 = 0x4E4F_534A;
+const CHUNK_BIN: u32 = 0x004E_4942;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMode {
+    Opaque,
+    Mask,
+    Blend,
+}
+
+// Texture reference of a material: index into `GltfScene::textures`.
+// Only TEXCOORD_0 is imported, materials that ask for another UV set are rejected
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureRef {
+    pub texture: usize,
+}
+
+// Metallic-roughness PBR parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: String,
+    pub base_color_factor: [f32; 4],
+    pub base_color_texture: Option<TextureRef>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub metallic_roughness_texture: Option<TextureRef>,
+    pub normal_texture: Option<TextureRef>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<TextureRef>,
+    pub occlusion_strength: f32,
+    pub emissive_texture: Option<TextureRef>,
+    pub emissive_factor: [f32; 3],
+    pub alpha_mode: AlphaMode,
+    pub alpha_cutoff: f32,
+    pub double_sided: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GltfImage {
+    // External file, resolved relative to the .gltf
+    Path(String),
+    // Embedded in a buffer view or a data URI
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfPrimitive {
+    pub mesh: MeshData,
+    pub material: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMesh {
+    pub name: String,
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+// Local transform is either a matrix or translation / rotation ( x, y, z, w ) / scale
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfNode {
+    pub name: String,
+    pub mesh: Option<usize>,
+    pub children: Vec<usize>,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+    pub matrix: Option<[f32; 16]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GltfScene {
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<GltfMaterial>,
+    pub nodes: Vec<GltfNode>,
+    // Top level nodes of the default scene
+    pub roots: Vec<usize>,
+    pub images: Vec<GltfImage>,
+    // texture index -> image index
+    pub textures: Vec<usize>,
+}
+
+impl Default for GltfMaterial {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color_factor: [ 1.0, 1.0, 1.0, 1.0 ],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_texture: None,
+            emissive_factor: [ 0.0, 0.0, 0.0 ],
+            alpha_mode: AlphaMode::Opaque,
+            alpha_cutoff: 0.5,
+            double_sided: false,
+        }
+    }
+}
+
+impl GltfNode {
+    pub fn local_matrix(&self) -> Mat4 {
+        if let Some(m) = self.matrix {
+            // glTF matrices are column major, like glm
+            return mat4(
+                m[0], m[1], m[2], m[3],
+                m[4], m[5], m[6], m[7],
+                m[8], m[9], m[10], m[11],
+                m[12], m[13], m[14], m[15],
+            );
+        }
+        let [ x, y, z, w ] = self.rotation;
+        Transform {
+            translation: glm::vec3(self.translation[0], self.translation[1], self.translation[2]),
+            rotation: Quat::new(x, y, z, w),
+            scale: glm::vec3(self.scale[0], self.scale[1], self.scale[2]),
+        }.matrix()
+    }
+}
+
+impl GltfScene {
+    // World matrix of every node, parents first. glTF nodes form trees -> a node that is
+    // reached twice ( a cycle or two parents ) or an index past the end is an Err
+    pub fn world_matrices(&self) -> Result<Vec<Mat4>, String> {
+        let mut world = vec![ identity(); self.nodes.len() ];
+        let mut visited = vec![ false; self.nodes.len() ];
+        let mut stack = self.roots.iter().map( | root | ( *root, None ) ).collect::<Vec<(usize, Option<usize>)>>();
+        while let Some(( node, parent )) = stack.pop() {
+            let Some(gltf_node) = self.nodes.get(node) else {
+                return Err(format!("missing node {}", node));
+            };
+            if std::mem::replace(&mut visited[node], true) {
+                return Err(format!("nodes[{}] is reached twice, the node hierarchy is not a tree", node));
+            }
+            let local = gltf_node.local_matrix();
+            world[node] = match parent {
+                Some(parent) => world[parent] * local,
+                None => local,
+            };
+            stack.extend(gltf_node.children.iter().map( | child | ( *child, Some(node) ) ));
+        }
+        Ok(world)
+    }
+
+    // Every index between meshes, materials, textures and images points at something
+    pub fn validate(&self) -> Result<(), String> {
+        for ( index, node ) in self.nodes.iter().enumerate() {
+            if let Some(mesh) = node.mesh && mesh >= self.meshes.len() {
+                return Err(format!("nodes[{}] points to missing mesh {}", index, mesh));
+            }
+        }
+        for ( index, mesh ) in self.meshes.iter().enumerate() {
+            for ( primitive_index, primitive ) in mesh.primitives.iter().enumerate() {
+                if let Some(material) = primitive.material && material >= self.materials.len() {
+                    return Err(format!("meshes[{}].primitives[{}] points to missing material {}", index, primitive_index, material));
+                }
+            }
+        }
+        for ( index, material ) in self.materials.iter().enumerate() {
+            let references = [
+                material.base_color_texture,
+                material.metallic_roughness_texture,
+                material.normal_texture,
+                material.occlusion_texture,
+                material.emissive_texture,
+            ];
+            if let Some(reference) = references.iter().flatten().find( | reference | reference.texture >= self.textures.len() ) {
+                return Err(format!("materials[{}] points to missing texture {}", index, reference.texture));
+            }
+        }
+        if let Some(( index, image )) = self.textures.iter().enumerate().find( | ( _, image ) | **image >= self.images.len() ) {
+            return Err(format!("textures[{}] points to missing image {}", index, image));
+        }
+        self.world_matrices().map( | _ | () )
+    }
+
+    // Creates one `Texture` per glTF texture, needs a current GL context
+    pub fn load_textures(&self) -> Result<Vec<Texture>, String> {
+        self.textures
+            .iter()
+            .map( | image | match self.images.get(*image) {
+                Some(GltfImage::Path(path)) => Texture::new(path),
+                Some(GltfImage::Bytes(bytes)) => Texture::from_memory(bytes),
+                None => Err(format!("missing image {}", image)),
+            })
+            .collect()
+    }
+}
+
+// Picks .glb or .gltf from the file contents, external files are relative to `path`
+pub fn load_gltf( path: &str ) -> Result<GltfScene, String> {
+    let bytes = fs::read(path).map_err( | err | format!("{}: {}", path, err) )?;
+    let base_dir = Path::new(path).parent().unwrap_or(Path::new("")).to_path_buf();
+    parse_gltf(&bytes, &base_dir).map_err( | err | format!("{}: {}", path, err) )
+}
+
+pub fn parse_gltf( bytes: &[u8], base_dir: &Path ) -> Result<GltfScene, String> {
+    let ( json, bin ) = if read_u32(bytes, 0) == Some(GLB_MAGIC) {
+        split_glb(bytes)?
+    } else {
+        let text = std::str::from_utf8(bytes).map_err( | _ | "glTF JSON is not valid UTF-8".to_string() )?;
+        ( text.to_string(), None )
+    };
+    let root = Json::parse(&json)?;
+    Importer { root: &root, base_dir: base_dir.to_path_buf(), bin, buffers: Vec::new() }.import()
+}
+
+fn read_u32( bytes: &[u8], offset: usize ) -> Option<u32> {
+    let slice = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([ slice[0], slice[1], slice[2], slice[3] ]))
+}
+
+// 12 byte header, then a JSON chunk and an optional BIN chunk
+fn split_glb( bytes: &[u8] ) -> Result<(String, Option<Vec<u8>>), String> {
+    let version = read_u32(bytes, 4).ok_or("GLB header is truncated")?;
+    if version != 2 {
+        return Err(format!("GLB version {} is not supported", version));
+    }
+    let total = (read_u32(bytes, 8).ok_or("GLB header is truncated")? as usize).min(bytes.len());
+
+    let ( mut json, mut bin ) = ( None, None );
+    let mut offset = 12;
+    while offset + 8 <= total {
+        let length = read_u32(bytes, offset).unwrap() as usize;
+        let kind = read_u32(bytes, offset + 4).unwrap();
+        let data = bytes.get(offset + 8..offset + 8 + length).ok_or("GLB chunk runs past the end of the file")?;
+        match kind {
+            CHUNK_JSON => json = Some(String::from_utf8(data.to_vec()).map_err( | _ | "GLB JSON chunk is not valid UTF-8".to_string() )?),
+            CHUNK_BIN if bin.is_none() => bin = Some(data.to_vec()),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    Ok(( json.ok_or("GLB file has no JSON chunk")?, bin ))
+}
+
+// `data:<mime>;base64,<payload>` -> bytes
+fn decode_data_uri( uri: &str ) -> Result<Vec<u8>, String> {
+    let ( header, payload ) = uri.split_once(',').ok_or("data URI without ','")?;
+    if !header.ends_with(";base64") {
+        return Err("only base64 data URIs are supported".to_string());
+    }
+    decode_base64(payload)
+}
+
+pub fn decode_base64( text: &str ) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let ( mut accumulator, mut bits ) = ( 0u32, 0 );
+    for character in text.bytes() {
+        let value = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            b'\r' | b'\n' | b' ' => continue,
+            _ => return Err(format!("invalid base64 character '{}'", character as char)),
+        };
+        accumulator = (accumulator << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((accumulator >> bits) as u8);
+        }
+    }
+    Ok(bytes)
+}
+
+struct Importer<'a> {
+    root: &'a Json,
+    base_dir: PathBuf,
+    bin: Option<Vec<u8>>,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<'a> Importer<'a> {
+    fn array( &self, key: &str ) -> &'a [Json] {
+        self.root.get(key).and_then(Json::as_array).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn import( mut self ) -> Result<GltfScene, String> {
+        let version = self.root.get("asset").and_then( | asset | asset.get("version") ).and_then(Json::as_str).unwrap_or("");
+        if !version.starts_with('2') {
+            return Err(format!("glTF version '{}' is not supported", version));
+        }
+
+        for ( index, buffer ) in self.array("buffers").iter().enumerate() {
+            let data = match buffer.get("uri").and_then(Json::as_str) {
+                Some(uri) if uri.starts_with("data:") => decode_data_uri(uri).map_err( | err | format!("buffers[{}]: {}", index, err) )?,
+                Some(uri) => {
+                    let path = self.base_dir.join(uri);
+                    fs::read(&path).map_err( | err | format!("buffers[{}] {}: {}", index, path.display(), err) )?
+                },
+                // The first buffer without an uri is the GLB BIN chunk
+                None => self.bin.take().ok_or_else( || format!("buffers[{}] has no uri and there is no GLB BIN chunk", index) )?,
+            };
+            self.buffers.push(data);
+        }
+
+        let mut scene = GltfScene::default();
+
+        for ( index, image ) in self.array("images").iter().enumerate() {
+            let image = if let Some(uri) = image.get("uri").and_then(Json::as_str) {
+                if uri.starts_with("data:") {
+                    GltfImage::Bytes(decode_data_uri(uri).map_err( | err | format!("images[{}]: {}", index, err) )?)
+                } else {
+                    GltfImage::Path(self.base_dir.join(uri).to_string_lossy().into_owned())
+                }
+            } else {
+                let view = image.get("bufferView").and_then(Json::as_usize).ok_or_else( || format!("images[{}] has neither uri nor bufferView", index) )?;
+                GltfImage::Bytes(self.buffer_view(view)?.to_vec())
+            };
+            scene.images.push(image);
+        }
+
+        for ( index, texture ) in self.array("textures").iter().enumerate() {
+            let source = texture.get("source").and_then(Json::as_usize).ok_or_else( || format!("textures[{}] has no source image", index) )?;
+            scene.textures.push(source);
+        }
+
+        for ( index, material ) in self.array("materials").iter().enumerate() {
+            scene.materials.push(Self::material(material).map_err( | err | format!("materials[{}]: {}", index, err) )?);
+        }
+
+        for ( index, mesh ) in self.array("meshes").iter().enumerate() {
+            let mut primitives = Vec::new();
+            for ( primitive_index, primitive ) in mesh.get("primitives").and_then(Json::as_array).map(Vec::as_slice).unwrap_or(&[]).iter().enumerate() {
+                let primitive = self.primitive(primitive).map_err( | err | format!("meshes[{}].primitives[{}]: {}", index, primitive_index, err) )?;
+                primitives.extend(primitive);
+            }
+            scene.meshes.push(GltfMesh {
+                name: mesh.get("name").and_then(Json::as_str).unwrap_or("").to_string(),
+                primitives,
+            });
+        }
+
+        for node in self.array("nodes") {
+            scene.nodes.push(GltfNode {
+                name: node.get("name").and_then(Json::as_str).unwrap_or("").to_string(),
+                mesh: node.get("mesh").and_then(Json::as_usize),
+                children: node.get("children").and_then(Json::as_array).map( | children | children.iter().filter_map(Json::as_usize).collect() ).unwrap_or_default(),
+                translation: node.get("translation").and_then(Json::as_f32_array::<3>).unwrap_or([ 0.0, 0.0, 0.0 ]),
+                rotation: node.get("rotation").and_then(Json::as_f32_array::<4>).unwrap_or([ 0.0, 0.0, 0.0, 1.0 ]),
+                scale: node.get("scale").and_then(Json::as_f32_array::<3>).unwrap_or([ 1.0, 1.0, 1.0 ]),
+                matrix: node.get("matrix").and_then(Json::as_f32_array::<16>),
+            });
+        }
+        for ( index, node ) in scene.nodes.iter().enumerate() {
+            if let Some(child) = node.children.iter().find( | child | **child >= scene.nodes.len() ) {
+                return Err(format!("nodes[{}] has missing child {}", index, child));
+            }
+        }
+
+        // Default scene roots, or every node nobody points to
+        let scene_index = self.root.get("scene").and_then(Json::as_usize).unwrap_or(0);
+        scene.roots = match self.array("scenes").get(scene_index).and_then( | s | s.get("nodes") ).and_then(Json::as_array) {
+            Some(nodes) => nodes.iter().filter_map(Json::as_usize).filter( | node | *node < scene.nodes.len() ).collect(),
+            None => (0..scene.nodes.len()).filter( | node | !scene.nodes.iter().any( | other | other.children.contains(node) ) ).collect(),
+        };
+
+        scene.validate()?;
+        Ok(scene)
+    }
+
+    fn texture_ref( info: Option<&Json> ) -> Result<Option<TextureRef>, String> {
+        let Some(info) = info else { return Ok(None) };
+        let tex_coord = info.get("texCoord").and_then(Json::as_usize).unwrap_or(0);
+        if tex_coord != 0 {
+            return Err(format!("texCoord {} is not supported, only TEXCOORD_0 is imported", tex_coord));
+        }
+        Ok(info.get("index").and_then(Json::as_usize).map( | texture | TextureRef { texture } ))
+    }
+
+    fn material( material: &Json ) -> Result<GltfMaterial, String> {
+        let defaults = GltfMaterial::default();
+        let pbr = material.get("pbrMetallicRoughness");
+        let pbr_value = | key: &str | pbr.and_then( | pbr | pbr.get(key) );
+        Ok(GltfMaterial {
+            name: material.get("name").and_then(Json::as_str).unwrap_or("").to_string(),
+            base_color_factor: pbr_value("baseColorFactor").and_then(Json::as_f32_array::<4>).unwrap_or(defaults.base_color_factor),
+            base_color_texture: Self::texture_ref(pbr_value("baseColorTexture"))?,
+            metallic_factor: pbr_value("metallicFactor").and_then(Json::as_f32).unwrap_or(defaults.metallic_factor),
+            roughness_factor: pbr_value("roughnessFactor").and_then(Json::as_f32).unwrap_or(defaults.roughness_factor),
+            metallic_roughness_texture: Self::texture_ref(pbr_value("metallicRoughnessTexture"))?,
+            normal_texture: Self::texture_ref(material.get("normalTexture"))?,
+            normal_scale: material.get("normalTexture").and_then( | t | t.get("scale") ).and_then(Json::as_f32).unwrap_or(1.0),
+            occlusion_texture: Self::texture_ref(material.get("occlusionTexture"))?,
+            occlusion_strength: material.get("occlusionTexture").and_then( | t | t.get("strength") ).and_then(Json::as_f32).unwrap_or(1.0),
+            emissive_texture: Self::texture_ref(material.get("emissiveTexture"))?,
+            emissive_factor: material.get("emissiveFactor").and_then(Json::as_f32_array::<3>).unwrap_or(defaults.emissive_factor),
+            alpha_mode: match material.get("alphaMode").and_then(Json::as_str) {
+                Some("MASK") => AlphaMode::Mask,
+                Some("BLEND") => AlphaMode::Blend,
+                _ => AlphaMode::Opaque,
+            },
+            alpha_cutoff: material.get("alphaCutoff").and_then(Json::as_f32).unwrap_or(defaults.alpha_cutoff),
+            double_sided: material.get("doubleSided").and_then(Json::as_bool).unwrap_or(false),
+        })
+    }
+
+    fn buffer_view( &self, index: usize ) -> Result<&[u8], String> {
+        let view = self.array("bufferViews").get(index).ok_or_else( || format!("missing bufferView {}", index) )?;
+        let buffer = view.get("buffer").and_then(Json::as_usize).ok_or_else( || format!("bufferViews[{}] has no buffer", index) )?;
+        let offset = view.get("byteOffset").and_then(Json::as_usize).unwrap_or(0);
+        let length = view.get("byteLength").and_then(Json::as_usize).ok_or_else( || format!("bufferViews[{}] has no byteLength", index) )?;
+        self.buffers
+            .get(buffer)
+            .and_then( | data | data.get(offset..offset + length) )
+            .ok_or_else( || format!("bufferViews[{}] is outside of its buffer", index) )
+    }
+
+    // Every element of an accessor as floats ( normalized integers become 0..1 / -1..1 )
+    fn read_accessor( &self, index: usize ) -> Result<(Vec<f32>, usize), String> {
+        let accessor = self.array("accessors").get(index).ok_or_else( || format!("missing accessor {}", index) )?;
+        if accessor.get("sparse").is_some() {
+            return Err(format!("accessors[{}]: sparse accessors are not supported", index));
+        }
+        let count = accessor.get("count").and_then(Json::as_usize).ok_or_else( || format!("accessors[{}] has no count", index) )?;
+        let components = match accessor.get("type").and_then(Json::as_str) {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            Some("MAT4") => 16,
+            other => return Err(format!("accessors[{}]: unsupported type {:?}", index, other)),
+        };
+        let component_type = accessor.get("componentType").and_then(Json::as_usize).unwrap_or(0);
+        let normalized = accessor.get("normalized").and_then(Json::as_bool).unwrap_or(false);
+        let component_size = match component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            _ => return Err(format!("accessors[{}]: unsupported componentType {}", index, component_type)),
+        };
+
+        // Accessor without a buffer view is all zeros
+        let Some(view_index) = accessor.get("bufferView").and_then(Json::as_usize) else {
+            return Ok(( vec![ 0.0; count * components ], components ));
+        };
+        let view = self.buffer_view(view_index)?;
+        let stride = self.array("bufferViews")[view_index].get("byteStride").and_then(Json::as_usize).unwrap_or(components * component_size);
+        let offset = accessor.get("byteOffset").and_then(Json::as_usize).unwrap_or(0);
+
+        let mut values = Vec::with_capacity(count * components);
+        for element in 0..count {
+            for component in 0..components {
+                let start = offset + element * stride + component * component_size;
+                let bytes = view.get(start..start + component_size).ok_or_else( || format!("accessors[{}] reads past its bufferView", index) )?;
+                let value = match component_type {
+                    5120 => { let v = bytes[0] as i8 as f32; if normalized { (v / 127.0).max(-1.0) } else { v } },
+                    5121 => { let v = bytes[0] as f32; if normalized { v / 255.0 } else { v } },
+                    5122 => { let v = i16::from_le_bytes([ bytes[0], bytes[1] ]) as f32; if normalized { (v / 32767.0).max(-1.0) } else { v } },
+                    5123 => { let v = u16::from_le_bytes([ bytes[0], bytes[1] ]) as f32; if normalized { v / 65535.0 } else { v } },
+                    5125 => u32::from_le_bytes([ bytes[0], bytes[1], bytes[2], bytes[3] ]) as f32,
+                    _ => f32::from_le_bytes([ bytes[0], bytes[1], bytes[2], bytes[3] ]),
+                };
+                values.push(value);
+            }
+        }
+        Ok(( values, components ))
+    }
+
+    // Indices are read separately so large u32 values don't lose precision through f32
+    fn read_indices( &self, index: usize ) -> Result<Vec<u32>, String> {
+        let accessor = self.array("accessors").get(index).ok_or_else( || format!("missing accessor {}", index) )?;
+        let count = accessor.get("count").and_then(Json::as_usize).unwrap_or(0);
+        let component_type = accessor.get("componentType").and_then(Json::as_usize).unwrap_or(0);
+        let size = match component_type {
+            5121 => 1,
+            5123 => 2,
+            5125 => 4,
+            _ => return Err(format!("accessors[{}]: indices must be unsigned integers", index)),
+        };
+        let view_index = accessor.get("bufferView").and_then(Json::as_usize).ok_or_else( || format!("accessors[{}] has no bufferView", index) )?;
+        let view = self.buffer_view(view_index)?;
+        let offset = accessor.get("byteOffset").and_then(Json::as_usize).unwrap_or(0);
+
+        (0..count)
+            .map( | element | {
+                let start = offset + element * size;
+                let bytes = view.get(start..start + size).ok_or_else( || format!("accessors[{}] reads past its bufferView", index) )?;
+                Ok(match size {
+                    1 => bytes[0] as u32,
+                    2 => u16::from_le_bytes([ bytes[0], bytes[1] ]) as u32,
+                    _ => u32::from_le_bytes([ bytes[0], bytes[1], bytes[2], bytes[3] ]),
+                })
+            })
+            .collect()
+    }
+
+    // Only triangle lists are imported, other modes are skipped with a warning
+    fn primitive( &self, primitive: &Json ) -> Result<Option<GltfPrimitive>, String> {
+        let mode = primitive.get("mode").and_then(Json::as_usize).unwrap_or(4);
+        if mode != 4 {
+            log::warn!(target: ASSETS, "glTF primitive mode {} skipped, only TRIANGLES are supported", mode);
+            return Ok(None);
+        }
+        let attributes = primitive.get("attributes").ok_or("primitive has no attributes")?;
+        let attribute = | name: &str | attributes.get(name).and_then(Json::as_usize);
+
+        let position_accessor = attribute("POSITION").ok_or("primitive has no POSITION")?;
+        let ( positions, _ ) = self.read_accessor(position_accessor)?;
+        let mut mesh = MeshData::new();
+        mesh.positions = positions.chunks_exact(3).map( | p | [ p[0], p[1], p[2] ] ).collect();
+        let vertex_count = mesh.positions.len();
+
+        mesh.indices = match primitive.get("indices").and_then(Json::as_usize) {
+            Some(accessor) => self.read_indices(accessor)?,
+            None => (0..vertex_count as u32).collect(),
+        };
+        if let Some(index) = mesh.indices.iter().find( | index | **index as usize >= vertex_count ) {
+            return Err(format!("index {} is out of range ( {} vertices )", index, vertex_count));
+        }
+
+        if let Some(accessor) = attribute("TEXCOORD_0") {
+            let ( uvs, _ ) = self.read_accessor(accessor)?;
+            // glTF has V going down, our images are flipped on load so V goes up
+            mesh.uvs = uvs.chunks_exact(2).map( | t | [ t[0], 1.0 - t[1] ] ).collect();
+        } else {
+            mesh.uvs = vec![ [ 0.0, 0.0 ]; vertex_count ];
+        }
+
+        match attribute("NORMAL") {
+            Some(accessor) => {
+                let ( normals, _ ) = self.read_accessor(accessor)?;
+                mesh.normals = normals.chunks_exact(3).map( | n | [ n[0], n[1], n[2] ] ).collect();
+            },
+            None => mesh.compute_normals(),
+        }
+
+        match attribute("TANGENT") {
+            Some(accessor) => {
+                let ( tangents, _ ) = self.read_accessor(accessor)?;
+                // Flipping V above also flips the bitangent
+                mesh.tangents = tangents.chunks_exact(4).map( | t | [ t[0], t[1], t[2], -t[3] ] ).collect();
+            },
+            None if attribute("TEXCOORD_0").is_some() => mesh.compute_tangents(),
+            None => {}
+        }
+
+        // Tangents are optional, a generated set always matches
+        let tangents_match = mesh.tangents.is_empty() || mesh.tangents.len() == vertex_count;
+        if mesh.normals.len() != vertex_count || mesh.uvs.len() != vertex_count || !tangents_match {
+            return Err("attribute counts don't match POSITION".to_string());
+        }
+
+        Ok(Some(GltfPrimitive {
+            mesh,
+            material: primitive.get("material").and_then(Json::as_usize),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample( name: &str ) -> GltfScene {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/assets/models").join(name);
+        load_gltf(&path.to_string_lossy()).unwrap()
+    }
+
+    fn counts( scene: &GltfScene ) -> Vec<(usize, usize)> {
+        scene.meshes.iter().flat_map( | mesh | &mesh.primitives ).map( | p | ( p.mesh.vertex_count(), p.mesh.indices.len() ) ).collect()
+    }
+
+    fn parse( json: &str ) -> Result<GltfScene, String> {
+        parse_gltf(json.as_bytes(), Path::new(""))
+    }
+
+    #[test]
+    fn loads_the_samples() {
+        // .gltf + external .bin, the image stays a path next to the file
+        let quad = sample("quad.gltf");
+        assert_eq!(counts(&quad), vec![ ( 4, 6 ) ]);
+        assert!(matches!(&quad.images[0], GltfImage::Path(path) if path.ends_with("models/../wall.jpg")));
+        assert_eq!(quad.materials[0].base_color_texture, Some(TextureRef { texture: 0 }));
+
+        // base64 buffer, normals are generated, the child is placed by its parent
+        let triangle = sample("triangle_embedded.gltf");
+        assert_eq!(counts(&triangle), vec![ ( 3, 3 ) ]);
+        assert_eq!(triangle.roots, vec![ 0 ]);
+        let world = triangle.world_matrices().unwrap();
+        let origin = world[1] * glm::vec4(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(( origin.x, origin.y ), ( 1.0, 0.0 ));
+
+        // Binary container, the PNG lives in a buffer view
+        let cube = sample("cube.glb");
+        assert_eq!(counts(&cube), vec![ ( 24, 36 ) ]);
+        assert!(matches!(&cube.images[0], GltfImage::Bytes(bytes) if bytes.starts_with(b"\x89PNG")));
+    }
+
+    #[test]
+    fn node_cycles_are_rejected() {
+        let err = parse(r#"{ "asset": { "version": "2.0" }, "scenes": [ { "nodes": [ 0 ] } ],
+            "nodes": [ { "children": [ 1 ] }, { "children": [ 0 ] } ] }"#).unwrap_err();
+        assert!(err.contains("not a tree"), "{}", err);
+
+        // Hand built scenes go through the same check
+        let scene = GltfScene { roots: vec![ 3 ], ..GltfScene::default() };
+        assert!(scene.world_matrices().is_err());
+    }
+
+    #[test]
+    fn dangling_indices_are_rejected() {
+        let err = parse(r#"{ "asset": { "version": "2.0" }, "nodes": [ { "mesh": 2 } ] }"#).unwrap_err();
+        assert!(err.contains("missing mesh 2"), "{}", err);
+        let err = parse(r#"{ "asset": { "version": "2.0" }, "materials": [ { "normalTexture": { "index": 0 } } ] }"#).unwrap_err();
+        assert!(err.contains("missing texture 0"), "{}", err);
+        let err = parse(r#"{ "asset": { "version": "2.0" }, "textures": [ { "source": 1 } ] }"#).unwrap_err();
+        assert!(err.contains("missing image 1"), "{}", err);
+    }
+
+    #[test]
+    fn second_uv_set_is_rejected() {
+        let err = parse(r#"{ "asset": { "version": "2.0" }, "images": [ { "uri": "a.png" } ], "textures": [ { "source": 0 } ],
+            "materials": [ { "pbrMetallicRoughness": { "baseColorTexture": { "index": 0, "texCoord": 1 } } } ] }"#).unwrap_err();
+        assert!(err.contains("materials[0]") && err.contains("texCoord 1"), "{}", err);
+    }
+
+    #[test]
+    fn short_tangents_are_rejected() {
+        // Accessors without a buffer view read as zeros -> no buffer needed
+        let scene = | tangents: usize | parse(&format!(r#"{{ "asset": {{ "version": "2.0" }},
+            "meshes": [ {{ "primitives": [ {{ "attributes": {{ "POSITION": 0, "TANGENT": 1 }} }} ] }} ],
+            "accessors": [ {{ "count": 3, "type": "VEC3", "componentType": 5126 }}, {{ "count": {}, "type": "VEC4", "componentType": 5126 }} ] }}"#,
+            tangents));
+        assert_eq!(scene(3).map( | scene | scene.meshes[0].primitives[0].mesh.tangents.len() ), Ok(3));
+        let err = scene(2).unwrap_err();
+        assert!(err.contains("attribute counts don't match POSITION"), "{}", err);
+    }
+}
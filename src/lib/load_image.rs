@@ -1,10 +1,25 @@
 use image::GenericImageView;
 
-pub fn load_image_into_cpu( image_path: &str  ) -> (i32,i32 , Vec<u8>) {
-    let image = image::open(image_path).expect("Unable to Load Image");
+pub fn load_image_into_cpu( image_path: &str  ) -> Result<(i32, i32, Vec<u8>), String> {
+    let image = image::open(image_path).map_err( | err | format!("{}: Unable to Load Image -> {}", image_path, err) )?;
     let image_buffer = image.flipv().into_rgba8();
     let image_data = image_buffer.as_raw();
 
     let ( width , height  ) = image.dimensions();
-    ( width as i32 , height as i32, image_data.to_owned()  )
-}
\ No newline at end of file
+    Ok(( width as i32 , height as i32, image_data.to_owned() ))
+}
+
+pub fn load_image_from_memory( bytes: &[u8] ) -> Result<(i32, i32, Vec<u8>), String> {
+    let image = image::load_from_memory(bytes).map_err( | err | format!("Unable to Decode Image -> {}", err) )?;
+    let image_buffer = image.flipv().into_rgba8();
+
+    let ( width , height  ) = image.dimensions();
+    Ok(( width as i32 , height as i32, image_buffer.into_raw() ))
+}
+
+// Decoded and scaled to `width` x `height` -> layers of a texture array must all have one size
+pub fn load_image_from_memory_sized( bytes: &[u8], width: u32, height: u32 ) -> Result<Vec<u8>, String> {
+    let image = image::load_from_memory(bytes).map_err( | err | format!("Unable to Decode Image -> {}", err) )?;
+    let image = image.resize_exact(width, height, image::imageops::FilterType::Triangle);
+    Ok(image.flipv().into_rgba8().into_raw())
+}
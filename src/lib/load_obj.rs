@@ -0,0 +1,366 @@
+// Wavefront OBJ + MTL loading
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::{logging::logging::ASSETS, mesh::mesh::{normalize, MeshData}, textures::texture::Texture};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMaterial {
+    pub name: String,
+    pub ambient: [f32; 3],
+    pub diffuse: [f32; 3],
+    pub specular: [f32; 3],
+    pub shininess: f32,
+    pub opacity: f32,
+    // Texture paths are already resolved relative to the .mtl file
+    pub diffuse_map: Option<String>,
+    pub specular_map: Option<String>,
+    pub normal_map: Option<String>,
+}
+
+// One draw call worth of triangles -> split on `o`, `g` and `usemtl`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjMesh {
+    pub name: String,
+    pub mesh: MeshData,
+    pub material: Option<usize>,
+}
+
+// Textures of one material, None where the .mtl has no map
+pub struct ObjMaps {
+    pub diffuse: Option<Texture>,
+    pub specular: Option<Texture>,
+    pub normal: Option<Texture>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjModel {
+    pub meshes: Vec<ObjMesh>,
+    pub materials: Vec<ObjMaterial>,
+}
+
+impl ObjMaterial {
+    pub fn new( name: &str ) -> Self {
+        Self {
+            name: name.to_string(),
+            ambient: [ 0.0, 0.0, 0.0 ],
+            diffuse: [ 1.0, 1.0, 1.0 ],
+            specular: [ 0.0, 0.0, 0.0 ],
+            shininess: 0.0,
+            opacity: 1.0,
+            diffuse_map: None,
+            specular_map: None,
+            normal_map: None,
+        }
+    }
+
+    // `map_Kd`, `map_Ks` and the normal map through `Texture`, needs a current GL context
+    pub fn load_maps(&self) -> Result<ObjMaps, String> {
+        Ok(ObjMaps {
+            diffuse: self.load_map(&self.diffuse_map)?,
+            specular: self.load_map(&self.specular_map)?,
+            normal: self.load_map(&self.normal_map)?,
+        })
+    }
+
+    fn load_map( &self, path: &Option<String> ) -> Result<Option<Texture>, String> {
+        path.as_deref().map(Texture::new).transpose()
+            .map_err( | err | format!("material '{}': {}", self.name, err) )
+    }
+}
+
+// Reads the .obj and every `mtllib` next to it
+pub fn load_obj( obj_path: &str ) -> Result<ObjModel, String> {
+    let source = fs::read_to_string(obj_path).map_err( | err | format!("{}: {}", obj_path, err) )?;
+    let base_dir = Path::new(obj_path).parent().unwrap_or(Path::new(""));
+
+    parse_obj(&source, | mtl_name | {
+        let mtl_path = base_dir.join(mtl_name);
+        let mtl_source = fs::read_to_string(&mtl_path).map_err( | err | format!("{}: {}", mtl_path.display(), err) )?;
+        parse_mtl(&mtl_source, mtl_path.parent().unwrap_or(Path::new("")))
+            .map_err( | err | format!("{}:{}", mtl_path.display(), err) )
+    }).map_err( | err | format!("{}:{}", obj_path, err) )
+}
+
+// Index triple of one face corner, already converted to 0-based
+type Corner = ( usize, Option<usize>, Option<usize> );
+
+// Collects the triangles of the current object / material
+struct MeshBuilder {
+    name: String,
+    material: Option<usize>,
+    mesh: MeshData,
+    // Position index each output vertex came from -> used to smooth generated normals
+    sources: Vec<usize>,
+    // Output vertices that got a `vn` from the file, generated normals never replace those
+    explicit: Vec<bool>,
+    lookup: HashMap<Corner, u32>,
+    missing_normals: bool,
+}
+
+impl MeshBuilder {
+    fn new( name: &str, material: Option<usize> ) -> Self {
+        Self { name: name.to_string(), material, mesh: MeshData::new(), sources: Vec::new(), explicit: Vec::new(), lookup: HashMap::new(), missing_normals: false }
+    }
+
+    // Same v/vt/vn triple -> same vertex in the index buffer
+    fn vertex( &mut self, corner: Corner, positions: &[[f32; 3]], uvs: &[[f32; 2]], normals: &[[f32; 3]] ) -> u32 {
+        if let Some(index) = self.lookup.get(&corner) {
+            return *index;
+        }
+        let ( position, uv, normal ) = corner;
+        if normal.is_none() {
+            self.missing_normals = true;
+        }
+        let index = self.mesh.push_vertex(
+            positions[position],
+            normal.map( | n | normals[n] ).unwrap_or([ 0.0, 0.0, 0.0 ]),
+            uv.map( | t | uvs[t] ).unwrap_or([ 0.0, 0.0 ]),
+        );
+        self.sources.push(position);
+        self.explicit.push(normal.is_some());
+        self.lookup.insert(corner, index);
+        index
+    }
+
+    fn finish( mut self, position_count: usize ) -> Option<ObjMesh> {
+        if self.mesh.indices.is_empty() {
+            return None;
+        }
+        if self.missing_normals {
+            generate_normals(&mut self.mesh, &self.sources, &self.explicit, position_count);
+        }
+        Some(ObjMesh { name: self.name, mesh: self.mesh, material: self.material })
+    }
+}
+
+// Smooth normals, area weighted, shared by every vertex at the same OBJ position.
+// Only vertices without a `vn` get one, faces that had normals keep them
+fn generate_normals( mesh: &mut MeshData, sources: &[usize], explicit: &[bool], position_count: usize ) {
+    let mut sums = vec![ [ 0.0f32; 3 ]; position_count ];
+    for triangle in 0..mesh.triangle_count() {
+        let face_normal = mesh.triangle_normal(triangle);
+        for corner in 0..3 {
+            let source = sources[mesh.indices[triangle * 3 + corner] as usize];
+            for axis in 0..3 {
+                sums[source][axis] += face_normal[axis];
+            }
+        }
+    }
+    for ( vertex, normal ) in mesh.normals.iter_mut().enumerate() {
+        if !explicit[vertex] {
+            *normal = normalize(sums[sources[vertex]]);
+        }
+    }
+}
+
+// OBJ indices are 1-based, negative ones count back from the end
+fn resolve_index( token: &str, count: usize, line: usize ) -> Result<usize, String> {
+    let value: i64 = token.parse().map_err( | _ | format!("{}: invalid index '{}'", line, token) )?;
+    let index = if value < 0 { count as i64 + value } else { value - 1 };
+    if index < 0 || index as usize >= count {
+        return Err(format!("{}: index {} out of range ( {} available )", line, value, count));
+    }
+    Ok(index as usize)
+}
+
+fn parse_floats<const N: usize>( parts: &[&str], line: usize, default: f32 ) -> Result<[f32; N], String> {
+    let mut values = [ default; N ];
+    for ( i, value ) in values.iter_mut().enumerate() {
+        if let Some(part) = parts.get(i) {
+            *value = part.parse().map_err( | _ | format!("{}: invalid number '{}'", line, part) )?;
+        } else if i == 0 || default.is_nan() {
+            return Err(format!("{}: expected {} numbers", line, N));
+        }
+    }
+    Ok(values)
+}
+
+// `load_mtl` gets the name after `mtllib` and returns its materials
+pub fn parse_obj<F>( source: &str, mut load_mtl: F ) -> Result<ObjModel, String>
+where
+    F: FnMut(&str) -> Result<Vec<ObjMaterial>, String>
+{
+    let ( mut positions, mut uvs, mut normals ) = ( Vec::new(), Vec::new(), Vec::new() );
+    let mut model = ObjModel::default();
+    let mut object_name = String::from("default");
+    let mut builder = MeshBuilder::new(&object_name, None);
+
+    for ( number, raw_line ) in source.lines().enumerate() {
+        let line = number + 1;
+        let content = raw_line.split('#').next().unwrap_or("").trim();
+        let mut parts = content.split_whitespace();
+        let Some(keyword) = parts.next() else { continue };
+        let args = parts.collect::<Vec<&str>>();
+
+        match keyword {
+            "v" => positions.push(parse_floats::<3>(&args, line, f32::NAN)?),
+            "vt" => {
+                let uv = parse_floats::<2>(&args, line, 0.0)?;
+                uvs.push(uv);
+            },
+            "vn" => normals.push(parse_floats::<3>(&args, line, f32::NAN)?),
+            "f" => {
+                if args.len() < 3 {
+                    return Err(format!("{}: a face needs at least 3 corners", line));
+                }
+                let mut corners = Vec::with_capacity(args.len());
+                for arg in &args {
+                    let mut fields = arg.split('/');
+                    let position = resolve_index(fields.next().unwrap_or(""), positions.len(), line)?;
+                    let uv = match fields.next() {
+                        Some(field) if !field.is_empty() => Some(resolve_index(field, uvs.len(), line)?),
+                        _ => None,
+                    };
+                    let normal = match fields.next() {
+                        Some(field) if !field.is_empty() => Some(resolve_index(field, normals.len(), line)?),
+                        _ => None,
+                    };
+                    corners.push(builder.vertex(( position, uv, normal ), &positions, &uvs, &normals));
+                }
+                // Fan triangulation -> fine for the convex polygons exporters write
+                for i in 1..corners.len() - 1 {
+                    builder.mesh.push_triangle(corners[0], corners[i], corners[i + 1]);
+                }
+            },
+            "o" | "g" => {
+                object_name = args.join(" ");
+                let material = builder.material;
+                let finished = std::mem::replace(&mut builder, MeshBuilder::new(&object_name, material));
+                model.meshes.extend(finished.finish(positions.len()));
+            },
+            "usemtl" => {
+                let name = args.join(" ");
+                let material = model.materials.iter().position( | m | m.name == name );
+                if material.is_none() {
+                    log::warn!(target: ASSETS, "OBJ line {}: unknown material '{}'", line, name);
+                }
+                let finished = std::mem::replace(&mut builder, MeshBuilder::new(&object_name, material));
+                model.meshes.extend(finished.finish(positions.len()));
+            },
+            "mtllib" => {
+                for name in &args {
+                    model.materials.extend(load_mtl(name)?);
+                }
+            },
+            // Smoothing groups, lines and points are not used
+            _ => {}
+        }
+    }
+
+    model.meshes.extend(builder.finish(positions.len()));
+    Ok(model)
+}
+
+// `base_dir` is where the texture paths inside the file are relative to
+pub fn parse_mtl( source: &str, base_dir: &Path ) -> Result<Vec<ObjMaterial>, String> {
+    let mut materials: Vec<ObjMaterial> = Vec::new();
+
+    for ( number, raw_line ) in source.lines().enumerate() {
+        let line = number + 1;
+        let content = raw_line.split('#').next().unwrap_or("").trim();
+        let mut parts = content.split_whitespace();
+        let Some(keyword) = parts.next() else { continue };
+        let args = parts.collect::<Vec<&str>>();
+
+        if keyword == "newmtl" {
+            materials.push(ObjMaterial::new(&args.join(" ")));
+            continue;
+        }
+        let Some(material) = materials.last_mut() else {
+            return Err(format!("{}: '{}' before any newmtl", line, keyword));
+        };
+        // Map options like `-bm 1.0` come first, the file name is always last
+        let texture_path = || args.last().map( | file | base_dir.join(file).to_string_lossy().into_owned() );
+
+        match keyword {
+            "Ka" => material.ambient = parse_floats::<3>(&args, line, f32::NAN)?,
+            "Kd" => material.diffuse = parse_floats::<3>(&args, line, f32::NAN)?,
+            "Ks" => material.specular = parse_floats::<3>(&args, line, f32::NAN)?,
+            "Ns" => material.shininess = parse_floats::<1>(&args, line, f32::NAN)?[0],
+            "d" => material.opacity = parse_floats::<1>(&args, line, f32::NAN)?[0],
+            "Tr" => material.opacity = 1.0 - parse_floats::<1>(&args, line, f32::NAN)?[0],
+            "map_Kd" => material.diffuse_map = texture_path(),
+            "map_Ks" => material.specular_map = texture_path(),
+            "map_Bump" | "map_bump" | "bump" | "norm" | "map_Kn" => material.normal_map = texture_path(),
+            _ => {}
+        }
+    }
+    Ok(materials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mesh::mesh::dot;
+
+    fn fixture() -> ObjModel {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/assets/models/pyramid.obj");
+        load_obj(&path.to_string_lossy()).unwrap()
+    }
+
+    #[test]
+    fn loads_the_pyramid_with_its_material() {
+        let model = fixture();
+        assert_eq!(model.meshes.len(), 1);
+        let pyramid = &model.meshes[0];
+        assert_eq!(pyramid.name, "pyramid");
+        // Base corners carry a normal, side corners don't -> 4 + 5 vertices, the quad is fanned into 2 triangles
+        assert_eq!(pyramid.mesh.vertex_count(), 9);
+        assert_eq!(pyramid.mesh.triangle_count(), 6);
+
+        assert_eq!(pyramid.material, Some(0));
+        let stone = &model.materials[0];
+        assert_eq!(stone.name, "stone");
+        assert_eq!(stone.diffuse, [ 0.8, 0.7, 0.6 ]);
+        assert_eq!(stone.shininess, 16.0);
+        assert!(stone.diffuse_map.as_deref().unwrap().ends_with("models/../wall.jpg"));
+        assert!(stone.specular_map.as_deref().unwrap().ends_with("models/pyramid_specular.png"));
+        assert!(stone.normal_map.as_deref().unwrap().ends_with("models/pyramid_normal.png"));
+    }
+
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    #[test]
+    fn loads_every_map_of_the_pyramid() {
+        let _context = crate::context::headless::HeadlessContext::new(4, 4).unwrap();
+        let maps = fixture().materials[0].load_maps().unwrap();
+        for ( name, map ) in [ ( "diffuse", &maps.diffuse ), ( "specular", &maps.specular ), ( "normal", &maps.normal ) ] {
+            assert!(map.as_ref().is_some_and( | texture | texture.id != 0 ), "{} map missing", name);
+        }
+    }
+
+    #[test]
+    fn generated_normals_leave_explicit_ones_alone() {
+        let model = fixture();
+        let mesh = &model.meshes[0].mesh;
+        // Base vertices come first and keep the `vn` from the file
+        assert!(mesh.normals[..4].iter().all( | normal | *normal == [ 0.0, -1.0, 0.0 ] ));
+        // The apex is shared by the 4 sides -> straight up
+        let apex = mesh.positions.iter().position( | p | *p == [ 0.0, 0.8, 0.0 ] ).unwrap();
+        let normal = mesh.normals[apex].map( | v | (v * 1000.0).round() / 1000.0 );
+        assert_eq!(normal, [ 0.0, 1.0, 0.0 ]);
+        for normal in &mesh.normals[4..] {
+            assert!((dot(*normal, *normal) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn missing_texture_is_an_error() {
+        let mut material = ObjMaterial::new("broken");
+        material.diffuse_map = Some("does/not/exist.png".to_string());
+        let err = material.load_maps().err().unwrap();
+        assert!(err.contains("broken") && err.contains("does/not/exist.png"), "{}", err);
+
+        // Same context for the other maps
+        let mut material = ObjMaterial::new("no normals");
+        material.normal_map = Some("missing_normal.png".to_string());
+        let err = material.load_maps().err().unwrap();
+        assert!(err.contains("no normals") && err.contains("missing_normal.png"), "{}", err);
+    }
+
+    #[test]
+    fn bad_indices_report_the_line() {
+        let err = parse_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n", | _ | Ok(Vec::new()) ).err().unwrap();
+        assert!(err.starts_with("3:"), "{}", err);
+    }
+}
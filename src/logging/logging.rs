@@ -0,0 +1,200 @@
+// Logging -> one logger behind the `log` facade, a level per target, stderr and / or a JSON lines file
+//   log::warn!(target: TEXTURE, "...")    [log] in openglyt.toml picks what is shown and where it goes
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    sync::{Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::{config::config::LogConfig, utils::json::Json};
+
+// Targets of our own messages, `[log.targets]` sets a level for each
+pub const SHADER: &str = "shader";
+pub const TEXTURE: &str = "texture";
+pub const WINDOW: &str = "window";
+pub const GL: &str = "gl";
+pub const ASSETS: &str = "assets";
+pub const APP: &str = "app";
+pub const TARGETS: &[&str] = &[ SHADER, TEXTURE, WINDOW, GL, ASSETS, APP ];
+
+const LEVELS: &[(&str, LevelFilter)] = &[
+    ( "off", LevelFilter::Off ),
+    ( "error", LevelFilter::Error ),
+    ( "warn", LevelFilter::Warn ),
+    ( "info", LevelFilter::Info ),
+    ( "debug", LevelFilter::Debug ),
+    ( "trace", LevelFilter::Trace ),
+];
+
+pub fn parse_level( name: &str ) -> Result<LevelFilter, String> {
+    LEVELS.iter()
+        .find( | ( label, _ ) | label.eq_ignore_ascii_case(name) )
+        .map( | ( _, level ) | *level )
+        .ok_or_else( || {
+            let names = LEVELS.iter().map( | ( label, _ ) | *label ).collect::<Vec<&str>>();
+            format!("unknown level '{}', expected one of: {}", name, names.join(", "))
+        })
+}
+
+struct Output {
+    level: LevelFilter,
+    targets: BTreeMap<String, LevelFilter>,
+    console: bool,
+    json: Option<File>,
+}
+
+impl Output {
+    // Targets we don't know ( other crates, module paths ) get the global level
+    fn level_for( &self, target: &str ) -> LevelFilter {
+        self.targets.get(target).copied().unwrap_or(self.level)
+    }
+}
+
+struct Logger {
+    output: Mutex<Output>,
+}
+
+impl Logger {
+    // A panic while logging must not silence everything after it
+    fn output(&self) -> MutexGuard<'_, Output> {
+        self.output.lock().unwrap_or_else( | poisoned | poisoned.into_inner() )
+    }
+}
+
+static LOGGER: Logger = Logger {
+    output: Mutex::new(Output { level: LevelFilter::Info, targets: BTreeMap::new(), console: true, json: None }),
+};
+
+// { "time": seconds since 1970, "level", "target", "message", "file", "line" }
+fn json_line( record: &Record ) -> Json {
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map( | time | time.as_secs_f64() ).unwrap_or(0.0);
+    let mut fields = BTreeMap::new();
+    fields.insert("time".to_string(), Json::Number(time));
+    fields.insert("level".to_string(), Json::String(record.level().to_string()));
+    fields.insert("target".to_string(), Json::String(record.target().to_string()));
+    fields.insert("message".to_string(), Json::String(record.args().to_string()));
+    if let Some(file) = record.file() {
+        fields.insert("file".to_string(), Json::String(file.to_string()));
+    }
+    if let Some(line) = record.line() {
+        fields.insert("line".to_string(), Json::Number(line as f64));
+    }
+    Json::Object(fields)
+}
+
+impl Log for Logger {
+    fn enabled( &self, metadata: &Metadata ) -> bool {
+        metadata.level() <= self.output().level_for(metadata.target())
+    }
+
+    fn log( &self, record: &Record ) {
+        let mut output = self.output();
+        if record.level() > output.level_for(record.target()) {
+            return;
+        }
+        if output.console {
+            eprintln!("{:<5} {}: {}", record.level(), record.target(), record.args());
+        }
+        if let Some(file) = output.json.as_mut() {
+            // Nowhere left to report a failing log file, the line is dropped
+            let _ = writeln!(file, "{}", json_line(record));
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = self.output().json.as_mut() {
+            let _ = file.flush();
+        }
+    }
+}
+
+// First thing in main() -> until configure() runs, info and up go to stderr
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+// Applies `[log]`, the JSON lines file is appended to and its directory created
+pub fn configure( config: &LogConfig ) -> Result<(), String> {
+    let json = match &config.json_file {
+        Some(path) => {
+            if let Some(parent) = path.parent().filter( | parent | !parent.as_os_str().is_empty() ) {
+                fs::create_dir_all(parent).map_err( | err | format!("log.json_file: {}: {}", parent.display(), err) )?;
+            }
+            let file = OpenOptions::new().create(true).append(true).open(path)
+                .map_err( | err | format!("log.json_file: {}: {}", path.display(), err) )?;
+            Some(file)
+        },
+        None => None,
+    };
+
+    let mut output = LOGGER.output();
+    output.level = config.level;
+    output.targets = config.targets.clone();
+    output.console = config.console;
+    output.json = json;
+    // The macros skip anything above this without asking the logger
+    log::set_max_level(config.targets.values().copied().fold(config.level, Ord::max));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use log::Level;
+
+    fn output( level: LevelFilter, targets: &[(&str, LevelFilter)] ) -> Output {
+        let targets = targets.iter().map( | ( target, level ) | ( target.to_string(), *level ) ).collect();
+        Output { level, targets, console: false, json: None }
+    }
+
+    #[test]
+    fn levels_parse_case_insensitive() {
+        assert_eq!(parse_level("warn"), Ok(LevelFilter::Warn));
+        assert_eq!(parse_level("DEBUG"), Ok(LevelFilter::Debug));
+        assert_eq!(parse_level("off"), Ok(LevelFilter::Off));
+        let err = parse_level("verbose").unwrap_err();
+        assert!(err.contains("'verbose'") && err.contains("off, error, warn, info, debug, trace"), "{}", err);
+    }
+
+    #[test]
+    fn target_overrides_win_over_the_global_level() {
+        let output = output(LevelFilter::Warn, &[ ( GL, LevelFilter::Trace ), ( TEXTURE, LevelFilter::Off ) ]);
+        // More verbose and quieter than the global level both work
+        assert_eq!(output.level_for(GL), LevelFilter::Trace);
+        assert_eq!(output.level_for(TEXTURE), LevelFilter::Off);
+        // Everything else, other crates too, gets the global level
+        assert_eq!(output.level_for(SHADER), LevelFilter::Warn);
+        assert_eq!(output.level_for("wgpu_core::device"), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn json_lines_round_trip() {
+        let message = "say \"hi\"\nline two\ttab \u{1} bell \\ done";
+        // format_args! only lives for the statement -> build and encode in one go
+        let line = json_line(&Record::builder()
+            .level(Level::Warn)
+            .target(ASSETS)
+            .file(Some("src/lib/load_obj.rs"))
+            .line(Some(42))
+            .args(format_args!("{}", message))
+            .build()
+        ).to_string();
+        // One record per line, control characters are escaped
+        assert!(!line.contains('\n') && !line.contains('\u{1}'), "{}", line);
+
+        let parsed = Json::parse(&line).unwrap();
+        assert_eq!(parsed.get("message").and_then(Json::as_str), Some(message));
+        assert_eq!(parsed.get("level").and_then(Json::as_str), Some("WARN"));
+        assert_eq!(parsed.get("target").and_then(Json::as_str), Some(ASSETS));
+        assert_eq!(parsed.get("file").and_then(Json::as_str), Some("src/lib/load_obj.rs"));
+        assert_eq!(parsed.get("line").and_then(Json::as_usize), Some(42));
+        assert!(parsed.get("time").and_then(Json::as_f64).is_some_and( | time | time > 0.0 ));
+    }
+}
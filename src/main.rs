@@ -1,13 +1,14 @@
-use std::{ffi::CString, fs::File, io::Read, os::windows, process, ptr::null, time::{SystemTime, UNIX_EPOCH}};
-use glfw::{fail_on_errors, ffi::glfwGetTime, Action, Context, Key, WindowEvent};
-use glm::{ext::{rotate, translate}, mat4, Mat4};
-use image::GenericImageView;
-
-use crate::{ 
-    lib::load_image::load_image_into_cpu, 
-    shaders::shader::Shader, 
-    textures::texture::Texture, 
-    utils::coordinates::{TexturePath, Vertices}
+// The modules follow their folders, `mod config { pub mod config; }`
+#![allow(clippy::module_inception)]
+
+use std::{path::Path, process};
+
+use crate::{
+    config::config::Config,
+    context::context::GlContext,
+    demos::demos::{find_demo, DemoEntry, DEFAULT_DEMO, DEMOS},
+    logging::logging::APP,
+    renderer::{capture::{record_app, CaptureSource, Recorder}, gl_state}
 };
 
 mod shaders {
@@ -18,157 +19,186 @@ mod textures {
     pub mod texture;
 }
 
+mod materials {
+    pub mod material;
+}
+
+mod renderer {
+    pub mod renderer;
+    pub mod gl_state;
+    pub mod gl_debug;
+    pub mod sprite_batch;
+    pub mod framebuffer;
+    pub mod capture;
+}
+
+mod context {
+    pub mod context;
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    pub mod headless;
+}
+
+mod config {
+    pub mod config;
+}
+
+mod logging {
+    pub mod logging;
+}
+
+mod input {
+    pub mod input;
+    pub mod actions;
+    pub mod gamepad;
+}
+
+mod app {
+    pub mod app;
+    pub mod runner;
+    pub mod clock;
+    pub mod frame_times;
+}
+
+mod demos {
+    pub mod demos;
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    pub mod golden;
+}
+
 mod lib {
     pub mod load_image;
+    pub mod load_obj;
+    pub mod load_gltf;
 }
 
 mod utils {
-    pub mod coordinates;
     pub mod payload;
+    pub mod bounds;
+    pub mod json;
+    pub mod toml;
+    #[cfg(test)]
+    pub mod fuzz;
 }
 
 mod camera {
     pub mod camera;
+    pub mod frustum;
 }
 
-const WINDOW_WIDTH:u32 = 800;
-const WINDOW_HEIGHT:u32 = 800;
-const WINDOW_TITLE: &str = "Can I get 3D";
-const VERTEX_SHADER_PATH: &str = "G:\\OpenGL-YT\\openglyt\\src\\glsl\\texture_v.vert";
-const FRAGMENT_SHADER_PATH: &str = "G:\\OpenGL-YT\\openglyt\\src\\glsl\\texture_f.frag";
-const TEXTURE_PATH: &str = "G:\\OpenGL-YT\\openglyt\\src\\assets\\wall.jpg";
+mod mesh {
+    pub mod mesh;
+    pub mod layout;
+    pub mod instancing;
+}
 
-type ShapeVerticesAndIndices = ( [f32; 32], [ u32; 6 ] );
+mod scene {
+    pub mod scene;
+    pub mod transform;
+}
 
-fn get_shape_vertices() -> ShapeVerticesAndIndices {
+mod other_shapes {
+    pub mod polygon;
+    pub mod parallelogram;
+    pub mod rhombus;
+    pub mod solids;
+    #[cfg(test)]
+    pub mod shape_asserts;
+}
 
-    let vertices = [
-            //  ***  -----------  Vertices - Colors - Textures  ----------- ***  
-            	-0.5, -0.5, 0.0,     1.0, 0.0, 0.0,	     0.0, 0.0, // Lower let corner
-                -0.5,  0.5, 0.0,     0.0, 1.0, 0.0,	     0.0, 1.0, // Upper let corner
-                 0.5,  0.5, 0.0,     0.0, 0.0, 1.0, 	 1.0, 1.0, // Upper right corner
-                 0.5, -0.5, 0.0,     1.0, 1.0, 1.0,	     1.0, 0.0  // Lower right corner
-    ];
+// Value after `flag`, e.g. `--demo triangle` -> "triangle"
+fn arg_value<'a>( args: &'a [String], flag: &str ) -> Option<&'a str> {
+    args.iter().position( | arg | arg == flag ).and_then( | i | args.get(i + 1) ).map( | value | value.as_str() )
+}
 
-    let indices = [
-        0, 2, 1, // Upper triangle
-	    0, 3, 2 // Lower triangle
-    ];
-    
-    ( vertices, indices )
+// Numbered PNGs of `demo` at 30 fps simulated time, whatever the real frame rate
+fn record_demo( context: &mut dyn GlContext, source: CaptureSource, demo: &DemoEntry, config: &Config, directory: &Path, frames: u32 ) -> Result<(), String> {
+    let mut app = (demo.create)(context, config);
+    let mut recorder = Recorder::new(directory, 30.0)?;
+    record_app(context, app.as_mut(), source, &mut recorder, frames)
 }
 
+// Headless builds render into the offscreen target
+#[cfg(all(feature = "headless", target_os = "linux"))]
+fn record( demo: &DemoEntry, config: &Config, directory: &Path, frames: u32 ) -> Result<(), String> {
+    let mut context = context::headless::HeadlessContext::new(config.window.width as i32, config.window.height as i32)?;
+    let source = CaptureSource::of(&context.target);
+    record_demo(&mut context, source, demo, config, directory, frames)
+}
 
-fn main() {
+// Otherwise a window opens and every frame is read from its back buffer before the swap
+#[cfg(not(all(feature = "headless", target_os = "linux")))]
+fn record( demo: &DemoEntry, config: &Config, directory: &Path, frames: u32 ) -> Result<(), String> {
+    let mut context = context::context::WindowContext::new(&config.window)?;
+    let ( width, height ) = context.framebuffer_size();
+    record_demo(&mut context, CaptureSource { framebuffer: 0, width, height }, demo, config, directory, frames)
+}
 
-    type BuffersType = ( u32, u32, u32 );
+fn main() {
+    logging::logging::init();
+    let args = std::env::args().collect::<Vec<String>>();
 
-    // initialize our glfw
-    let mut initialize_glfw = match glfw::init(fail_on_errors!()) {
-        Ok(glfw) => glfw,
-        Err(e) => {
-            eprintln!("GLFW - Iniialization Error -> {:?}", e);
-            process::exit(1)
+    if args.iter().any( | arg | arg == "--list" ) {
+        for demo in DEMOS {
+            println!("{:<14} {}", demo.name, demo.description);
         }
-    };
+        process::exit(0);
+    }
 
-    let ( mut window, events ) =  match initialize_glfw.create_window( WINDOW_WIDTH , WINDOW_HEIGHT, WINDOW_TITLE, glfw::WindowMode::Windowed ) {
-        Some( window ) => window,
-        None => {
-            eprintln!("Creating Window Error!");
-            process::exit(1)
+    // --demo <name>, see --list
+    let demo = match find_demo(arg_value(&args, "--demo").unwrap_or(DEFAULT_DEMO)) {
+        Ok(demo) => demo,
+        Err(err) => {
+            log::error!(target: APP, "{}", err);
+            process::exit(2)
         }
     };
 
-    window.make_current();
-    window.set_key_polling(true);
-
-    gl::load_with( | ptr | window.get_proc_address(ptr)  );
-    
-    let ( vertices, indices ) = get_shape_vertices();
-    
-    let mut vertex_shader_impl_buffer = String::new();
-    File::open(VERTEX_SHADER_PATH).expect("Vertex shader File Reading Error!").read_to_string(&mut vertex_shader_impl_buffer).unwrap();
-    let mut fragment_shader_impl_buffer = String::new();
-    File::open(FRAGMENT_SHADER_PATH).expect("Fragment shader File Reading Error!").read_to_string(&mut fragment_shader_impl_buffer).unwrap();
-    
-    // Shaders
-    let shader = Shader::new(&vertex_shader_impl_buffer, &fragment_shader_impl_buffer);
-    let texture = Texture::new(TEXTURE_PATH);
-    println!("Texture Id -> {:?}", texture.id);
-    
-    let ( mut vao, mut vbo, mut ebo ) :BuffersType = ( 0, 0, 0 );
-    unsafe {
-        gl::Viewport(0,0,WINDOW_WIDTH as i32, WINDOW_HEIGHT as i32);
-        
-        // Generate -->> Arrays && Buffers
-        gl::GenVertexArrays(1, &mut vao);
-        gl::GenBuffers(1, &mut vbo);
-        gl::GenBuffers(1, &mut ebo);
-        
-        // Bind them
-        gl::BindVertexArray(vao);
-        
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(gl::ARRAY_BUFFER, std::mem::size_of_val(&vertices) as isize , vertices.as_ptr() as * const _, gl::STATIC_DRAW);
-
-        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
-        gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, std::mem::size_of_val(&indices) as isize , indices.as_ptr() as * const _, gl::STATIC_DRAW);
-        
-        
-        gl::VertexAttribPointer( 0, 3 , gl::FLOAT, gl::FALSE, 8 * size_of::<f32>() as i32, std::ptr::null() );
-        gl::EnableVertexAttribArray(0);
-
-        gl::VertexAttribPointer( 1, 3 , gl::FLOAT, gl::FALSE, 8 * size_of::<f32>() as i32, (3 * size_of::<f32>()) as *const _ );
-        gl::EnableVertexAttribArray(1);
-        gl::VertexAttribPointer( 2, 2 , gl::FLOAT, gl::FALSE, 8 * size_of::<f32>() as i32, (6 * size_of::<f32>()) as *const _ );
-        gl::EnableVertexAttribArray(2);
-
-        let opengl_err = gl::GetError();
-        if opengl_err != gl::NO_ERROR {
-            eprintln!("Error in OpenGL -> {:?}", opengl_err);
+    // openglyt.toml or --config <path>, then flags like --width / --samples on top
+    let config = match Config::load(demo.window(), &args) {
+        Ok(config) => config,
+        Err(err) => {
+            log::error!(target: APP, "Config error -> {}", err);
+            process::exit(2)
         }
-
-    }
-
-    println!("Shader Program ID -> {:?}", shader.id );
-
-    // Uniforms 
-    let scale_uniform = CString::new("scale").expect("Invalid Uniform Provided");
-    let vertex_shader_scale_loc = unsafe {
-        gl::GetUniformLocation(shader.id, scale_uniform.as_ptr() as *const _)
     };
+    if let Err(err) = logging::logging::configure(&config.log) {
+        log::error!(target: APP, "Config error -> {}", err);
+        process::exit(2)
+    }
 
-    while !window.should_close() {
-        initialize_glfw.poll_events();
-        window.swap_buffers();
-
-        for ( _, event ) in glfw::flush_messages(&events) {
-            match event {
-                glfw::WindowEvent::Key(  Key::Escape, _, Action::Press, _ ) => {
-                    window.set_should_close(true);
-                },
-                _ => {
-                    eprintln!("There is some error occured in closing our Window");
+    // Offscreen regression check of every demo, no window needed
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    {
+        if let Some(mode) = demos::golden::GoldenMode::from_args(&args) {
+            match demos::golden::run(mode, demos::golden::DEFAULT_TOLERANCE) {
+                Ok(()) => process::exit(0),
+                Err(failures) => {
+                    log::error!(target: APP, "Golden image check failed:\n{}", failures);
+                    process::exit(1)
                 }
             }
         }
+    }
 
-        unsafe {
-            gl::ClearColor(0.21, 0.13, 0.02, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT);
-
-            let curtime = glfwGetTime();
-            let ran_value: f32 = ((curtime.cos() / 2.0) + 0.5) as f32;
-            gl::UseProgram(shader.id);
-            gl::Uniform1f(vertex_shader_scale_loc, ran_value );
-
-            gl::BindVertexArray(vao);
-            
-            gl::ActiveTexture(texture.id);
-            gl::BindTexture(gl::TEXTURE_2D, texture.id);
-            
-            gl::DrawElements(gl::TRIANGLES, 3, gl::UNSIGNED_INT, null());
+    // --record <dir> [--frames N] -> the selected demo as numbered PNGs
+    if args.iter().any( | arg | arg == "--record" ) {
+        let directory = arg_value(&args, "--record").unwrap_or("recording");
+        // Config::load already turned anything but a positive count into an error
+        let frames = arg_value(&args, "--frames").and_then( | count | count.parse::<u32>().ok() ).unwrap_or(60);
+        match record(demo, &config, Path::new(directory), frames) {
+            Ok(()) => process::exit(0),
+            Err(err) => {
+                log::error!(target: APP, "Recording failed -> {}", err);
+                process::exit(1)
+            }
         }
     }
-}
\ No newline at end of file
+
+    // Off unless asked for, every check is a round of glGet* calls
+    gl_state::set_debug(config.window.gl_verify_state);
+
+    if let Err(err) = app::runner::run(&config, demo.create) {
+        log::error!(target: APP, "{}", err);
+        process::exit(1)
+    }
+}
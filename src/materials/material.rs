@@ -0,0 +1,92 @@
+// Material -> Shader + Textures + Uniform values, applied with one bind()
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use glm::Mat4;
+
+use crate::{renderer::gl_state::{self, UniformValue}, shaders::shader::Shader, textures::texture::Texture};
+
+struct TextureSlot {
+    name: String,
+    unit: u32,
+    texture: Rc<Texture>,
+}
+
+pub struct Material {
+    pub shader: Rc<Shader>,
+    // Drawn after the opaque objects, back to front, with blending on
+    pub transparent: bool,
+    textures: Vec<TextureSlot>,
+    uniforms: Vec<(String, UniformValue)>,
+    locations: RefCell<HashMap<String, i32>>,
+}
+
+impl Material {
+    pub fn new( shader: Rc<Shader> ) -> Self {
+        Self {
+            shader,
+            transparent: false,
+            textures: Vec::new(),
+            uniforms: Vec::new(),
+            locations: RefCell::new(HashMap::new()),
+        }
+    }
+
+    // `sampler_name` is the `uniform sampler2D` in the shader, units are handed out in order
+    pub fn set_texture( &mut self, sampler_name: &str, texture: Rc<Texture> ) -> &mut Self {
+        if let Some(slot) = self.textures.iter_mut().find( | slot | slot.name == sampler_name ) {
+            slot.texture = texture;
+        } else {
+            let unit = self.textures.len() as u32;
+            self.textures.push(TextureSlot { name: sampler_name.to_string(), unit, texture });
+        }
+        self
+    }
+
+    // ( unit, texture id ) of every slot
+    pub fn texture_ids(&self) -> Vec<(u32, u32)> {
+        self.textures.iter().map( | slot | ( slot.unit, slot.texture.id ) ).collect()
+    }
+
+    pub fn set( &mut self, uniform_name: &str, value: UniformValue ) -> &mut Self {
+        match self.uniforms.iter_mut().find( | ( name, _ ) | name == uniform_name ) {
+            Some(( _, current )) => *current = value,
+            None => self.uniforms.push(( uniform_name.to_string(), value )),
+        }
+        self
+    }
+
+    pub fn set_float( &mut self, uniform_name: &str, value: f32 ) -> &mut Self {
+        self.set(uniform_name, UniformValue::Float(value))
+    }
+
+    pub fn set_vec4( &mut self, uniform_name: &str, value: [f32; 4] ) -> &mut Self {
+        self.set(uniform_name, UniformValue::Vec4(value))
+    }
+
+    pub fn set_mat4( &mut self, uniform_name: &str, value: Mat4 ) -> &mut Self {
+        self.set(uniform_name, UniformValue::Mat4(value))
+    }
+
+    fn location( &self, name: &str ) -> i32 {
+        *self.locations
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_insert_with( || self.shader.uniform_location(name) )
+    }
+
+    // Program, textures and uniforms -> only what differs from the current GL state is sent
+    pub fn bind(&self) {
+        let program = self.shader.id;
+        gl_state::use_program(program);
+
+        for slot in &self.textures {
+            gl_state::bind_texture(slot.unit, slot.texture.target, slot.texture.id);
+        }
+
+        let samplers = self.textures.iter().map( | slot | ( slot.name.as_str(), UniformValue::Int(slot.unit as i32) ) );
+        let values = self.uniforms.iter().map( | ( name, value ) | ( name.as_str(), *value ) );
+        for ( name, value ) in samplers.chain(values) {
+            gl_state::uniform(self.location(name), value);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+// Instanced Rendering -> one draw call, per-instance data in its own buffer
+use std::ptr::null;
+
+use glm::Mat4;
+
+use crate::{mesh::mesh::Mesh, renderer::gl_state};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instance {
+    pub model: Mat4,
+    // Multiplied with the texture / vertex color
+    pub tint: [f32; 4],
+    // Layer of a TEXTURE_2D_ARRAY, stored as float like every other attribute
+    pub layer: f32,
+}
+
+impl Instance {
+    pub fn new( model: Mat4 ) -> Self {
+        Self { model, tint: [ 1.0, 1.0, 1.0, 1.0 ], layer: 0.0 }
+    }
+
+    pub fn with_tint( mut self, tint: [f32; 4] ) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    pub fn with_layer( mut self, layer: u32 ) -> Self {
+        self.layer = layer as f32;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstanceAttribute {
+    // Takes 4 consecutive locations, one per column
+    Model,
+    Tint,
+    Layer,
+}
+
+impl InstanceAttribute {
+    pub fn components(&self) -> usize {
+        match self {
+            InstanceAttribute::Model => 16,
+            InstanceAttribute::Tint => 4,
+            InstanceAttribute::Layer => 1,
+        }
+    }
+
+    // Shader locations used, a vertex attribute holds at most 4 floats
+    pub fn locations(&self) -> usize {
+        self.components().div_ceil(4)
+    }
+}
+
+// Same idea as VertexLayout, but every attribute advances once per instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceLayout {
+    pub attributes: Vec<(InstanceAttribute, u32)>,
+    // Instances that share one value, 1 = every instance gets its own
+    pub divisor: u32,
+}
+
+impl InstanceLayout {
+    pub fn new() -> Self {
+        Self { attributes: Vec::new(), divisor: 1 }
+    }
+
+    pub fn with( mut self, attribute: InstanceAttribute, location: u32 ) -> Self {
+        self.attributes.push(( attribute, location ));
+        self
+    }
+
+    // Starts after VertexLayout::standard() -> Model 4..7, Tint 8, Layer 9
+    pub fn standard() -> Self {
+        Self::new()
+            .with(InstanceAttribute::Model, 4)
+            .with(InstanceAttribute::Tint, 8)
+            .with(InstanceAttribute::Layer, 9)
+    }
+
+    // Floats per instance
+    pub fn stride(&self) -> usize {
+        self.attributes.iter().map( | ( attribute, _ ) | attribute.components() ).sum()
+    }
+
+    pub fn pack( &self, instances: &[Instance] ) -> Vec<f32> {
+        let mut data = Vec::with_capacity(instances.len() * self.stride());
+        for instance in instances {
+            for ( attribute, _ ) in &self.attributes {
+                match attribute {
+                    InstanceAttribute::Model => {
+                        for column in 0..4 {
+                            let c = instance.model[column];
+                            data.extend_from_slice(&[ c.x, c.y, c.z, c.w ]);
+                        }
+                    },
+                    InstanceAttribute::Tint => data.extend_from_slice(&instance.tint),
+                    InstanceAttribute::Layer => data.push(instance.layer),
+                }
+            }
+        }
+        data
+    }
+
+    // Sets pointers + divisors of the bound VAO, reading from the bound ARRAY_BUFFER
+    pub fn apply(&self) {
+        let stride = (self.stride() * size_of::<f32>()) as i32;
+        let mut offset = 0;
+        for ( attribute, location ) in &self.attributes {
+            let mut remaining = attribute.components();
+            for slot in 0..attribute.locations() as u32 {
+                let components = remaining.min(4);
+                unsafe {
+                    gl::VertexAttribPointer(
+                        location + slot,
+                        components as i32,
+                        gl::FLOAT,
+                        gl::FALSE,
+                        stride,
+                        (offset * size_of::<f32>()) as *const _
+                    );
+                    gl::EnableVertexAttribArray(location + slot);
+                    gl::VertexAttribDivisor(location + slot, self.divisor);
+                }
+                offset += components;
+                remaining -= components;
+            }
+        }
+    }
+}
+
+// Streaming buffer of instances, hooked into the VAO of every mesh it draws
+pub struct InstanceBuffer {
+    pub vbo: u32,
+    pub layout: InstanceLayout,
+    // In instances
+    capacity: usize,
+    attached: Vec<u32>,
+}
+
+impl InstanceBuffer {
+    pub fn new( layout: InstanceLayout ) -> Self {
+        let mut vbo = 0;
+        unsafe { gl::GenBuffers(1, &mut vbo) };
+        Self { vbo, layout, capacity: 0, attached: Vec::new() }
+    }
+
+    // Replaces the contents, the old storage is orphaned so the GPU never waits on us
+    pub fn upload( &mut self, instances: &[Instance] ) {
+        let data = self.layout.pack(instances);
+        let bytes = size_of_val(data.as_slice()) as isize;
+        gl_state::bind_buffer(gl::ARRAY_BUFFER, self.vbo);
+        unsafe {
+            if instances.len() > self.capacity {
+                // Grow by doubling so a slowly growing crowd doesn't reallocate every frame
+                self.capacity = instances.len().max(self.capacity * 2);
+            }
+            let capacity_bytes = (self.capacity * self.layout.stride() * size_of::<f32>()) as isize;
+            gl::BufferData(gl::ARRAY_BUFFER, capacity_bytes, null(), gl::STREAM_DRAW);
+            gl::BufferSubData(gl::ARRAY_BUFFER, 0, bytes, data.as_ptr() as *const _);
+        }
+    }
+
+    // Adds the instance attributes to the mesh VAO, once per mesh
+    pub fn attach( &mut self, mesh: &Mesh ) {
+        if self.attached.contains(&mesh.vao) {
+            return;
+        }
+        gl_state::bind_vertex_array(mesh.vao);
+        gl_state::bind_buffer(gl::ARRAY_BUFFER, self.vbo);
+        self.layout.apply();
+        self.attached.push(mesh.vao);
+    }
+
+    // Every instance of `mesh` in a single DrawElementsInstanced
+    pub fn draw_instanced( &mut self, mesh: &Mesh, instances: &[Instance] ) {
+        if instances.is_empty() {
+            return;
+        }
+        self.upload(instances);
+        self.attach(mesh);
+        gl_state::bind_vertex_array(mesh.vao);
+        unsafe {
+            gl::DrawElementsInstanced(gl::TRIANGLES, mesh.index_count, gl::UNSIGNED_INT, null(), instances.len() as i32);
+        }
+    }
+}
+
+// Needs the context that created it to still be current, same as FrameCapture.
+// The attribute pointers live in the mesh VAOs, they go away with the meshes
+impl Drop for InstanceBuffer {
+    fn drop( &mut self ) {
+        unsafe { gl::DeleteBuffers(1, &self.vbo) };
+        gl_state::forget_buffer(self.vbo);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use glm::vec3;
+
+    use crate::scene::transform::{identity, Transform};
+
+    #[test]
+    fn standard_layout_packs_model_tint_and_layer() {
+        let layout = InstanceLayout::standard();
+        // Model over 4 locations, then one each for the tint and the layer
+        let locations = layout.attributes.iter().map( | ( attribute, location ) | ( *location, attribute.locations() ) ).collect::<Vec<_>>();
+        assert_eq!(locations, vec![ ( 4, 4 ), ( 8, 1 ), ( 9, 1 ) ]);
+        assert_eq!(layout.stride(), 16 + 4 + 1);
+
+        let model = Transform { translation: vec3(1.0, 2.0, 3.0), ..Transform::default() }.matrix();
+        let instances = [
+            Instance::new(model).with_tint([ 0.1, 0.2, 0.3, 0.4 ]).with_layer(2),
+            Instance::new(model),
+        ];
+        let data = layout.pack(&instances);
+        assert_eq!(data.len(), 2 * layout.stride());
+        // Column major, the translation is the last column
+        assert_eq!(&data[0..4], &[ 1.0, 0.0, 0.0, 0.0 ]);
+        assert_eq!(&data[12..16], &[ 1.0, 2.0, 3.0, 1.0 ]);
+        assert_eq!(&data[16..21], &[ 0.1, 0.2, 0.3, 0.4, 2.0 ]);
+        assert_eq!(&data[21 + 16..], &[ 1.0, 1.0, 1.0, 1.0, 0.0 ]);
+    }
+
+    #[test]
+    fn attributes_are_packed_in_layout_order() {
+        let layout = InstanceLayout::new().with(InstanceAttribute::Layer, 0).with(InstanceAttribute::Tint, 1);
+        assert_eq!(layout.stride(), 5);
+        let instance = Instance::new(identity()).with_tint([ 0.5, 0.5, 0.5, 1.0 ]).with_layer(7);
+        assert_eq!(layout.pack(&[ instance ]), vec![ 7.0, 0.5, 0.5, 0.5, 1.0 ]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    fn dropping_leaves_no_stale_bindings() {
+        use crate::{context::{context::GlContext, headless::HeadlessContext}, mesh::{layout::VertexLayout, mesh::MeshData}};
+
+        let context = HeadlessContext::new(4, 4).unwrap();
+        context.bind_target();
+        let mut data = MeshData::new();
+        for position in [ [ 0.0, 0.0, 0.0 ], [ 1.0, 0.0, 0.0 ], [ 0.0, 1.0, 0.0 ] ] {
+            data.push_vertex(position, [ 0.0, 0.0, 1.0 ], [ 0.0, 0.0 ]);
+        }
+        data.push_triangle(0, 1, 2);
+        let mesh = Mesh::new(&data, &VertexLayout::standard());
+        let mut buffer = InstanceBuffer::new(InstanceLayout::standard());
+        buffer.draw_instanced(&mesh, &[ Instance::new(identity()) ]);
+
+        let ( vao, vbo ) = ( mesh.vao, buffer.vbo );
+        drop(buffer);
+        drop(mesh);
+        unsafe {
+            assert_eq!(gl::IsVertexArray(vao), gl::FALSE);
+            assert_eq!(gl::IsBuffer(vbo), gl::FALSE);
+        }
+        // The cache followed GL back to 0 instead of keeping the deleted ids
+        assert_eq!(gl_state::verify(), Ok(()));
+    }
+}
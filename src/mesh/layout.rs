@@ -0,0 +1,87 @@
+// Vertex Layout -> which MeshData attributes go to which shader location
+use crate::mesh::mesh::MeshData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexAttribute {
+    Position,
+    Normal,
+    Uv,
+    Tangent,
+}
+
+impl VertexAttribute {
+    pub fn components(&self) -> usize {
+        match self {
+            VertexAttribute::Position | VertexAttribute::Normal => 3,
+            VertexAttribute::Uv => 2,
+            VertexAttribute::Tangent => 4,
+        }
+    }
+}
+
+// Attributes in buffer order, each with its `layout (location = N)`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VertexLayout {
+    pub attributes: Vec<(VertexAttribute, u32)>,
+}
+
+impl VertexLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with( mut self, attribute: VertexAttribute, location: u32 ) -> Self {
+        self.attributes.push(( attribute, location ));
+        self
+    }
+
+    // Position 0, Normal 1, UV 2, Tangent 3
+    pub fn standard() -> Self {
+        Self::new()
+            .with(VertexAttribute::Position, 0)
+            .with(VertexAttribute::Normal, 1)
+            .with(VertexAttribute::Uv, 2)
+            .with(VertexAttribute::Tangent, 3)
+    }
+
+    // Floats per vertex
+    pub fn stride(&self) -> usize {
+        self.attributes.iter().map( | ( attribute, _ ) | attribute.components() ).sum()
+    }
+
+    // One flat float array in layout order, missing attributes are filled with defaults
+    pub fn interleave( &self, mesh: &MeshData ) -> Vec<f32> {
+        let mut vertices = Vec::with_capacity(mesh.vertex_count() * self.stride());
+        for i in 0..mesh.vertex_count() {
+            for ( attribute, _ ) in &self.attributes {
+                match attribute {
+                    VertexAttribute::Position => vertices.extend_from_slice(&mesh.positions[i]),
+                    VertexAttribute::Normal => vertices.extend_from_slice(mesh.normals.get(i).unwrap_or(&[ 0.0, 0.0, 1.0 ])),
+                    VertexAttribute::Uv => vertices.extend_from_slice(mesh.uvs.get(i).unwrap_or(&[ 0.0, 0.0 ])),
+                    VertexAttribute::Tangent => vertices.extend_from_slice(mesh.tangents.get(i).unwrap_or(&[ 1.0, 0.0, 0.0, 1.0 ])),
+                }
+            }
+        }
+        vertices
+    }
+
+    // Sets the attribute pointers of the bound VAO / ARRAY_BUFFER
+    pub fn apply(&self) {
+        let stride = (self.stride() * size_of::<f32>()) as i32;
+        let mut offset = 0;
+        for ( attribute, location ) in &self.attributes {
+            unsafe {
+                gl::VertexAttribPointer(
+                    *location,
+                    attribute.components() as i32,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    stride,
+                    (offset * size_of::<f32>()) as *const _
+                );
+                gl::EnableVertexAttribArray(*location);
+            }
+            offset += attribute.components();
+        }
+    }
+}
@@ -0,0 +1,237 @@
+// Common Mesh format -> every generator / loader produces this
+use std::ptr::null;
+
+use crate::{mesh::layout::VertexLayout, renderer::{gl_debug::{self, gl_check}, gl_state}, utils::bounds::{Aabb, BoundingSphere}};
+
+// Indexed triangle list, counter-clockwise triangles are front facing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    // xyz along +U, w is the handedness -> bitangent = cross(normal, tangent) * w
+    pub tangents: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+}
+
+impl MeshData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    // Adds one vertex and returns its index
+    pub fn push_vertex( &mut self, position: [f32; 3], normal: [f32; 3], uv: [f32; 2] ) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push(uv);
+        (self.positions.len() - 1) as u32
+    }
+
+    pub fn push_triangle( &mut self, a: u32, b: u32, c: u32 ) {
+        self.indices.extend_from_slice(&[ a, b, c ]);
+    }
+
+    // Appends another mesh, its indices are shifted past our vertices
+    pub fn append( &mut self, other: &MeshData ) {
+        let offset = self.positions.len() as u32;
+        self.positions.extend_from_slice(&other.positions);
+        self.normals.extend_from_slice(&other.normals);
+        self.uvs.extend_from_slice(&other.uvs);
+        if self.tangents.len() == offset as usize && other.tangents.len() == other.positions.len() {
+            self.tangents.extend_from_slice(&other.tangents);
+        } else {
+            self.tangents.clear();
+        }
+        self.indices.extend(other.indices.iter().map( | index | index + offset ));
+    }
+
+    // Face normal of a triangle ( not normalized, length = 2 * area )
+    pub fn triangle_normal( &self, triangle: usize ) -> [f32; 3] {
+        let a = self.positions[self.indices[triangle * 3] as usize];
+        let b = self.positions[self.indices[triangle * 3 + 1] as usize];
+        let c = self.positions[self.indices[triangle * 3 + 2] as usize];
+        let ( ab, ac ) = ( sub(b, a), sub(c, a) );
+        cross(ab, ac)
+    }
+
+    // Smooth normals from the triangles sharing each vertex ( area weighted )
+    pub fn compute_normals(&mut self) {
+        let mut normals = vec![ [ 0.0f32; 3 ]; self.positions.len() ];
+        for triangle in 0..self.triangle_count() {
+            let face_normal = self.triangle_normal(triangle);
+            for corner in 0..3 {
+                let vertex = self.indices[triangle * 3 + corner] as usize;
+                for axis in 0..3 {
+                    normals[vertex][axis] += face_normal[axis];
+                }
+            }
+        }
+        self.normals = normals.into_iter().map(normalize).collect();
+    }
+
+    // Per-vertex tangents from the UV layout, summed over the triangles of each vertex
+    pub fn compute_tangents(&mut self) {
+        let mut tangents = vec![ [ 0.0f32; 3 ]; self.positions.len() ];
+        let mut bitangents = vec![ [ 0.0f32; 3 ]; self.positions.len() ];
+
+        for triangle in self.indices.chunks_exact(3) {
+            let ( a, b, c ) = ( triangle[0] as usize, triangle[1] as usize, triangle[2] as usize );
+            let ( edge1, edge2 ) = ( sub(self.positions[b], self.positions[a]), sub(self.positions[c], self.positions[a]) );
+            let ( du1, dv1 ) = ( self.uvs[b][0] - self.uvs[a][0], self.uvs[b][1] - self.uvs[a][1] );
+            let ( du2, dv2 ) = ( self.uvs[c][0] - self.uvs[a][0], self.uvs[c][1] - self.uvs[a][1] );
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() < 1e-12 {
+                continue;
+            }
+            let r = 1.0 / det;
+            let tangent = [
+                (edge1[0] * dv2 - edge2[0] * dv1) * r,
+                (edge1[1] * dv2 - edge2[1] * dv1) * r,
+                (edge1[2] * dv2 - edge2[2] * dv1) * r,
+            ];
+            let bitangent = [
+                (edge2[0] * du1 - edge1[0] * du2) * r,
+                (edge2[1] * du1 - edge1[1] * du2) * r,
+                (edge2[2] * du1 - edge1[2] * du2) * r,
+            ];
+            for &vertex in &[ a, b, c ] {
+                for axis in 0..3 {
+                    tangents[vertex][axis] += tangent[axis];
+                    bitangents[vertex][axis] += bitangent[axis];
+                }
+            }
+        }
+
+        self.tangents = (0..self.positions.len())
+                        .map( | vertex | {
+                            let normal = self.normals[vertex];
+                            // Gram-Schmidt -> make the tangent perpendicular to the normal
+                            let t = tangents[vertex];
+                            let n_dot_t = dot(normal, t);
+                            let mut tangent = normalize(sub(t, scale(normal, n_dot_t)));
+                            if dot(tangent, tangent) < 0.5 {
+                                tangent = any_perpendicular(normal);
+                            }
+                            let w = if dot(cross(normal, tangent), bitangents[vertex]) < 0.0 { -1.0 } else { 1.0 };
+                            [ tangent[0], tangent[1], tangent[2], w ]
+                        })
+                        .collect();
+    }
+
+    pub fn bounds(&self) -> Option<Aabb> {
+        Aabb::from_points(&self.positions)
+    }
+
+    pub fn bounding_sphere(&self) -> Option<BoundingSphere> {
+        BoundingSphere::from_points(&self.positions)
+    }
+}
+
+// Mesh uploaded to the GPU -> VAO + VBO + EBO
+pub struct Mesh {
+    pub vao: u32,
+    pub vbo: u32,
+    pub ebo: u32,
+    pub index_count: i32,
+    pub bounds: Option<Aabb>,
+}
+
+impl Mesh {
+    pub fn new( data: &MeshData, layout: &VertexLayout ) -> Self {
+        let vertices = layout.interleave(data);
+        let ( mut vao, mut vbo, mut ebo ) = ( 0, 0, 0 );
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::GenBuffers(1, &mut ebo);
+
+            gl_state::bind_vertex_array(vao);
+
+            gl_state::bind_buffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(gl::ARRAY_BUFFER, size_of_val(vertices.as_slice()) as isize, vertices.as_ptr() as *const _, gl::STATIC_DRAW);
+
+            gl_state::bind_buffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+            gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, size_of_val(data.indices.as_slice()) as isize, data.indices.as_ptr() as *const _, gl::STATIC_DRAW);
+
+            layout.apply();
+            gl_state::bind_vertex_array(0);
+        }
+
+        Self {
+            vao,
+            vbo,
+            ebo,
+            index_count: data.indices.len() as i32,
+            bounds: data.bounds(),
+        }
+    }
+
+    // Names the VAO and both buffers in debuggers and driver messages
+    pub fn label( &self, name: &str ) {
+        gl_debug::label(gl::VERTEX_ARRAY, self.vao, name);
+        gl_debug::label(gl::BUFFER, self.vbo, &format!("{} vertices", name));
+        gl_debug::label(gl::BUFFER, self.ebo, &format!("{} indices", name));
+    }
+
+    pub fn draw(&self) {
+        gl_state::bind_vertex_array(self.vao);
+        gl_check!(gl::DrawElements(gl::TRIANGLES, self.index_count, gl::UNSIGNED_INT, null()));
+    }
+}
+
+// Needs the context that created it to still be current, same as FrameCapture
+impl Drop for Mesh {
+    fn drop( &mut self ) {
+        let buffers = [ self.vbo, self.ebo ];
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(buffers.len() as i32, buffers.as_ptr());
+        }
+        gl_state::forget_vertex_array(self.vao);
+        for buffer in buffers {
+            gl_state::forget_buffer(buffer);
+        }
+    }
+}
+
+pub fn sub( a: [f32; 3], b: [f32; 3] ) -> [f32; 3] {
+    [ a[0] - b[0], a[1] - b[1], a[2] - b[2] ]
+}
+
+pub fn cross( a: [f32; 3], b: [f32; 3] ) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub fn dot( a: [f32; 3], b: [f32; 3] ) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub fn scale( v: [f32; 3], s: f32 ) -> [f32; 3] {
+    [ v[0] * s, v[1] * s, v[2] * s ]
+}
+
+// Used when the UVs can't give us a tangent ( degenerate triangles, poles )
+pub fn any_perpendicular( normal: [f32; 3] ) -> [f32; 3] {
+    let helper = if normal[0].abs() < 0.9 { [ 1.0, 0.0, 0.0 ] } else { [ 0.0, 1.0, 0.0 ] };
+    normalize(cross(helper, normal))
+}
+
+pub fn normalize( v: [f32; 3] ) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length == 0.0 {
+        return v;
+    }
+    [ v[0] / length, v[1] / length, v[2] / length ]
+}
@@ -0,0 +1,38 @@
+// Parallelogram centered on the origin
+use crate::mesh::mesh::MeshData;
+
+const FRONT: [f32; 3] = [ 0.0, 0.0, 1.0 ];
+
+// `skew` moves the top edge to the right ( negative -> left ), the texture follows the slant
+pub fn parallelogram( width: f32, height: f32, skew: f32 ) -> MeshData {
+    let ( half_w, half_h, half_skew ) = ( width / 2.0, height / 2.0, skew / 2.0 );
+    let mut mesh = MeshData::new();
+
+    mesh.push_vertex([ -half_w - half_skew, -half_h, 0.0 ], FRONT, [ 0.0, 0.0 ]); // Bottom left
+    mesh.push_vertex([  half_w - half_skew, -half_h, 0.0 ], FRONT, [ 1.0, 0.0 ]); // Bottom right
+    mesh.push_vertex([  half_w + half_skew,  half_h, 0.0 ], FRONT, [ 1.0, 1.0 ]); // Top right
+    mesh.push_vertex([ -half_w + half_skew,  half_h, 0.0 ], FRONT, [ 0.0, 1.0 ]); // Top left
+
+    mesh.push_triangle(0, 1, 2);
+    mesh.push_triangle(2, 3, 0);
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::other_shapes::shape_asserts::{assert_front_facing_with_uvs, signed_area};
+
+    #[test]
+    fn parallelogram_is_ccw_with_uvs_in_range() {
+        for skew in [ -0.4, 0.0, 0.25 ] {
+            let mesh = parallelogram(1.0, 0.5, skew);
+            assert_front_facing_with_uvs(&mesh);
+            for triangle in 0..mesh.triangle_count() {
+                // Each half is width * height / 2
+                let area = signed_area(&mesh, triangle);
+                assert!((area - 0.25).abs() < 1e-6, "triangle {} has signed area {}", triangle, area);
+            }
+        }
+    }
+}
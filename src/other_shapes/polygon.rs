@@ -0,0 +1,126 @@
+// Flat 2D shapes in the XY plane, facing +Z
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::mesh::mesh::MeshData;
+
+const FRONT: [f32; 3] = [ 0.0, 0.0, 1.0 ];
+
+// Point on a circle, angle 0 is straight up so a triangle points upwards
+fn on_circle( radius: f32, angle: f32 ) -> [f32; 3] {
+    [ radius * (angle + FRAC_PI_2).cos(), radius * (angle + FRAC_PI_2).sin(), 0.0 ]
+}
+
+// UV inside the square around a circle of `radius`
+fn circle_uv( position: [f32; 3], radius: f32 ) -> [f32; 2] {
+    [ position[0] / radius * 0.5 + 0.5, position[1] / radius * 0.5 + 0.5 ]
+}
+
+// Center vertex + one vertex per corner, drawn as a triangle fan
+pub fn regular_polygon( sides: u32, radius: f32 ) -> MeshData {
+    assert!(sides >= 3, "A polygon needs at least 3 sides");
+    let mut mesh = MeshData::new();
+    let center = mesh.push_vertex([ 0.0, 0.0, 0.0 ], FRONT, [ 0.5, 0.5 ]);
+
+    for side in 0..sides {
+        let position = on_circle(radius, side as f32 / sides as f32 * TAU);
+        mesh.push_vertex(position, FRONT, circle_uv(position, radius));
+    }
+
+    for side in 0..sides {
+        let next = (side + 1) % sides;
+        mesh.push_triangle(center, side + 1, next + 1);
+    }
+    mesh
+}
+
+// A disc is a polygon with enough sides to look round
+pub fn circle( radius: f32, segments: u32 ) -> MeshData {
+    regular_polygon(segments, radius)
+}
+
+// Flat annulus, the UVs match a circle of `outer_radius` so a texture isn't squashed
+pub fn ring( inner_radius: f32, outer_radius: f32, segments: u32 ) -> MeshData {
+    assert!(segments >= 3, "A ring needs at least 3 segments");
+    assert!(inner_radius < outer_radius, "Inner radius must be smaller than the outer radius");
+    let mut mesh = MeshData::new();
+
+    for segment in 0..segments {
+        let angle = segment as f32 / segments as f32 * TAU;
+        let inner = on_circle(inner_radius, angle);
+        let outer = on_circle(outer_radius, angle);
+        mesh.push_vertex(inner, FRONT, circle_uv(inner, outer_radius));
+        mesh.push_vertex(outer, FRONT, circle_uv(outer, outer_radius));
+    }
+
+    for segment in 0..segments {
+        let ( inner, outer ) = ( segment * 2, segment * 2 + 1 );
+        let next = (segment + 1) % segments;
+        let ( next_inner, next_outer ) = ( next * 2, next * 2 + 1 );
+        mesh.push_triangle(inner, outer, next_outer);
+        mesh.push_triangle(inner, next_outer, next_inner);
+    }
+    mesh
+}
+
+// Star with `points` tips, alternating between the outer and inner radius
+pub fn star( points: u32, inner_radius: f32, outer_radius: f32 ) -> MeshData {
+    assert!(points >= 2, "A star needs at least 2 points");
+    let mut mesh = MeshData::new();
+    let center = mesh.push_vertex([ 0.0, 0.0, 0.0 ], FRONT, [ 0.5, 0.5 ]);
+    let corners = points * 2;
+
+    for corner in 0..corners {
+        let radius = if corner % 2 == 0 { outer_radius } else { inner_radius };
+        let position = on_circle(radius, corner as f32 / corners as f32 * TAU);
+        mesh.push_vertex(position, FRONT, circle_uv(position, outer_radius));
+    }
+
+    for corner in 0..corners {
+        let next = (corner + 1) % corners;
+        mesh.push_triangle(center, corner + 1, next + 1);
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::other_shapes::shape_asserts::{assert_circle_uvs, assert_front_facing_with_uvs};
+
+    #[test]
+    fn regular_polygons_are_ccw_with_uvs_in_range() {
+        for sides in [ 3, 4, 5, 6, 12 ] {
+            let mesh = regular_polygon(sides, 0.5);
+            assert_eq!(mesh.vertex_count(), sides as usize + 1);
+            assert_eq!(mesh.triangle_count(), sides as usize);
+            assert_front_facing_with_uvs(&mesh);
+            assert_circle_uvs(&mesh, 0.5);
+        }
+    }
+
+    #[test]
+    fn circle_is_ccw_with_uvs_in_range() {
+        let mesh = circle(2.0, 48);
+        assert_eq!(mesh.triangle_count(), 48);
+        assert_front_facing_with_uvs(&mesh);
+        assert_circle_uvs(&mesh, 2.0);
+    }
+
+    #[test]
+    fn ring_is_ccw_with_uvs_in_range() {
+        let mesh = ring(0.25, 1.0, 32);
+        assert_eq!(mesh.vertex_count(), 64);
+        assert_eq!(mesh.triangle_count(), 64);
+        assert_front_facing_with_uvs(&mesh);
+        // The hole is cut out of the same texture square
+        assert_circle_uvs(&mesh, 1.0);
+    }
+
+    #[test]
+    fn star_is_ccw_with_uvs_in_range() {
+        let mesh = star(5, 0.4, 1.0);
+        assert_eq!(mesh.triangle_count(), 10);
+        assert_front_facing_with_uvs(&mesh);
+        assert_circle_uvs(&mesh, 1.0);
+    }
+}
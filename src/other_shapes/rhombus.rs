@@ -1,10 +1,35 @@
-// Initialize the GLFW
-// Run a loop until we've a running window
+// Rhombus centered on the origin, described by its two diagonals
+use crate::mesh::mesh::MeshData;
 
-// mod rhombus {
-//     use glfw;
+const FRONT: [f32; 3] = [ 0.0, 0.0, 1.0 ];
 
-//     // Initialize our GLFW
-//     glfw::init( glfw::fail_on_errors!() )
+// Corners sit on the middle of each edge of the bounding rectangle, UVs are planar
+pub fn rhombus( width: f32, height: f32 ) -> MeshData {
+    let ( half_w, half_h ) = ( width / 2.0, height / 2.0 );
+    let mut mesh = MeshData::new();
 
-// }
\ No newline at end of file
+    mesh.push_vertex([ 0.0, -half_h, 0.0 ], FRONT, [ 0.5, 0.0 ]); // Bottom
+    mesh.push_vertex([ half_w, 0.0, 0.0 ], FRONT, [ 1.0, 0.5 ]);  // Right
+    mesh.push_vertex([ 0.0, half_h, 0.0 ], FRONT, [ 0.5, 1.0 ]);  // Top
+    mesh.push_vertex([ -half_w, 0.0, 0.0 ], FRONT, [ 0.0, 0.5 ]); // Left
+
+    mesh.push_triangle(0, 1, 2);
+    mesh.push_triangle(2, 3, 0);
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::other_shapes::shape_asserts::{assert_front_facing_with_uvs, assert_uvs};
+
+    #[test]
+    fn rhombus_is_ccw_with_uvs_in_range() {
+        for ( width, height ) in [ ( 0.7, 0.6 ), ( 0.2, 1.5 ) ] {
+            let mesh = rhombus(width, height);
+            assert_front_facing_with_uvs(&mesh);
+            // Planar over the bounding rectangle
+            assert_uvs(&mesh, | position | [ position[0] / width + 0.5, position[1] / height + 0.5 ]);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+// Checks shared by the flat shape tests
+use crate::mesh::mesh::MeshData;
+
+// The face normal is the plain cross product -> its z is twice the area, positive when counter-clockwise from +Z
+pub fn signed_area( mesh: &MeshData, triangle: usize ) -> f32 {
+    mesh.triangle_normal(triangle)[2] / 2.0
+}
+
+// Every triangle counter-clockwise seen from +Z, every UV inside the texture
+pub fn assert_front_facing_with_uvs( mesh: &MeshData ) {
+    assert!(mesh.triangle_count() > 0);
+    for triangle in 0..mesh.triangle_count() {
+        let area = signed_area(mesh, triangle);
+        assert!(area > 0.0, "triangle {} has signed area {}", triangle, area);
+    }
+    for uv in &mesh.uvs {
+        assert!(uv.iter().all( | c | (-1e-6..=1.0 + 1e-6).contains(c) ), "uv {:?} outside 0..1", uv);
+    }
+}
+
+// Each vertex has exactly the UV `expected` gives for its position
+pub fn assert_uvs( mesh: &MeshData, expected: impl Fn([f32; 3]) -> [f32; 2] ) {
+    assert_eq!(mesh.uvs.len(), mesh.vertex_count());
+    for ( position, uv ) in mesh.positions.iter().zip(&mesh.uvs) {
+        let want = expected(*position);
+        assert!((uv[0] - want[0]).abs() < 1e-5 && (uv[1] - want[1]).abs() < 1e-5, "{:?} has uv {:?}, expected {:?}", position, uv, want);
+    }
+}
+
+// The texture square stretched over a circle of `radius` -> uv = position / radius * 0.5 + 0.5
+pub fn assert_circle_uvs( mesh: &MeshData, radius: f32 ) {
+    assert_uvs(mesh, | position | [ position[0] / radius * 0.5 + 0.5, position[1] / radius * 0.5 + 0.5 ]);
+}
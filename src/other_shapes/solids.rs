@@ -0,0 +1,488 @@
+// 3D Shapes -> all centered on the origin, +Y is up, tangents are filled in
+use std::{collections::HashMap, f32::consts::{FRAC_PI_2, PI, TAU}};
+
+use crate::mesh::mesh::{normalize, MeshData};
+
+// One point of a shape of revolution: ( radius, y, normal radius, normal y )
+type ProfilePoint = [f32; 4];
+
+// Spins a profile ( ordered from top to bottom ) around the Y axis.
+// The seam column is duplicated so U can go from 0 to 1 without wrapping.
+fn revolve( profile: &[ProfilePoint], segments: u32 ) -> MeshData {
+    assert!(segments >= 3, "Need at least 3 segments around the Y axis");
+    let mut mesh = MeshData::new();
+
+    // V follows the length of the profile, 1 at the top and 0 at the bottom
+    let mut lengths = vec![ 0.0f32 ];
+    for pair in profile.windows(2) {
+        let ( dr, dy ) = ( pair[1][0] - pair[0][0], pair[1][1] - pair[0][1] );
+        lengths.push(lengths.last().unwrap() + (dr * dr + dy * dy).sqrt());
+    }
+    let total_length = lengths.last().unwrap().max(f32::EPSILON);
+
+    for ( ring, point ) in profile.iter().enumerate() {
+        let [ radius, y, normal_r, normal_y ] = *point;
+        for segment in 0..=segments {
+            let u = segment as f32 / segments as f32;
+            let ( sin, cos ) = (u * TAU).sin_cos();
+            mesh.push_vertex(
+                [ radius * sin, y, radius * cos ],
+                normalize([ normal_r * sin, normal_y, normal_r * cos ]),
+                [ u, 1.0 - lengths[ring] / total_length ],
+            );
+        }
+    }
+
+    let columns = segments + 1;
+    for ring in 0..(profile.len() as u32 - 1) {
+        // Rings with no radius ( poles, cone tip ) would give zero-area triangles
+        let top_closed = profile[ring as usize][0].abs() < 1e-6;
+        let bottom_closed = profile[ring as usize + 1][0].abs() < 1e-6;
+        for segment in 0..segments {
+            let top = ring * columns + segment;
+            let bottom = top + columns;
+            if !bottom_closed {
+                mesh.push_triangle(top, bottom, bottom + 1);
+            }
+            if !top_closed {
+                mesh.push_triangle(top, bottom + 1, top + 1);
+            }
+        }
+    }
+    mesh
+}
+
+// Flat disc at height `y`, facing up or down
+fn cap( radius: f32, y: f32, segments: u32, facing_up: bool ) -> MeshData {
+    let normal = if facing_up { [ 0.0, 1.0, 0.0 ] } else { [ 0.0, -1.0, 0.0 ] };
+    let mut mesh = MeshData::new();
+    let center = mesh.push_vertex([ 0.0, y, 0.0 ], normal, [ 0.5, 0.5 ]);
+
+    for segment in 0..segments {
+        let ( sin, cos ) = (segment as f32 / segments as f32 * TAU).sin_cos();
+        // Looking from above, +X is right and +Z is towards the viewer -> flip V
+        let uv = if facing_up { [ 0.5 + sin * 0.5, 0.5 - cos * 0.5 ] } else { [ 0.5 + sin * 0.5, 0.5 + cos * 0.5 ] };
+        mesh.push_vertex([ radius * sin, y, radius * cos ], normal, uv);
+    }
+
+    for segment in 0..segments {
+        let ( current, next ) = ( segment + 1, (segment + 1) % segments + 1 );
+        if facing_up {
+            mesh.push_triangle(center, current, next);
+        } else {
+            mesh.push_triangle(center, next, current);
+        }
+    }
+    mesh
+}
+
+// Grid of ( subdivisions + 1 )^2 vertices spanning `u_axis` x `v_axis`, u x v must equal the normal
+fn grid( origin: [f32; 3], u_axis: [f32; 3], v_axis: [f32; 3], normal: [f32; 3], u_steps: u32, v_steps: u32 ) -> MeshData {
+    let mut mesh = MeshData::new();
+    for row in 0..=v_steps {
+        for column in 0..=u_steps {
+            let ( u, v ) = ( column as f32 / u_steps as f32, row as f32 / v_steps as f32 );
+            let position = [
+                origin[0] + u_axis[0] * u + v_axis[0] * v,
+                origin[1] + u_axis[1] * u + v_axis[1] * v,
+                origin[2] + u_axis[2] * u + v_axis[2] * v,
+            ];
+            mesh.push_vertex(position, normal, [ u, v ]);
+        }
+    }
+
+    let columns = u_steps + 1;
+    for row in 0..v_steps {
+        for column in 0..u_steps {
+            let bottom_left = row * columns + column;
+            let top_left = bottom_left + columns;
+            mesh.push_triangle(bottom_left, bottom_left + 1, top_left + 1);
+            mesh.push_triangle(bottom_left, top_left + 1, top_left);
+        }
+    }
+    mesh
+}
+
+// Cube with `subdivisions` quads along every edge, each face has the full texture
+pub fn cube( size: f32, subdivisions: u32 ) -> MeshData {
+    let steps = subdivisions.max(1);
+    let h = size / 2.0;
+    // normal, u axis, v axis
+    let faces: [[[f32; 3]; 3]; 6] = [
+        [ [  1.0, 0.0, 0.0 ], [ 0.0, 0.0, -1.0 ], [ 0.0, 1.0, 0.0 ] ], // Right
+        [ [ -1.0, 0.0, 0.0 ], [ 0.0, 0.0,  1.0 ], [ 0.0, 1.0, 0.0 ] ], // Left
+        [ [ 0.0,  1.0, 0.0 ], [ 1.0, 0.0, 0.0 ], [ 0.0, 0.0, -1.0 ] ], // Top
+        [ [ 0.0, -1.0, 0.0 ], [ 1.0, 0.0, 0.0 ], [ 0.0, 0.0,  1.0 ] ], // Bottom
+        [ [ 0.0, 0.0,  1.0 ], [  1.0, 0.0, 0.0 ], [ 0.0, 1.0, 0.0 ] ], // Front
+        [ [ 0.0, 0.0, -1.0 ], [ -1.0, 0.0, 0.0 ], [ 0.0, 1.0, 0.0 ] ], // Back
+    ];
+
+    let mut mesh = MeshData::new();
+    for [ normal, u_axis, v_axis ] in faces {
+        let origin = [
+            (normal[0] - u_axis[0] - v_axis[0]) * h,
+            (normal[1] - u_axis[1] - v_axis[1]) * h,
+            (normal[2] - u_axis[2] - v_axis[2]) * h,
+        ];
+        let u_edge = [ u_axis[0] * size, u_axis[1] * size, u_axis[2] * size ];
+        let v_edge = [ v_axis[0] * size, v_axis[1] * size, v_axis[2] * size ];
+        mesh.append(&grid(origin, u_edge, v_edge, normal, steps, steps));
+    }
+    mesh.compute_tangents();
+    mesh
+}
+
+// Flat plane on XZ facing +Y
+pub fn plane( width: f32, depth: f32, subdivisions_x: u32, subdivisions_z: u32 ) -> MeshData {
+    let mut mesh = grid(
+        [ -width / 2.0, 0.0, depth / 2.0 ],
+        [ width, 0.0, 0.0 ],
+        [ 0.0, 0.0, -depth ],
+        [ 0.0, 1.0, 0.0 ],
+        subdivisions_x.max(1),
+        subdivisions_z.max(1),
+    );
+    mesh.compute_tangents();
+    mesh
+}
+
+// Latitude / longitude sphere, `rings` runs from pole to pole
+pub fn uv_sphere( radius: f32, segments: u32, rings: u32 ) -> MeshData {
+    let rings = rings.max(2);
+    let profile = (0..=rings)
+                    .map( | ring | {
+                        let angle = ring as f32 / rings as f32 * PI;
+                        let ( sin, cos ) = angle.sin_cos();
+                        // Exactly zero at the poles so `revolve` skips the degenerate triangles
+                        let sin = if ring == 0 || ring == rings { 0.0 } else { sin };
+                        [ radius * sin, radius * cos, sin, cos ]
+                    })
+                    .collect::<Vec<ProfilePoint>>();
+    let mut mesh = revolve(&profile, segments);
+    mesh.compute_tangents();
+    mesh
+}
+
+// Subdivided icosahedron, evenly spread triangles without pinched poles
+pub fn icosphere( radius: f32, subdivisions: u32 ) -> MeshData {
+    let t = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let mut positions: Vec<[f32; 3]> = [
+        [ -1.0,  t, 0.0 ], [ 1.0,  t, 0.0 ], [ -1.0, -t, 0.0 ], [ 1.0, -t, 0.0 ],
+        [ 0.0, -1.0,  t ], [ 0.0, 1.0,  t ], [ 0.0, -1.0, -t ], [ 0.0, 1.0, -t ],
+        [  t, 0.0, -1.0 ], [  t, 0.0, 1.0 ], [ -t, 0.0, -1.0 ], [ -t, 0.0, 1.0 ],
+    ].iter().map( | p | normalize(*p) ).collect();
+
+    let mut triangles: Vec<[u32; 3]> = vec![
+        [ 0, 11, 5 ], [ 0, 5, 1 ], [ 0, 1, 7 ], [ 0, 7, 10 ], [ 0, 10, 11 ],
+        [ 1, 5, 9 ], [ 5, 11, 4 ], [ 11, 10, 2 ], [ 10, 7, 6 ], [ 7, 1, 8 ],
+        [ 3, 9, 4 ], [ 3, 4, 2 ], [ 3, 2, 6 ], [ 3, 6, 8 ], [ 3, 8, 9 ],
+        [ 4, 9, 5 ], [ 2, 4, 11 ], [ 6, 2, 10 ], [ 8, 6, 7 ], [ 9, 8, 1 ],
+    ];
+
+    // Split every triangle in 4, shared edges reuse the same midpoint
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut midpoint = | a: u32, b: u32, positions: &mut Vec<[f32; 3]> | -> u32 {
+            let key = ( a.min(b), a.max(b) );
+            *midpoints.entry(key).or_insert_with( || {
+                let ( pa, pb ) = ( positions[a as usize], positions[b as usize] );
+                positions.push(normalize([ pa[0] + pb[0], pa[1] + pb[1], pa[2] + pb[2] ]));
+                (positions.len() - 1) as u32
+            })
+        };
+
+        let mut next = Vec::with_capacity(triangles.len() * 4);
+        for [ a, b, c ] in triangles {
+            let ab = midpoint(a, b, &mut positions);
+            let bc = midpoint(b, c, &mut positions);
+            let ca = midpoint(c, a, &mut positions);
+            next.extend_from_slice(&[ [ a, ab, ca ], [ b, bc, ab ], [ c, ca, bc ], [ ab, bc, ca ] ]);
+        }
+        triangles = next;
+    }
+
+    let mut mesh = MeshData::new();
+    for position in &positions {
+        let u = 0.5 + position[0].atan2(position[2]) / TAU;
+        let v = 0.5 + position[1].clamp(-1.0, 1.0).asin() / PI;
+        mesh.push_vertex([ position[0] * radius, position[1] * radius, position[2] * radius ], *position, [ u, v ]);
+    }
+
+    // Seam -> triangles whose U wraps around get their own copies of the low-U vertices
+    let is_pole = | normal: [f32; 3] | normal[1].abs() > 1.0 - 1e-6;
+    let mut seam_copies: HashMap<u32, u32> = HashMap::new();
+    for triangle in triangles.iter_mut() {
+        let corners = triangle.map( | index | index as usize );
+        let crosses_seam = if corners.iter().any( | &index | is_pole(mesh.normals[index]) ) {
+            // Pole U is meaningless, look at the other two corners only
+            let us = corners.iter().filter( | &&index | !is_pole(mesh.normals[index]) ).map( | &index | mesh.uvs[index][0] ).collect::<Vec<f32>>();
+            us.len() == 2 && (us[0] - us[1]).abs() > 0.5
+        } else {
+            // Front facing triangles are counter-clockwise in UV space too, wrapped ones flip
+            let [ a, b, c ] = corners.map( | index | mesh.uvs[index] );
+            (b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1]) < 0.0
+        };
+        if !crosses_seam {
+            continue;
+        }
+        for index in triangle.iter_mut() {
+            if mesh.uvs[*index as usize][0] < 0.5 {
+                let original = *index;
+                *index = *seam_copies.entry(original).or_insert_with( || {
+                    let [ u, v ] = mesh.uvs[original as usize];
+                    mesh.push_vertex(mesh.positions[original as usize], mesh.normals[original as usize], [ u + 1.0, v ])
+                });
+            }
+        }
+    }
+
+    // Poles -> one copy per triangle with U in the middle of the other two corners
+    for triangle in triangles.iter_mut() {
+        for corner in 0..3 {
+            let index = triangle[corner] as usize;
+            if !is_pole(mesh.normals[index]) {
+                continue;
+            }
+            let ( other1, other2 ) = ( triangle[(corner + 1) % 3] as usize, triangle[(corner + 2) % 3] as usize );
+            let u = (mesh.uvs[other1][0] + mesh.uvs[other2][0]) / 2.0;
+            let v = mesh.uvs[index][1];
+            triangle[corner] = mesh.push_vertex(mesh.positions[index], mesh.normals[index], [ u, v ]);
+        }
+    }
+
+    // Rebuilt from the final faces -> vertices the seam / pole copies replaced are left out
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut compact = MeshData::new();
+    for triangle in triangles {
+        let [ a, b, c ] = triangle.map( | index | {
+            *remap.entry(index).or_insert_with( || {
+                let index = index as usize;
+                compact.push_vertex(mesh.positions[index], mesh.normals[index], mesh.uvs[index])
+            })
+        });
+        compact.push_triangle(a, b, c);
+    }
+    compact.compute_tangents();
+    compact
+}
+
+// Cylinder along Y with `height_segments` rings on the side, closed with caps
+pub fn cylinder( radius: f32, height: f32, segments: u32, height_segments: u32 ) -> MeshData {
+    let steps = height_segments.max(1);
+    let profile = (0..=steps)
+                    .map( | step | [ radius, height / 2.0 - height * step as f32 / steps as f32, 1.0, 0.0 ] )
+                    .collect::<Vec<ProfilePoint>>();
+
+    let mut mesh = revolve(&profile, segments);
+    mesh.append(&cap(radius, height / 2.0, segments, true));
+    mesh.append(&cap(radius, -height / 2.0, segments, false));
+    mesh.compute_tangents();
+    mesh
+}
+
+// Cone along Y with the tip at +height / 2, the tip vertex is split per segment for smooth shading
+pub fn cone( radius: f32, height: f32, segments: u32, height_segments: u32 ) -> MeshData {
+    let steps = height_segments.max(1);
+    // Side normal is perpendicular to the slant
+    let slant = (radius * radius + height * height).sqrt();
+    let ( normal_r, normal_y ) = ( height / slant, radius / slant );
+    let profile = (0..=steps)
+                    .map( | step | {
+                        let t = step as f32 / steps as f32;
+                        [ radius * t, height / 2.0 - height * t, normal_r, normal_y ]
+                    })
+                    .collect::<Vec<ProfilePoint>>();
+
+    let mut mesh = revolve(&profile, segments);
+    mesh.append(&cap(radius, -height / 2.0, segments, false));
+    mesh.compute_tangents();
+    mesh
+}
+
+// Donut around the Y axis, `major_radius` is the distance to the middle of the tube
+pub fn torus( major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32 ) -> MeshData {
+    let minor_segments = minor_segments.max(3);
+    // Walk around the tube starting at the top, going down the outside first
+    let profile = (0..=minor_segments)
+                    .map( | step | {
+                        let angle = FRAC_PI_2 - step as f32 / minor_segments as f32 * TAU;
+                        let ( sin, cos ) = angle.sin_cos();
+                        [ major_radius + minor_radius * cos, minor_radius * sin, cos, sin ]
+                    })
+                    .collect::<Vec<ProfilePoint>>();
+
+    let mut mesh = revolve(&profile, major_segments);
+    mesh.compute_tangents();
+    mesh
+}
+
+// Cylinder of `height` with a half sphere on each end, total height is height + 2 * radius
+pub fn capsule( radius: f32, height: f32, segments: u32, hemisphere_rings: u32 ) -> MeshData {
+    let rings = hemisphere_rings.max(1);
+    let mut profile: Vec<ProfilePoint> = Vec::new();
+
+    // Top half sphere, from the pole down to the equator
+    for ring in 0..=rings {
+        let angle = ring as f32 / rings as f32 * FRAC_PI_2;
+        let ( sin, cos ) = angle.sin_cos();
+        let sin = if ring == 0 { 0.0 } else { sin };
+        profile.push([ radius * sin, height / 2.0 + radius * cos, sin, cos ]);
+    }
+    // Bottom half sphere, from the equator down to the pole
+    for ring in 0..=rings {
+        let angle = FRAC_PI_2 + ring as f32 / rings as f32 * FRAC_PI_2;
+        let ( sin, cos ) = angle.sin_cos();
+        let sin = if ring == rings { 0.0 } else { sin };
+        profile.push([ radius * sin, -height / 2.0 + radius * cos, sin, cos ]);
+    }
+
+    let mut mesh = revolve(&profile, segments);
+    mesh.compute_tangents();
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    // Seam and pole copies share a position -> weld them before looking at edges
+    fn welded( mesh: &MeshData ) -> Vec<usize> {
+        let mut ids: HashMap<[i64; 3], usize> = HashMap::new();
+        mesh.positions
+            .iter()
+            .map( | p | {
+                let key = p.map( | c | (c * 1e4).round() as i64 );
+                let next = ids.len();
+                *ids.entry(key).or_insert(next)
+            })
+            .collect()
+    }
+
+    // Closed surface -> every edge is used once in each direction by two triangles
+    fn assert_watertight( mesh: &MeshData ) {
+        let ids = welded(mesh);
+        let mut edges: HashMap<(usize, usize), i32> = HashMap::new();
+        for triangle in mesh.indices.chunks_exact(3) {
+            for corner in 0..3 {
+                let ( a, b ) = ( ids[triangle[corner] as usize], ids[triangle[(corner + 1) % 3] as usize] );
+                assert_ne!(a, b, "degenerate triangle {:?}", triangle);
+                *edges.entry(( a, b )).or_default() += 1;
+            }
+        }
+        for ( ( a, b ), count ) in &edges {
+            assert_eq!(*count, 1, "edge {} -> {} is used {} times", a, b, count);
+            assert_eq!(edges.get(&( *b, *a )), Some(&1), "edge {} -> {} has no opposite, the surface has a hole", a, b);
+        }
+    }
+
+    fn assert_unit_normals( mesh: &MeshData ) {
+        assert_eq!(mesh.normals.len(), mesh.vertex_count());
+        for normal in &mesh.normals {
+            let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+            assert!((length - 1.0).abs() < 1e-4, "normal {:?} has length {}", normal, length);
+        }
+    }
+
+    // Normal mapping needs an orthonormal frame -> unit tangent, perpendicular to the normal, w picks the bitangent side
+    fn assert_tangent_frames( mesh: &MeshData ) {
+        assert_eq!(mesh.tangents.len(), mesh.vertex_count());
+        for ( tangent, normal ) in mesh.tangents.iter().zip(&mesh.normals) {
+            let length = (tangent[0] * tangent[0] + tangent[1] * tangent[1] + tangent[2] * tangent[2]).sqrt();
+            assert!((length - 1.0).abs() < 1e-4, "tangent {:?} has length {}", tangent, length);
+            let n_dot_t = tangent[0] * normal[0] + tangent[1] * normal[1] + tangent[2] * normal[2];
+            assert!(n_dot_t.abs() < 1e-4, "tangent {:?} is not perpendicular to normal {:?}", tangent, normal);
+            assert!(tangent[3] == 1.0 || tangent[3] == -1.0, "tangent {:?} has handedness {}", tangent, tangent[3]);
+        }
+    }
+
+    fn assert_every_vertex_used( mesh: &MeshData ) {
+        let mut used = vec![ false; mesh.vertex_count() ];
+        for index in &mesh.indices {
+            used[*index as usize] = true;
+        }
+        assert!(used.iter().all( | used | *used ), "{} of {} vertices are unreferenced", used.iter().filter( | used | !**used ).count(), used.len());
+    }
+
+    #[test]
+    fn cube_counts_and_watertight() {
+        let mesh = cube(1.0, 1);
+        assert_eq!(( mesh.vertex_count(), mesh.triangle_count() ), ( 24, 12 ));
+        let mesh = cube(2.0, 3);
+        assert_eq!(( mesh.vertex_count(), mesh.triangle_count() ), ( 6 * 16, 6 * 18 ));
+        assert_watertight(&mesh);
+        assert_unit_normals(&mesh);
+        assert_tangent_frames(&mesh);
+    }
+
+    #[test]
+    fn plane_counts_and_normals() {
+        let mesh = plane(2.0, 1.0, 4, 2);
+        assert_eq!(( mesh.vertex_count(), mesh.triangle_count() ), ( 15, 16 ));
+        assert_unit_normals(&mesh);
+        assert_tangent_frames(&mesh);
+    }
+
+    #[test]
+    fn uv_sphere_counts_and_watertight() {
+        let ( segments, rings ) = ( 16, 8 );
+        let mesh = uv_sphere(1.0, segments, rings);
+        assert_eq!(mesh.vertex_count(), ((rings + 1) * (segments + 1)) as usize);
+        // Pole rows have one triangle per segment, the rest two
+        assert_eq!(mesh.triangle_count(), (segments * 2 + (rings - 2) * segments * 2) as usize);
+        assert_watertight(&mesh);
+        assert_unit_normals(&mesh);
+        assert_tangent_frames(&mesh);
+    }
+
+    #[test]
+    fn icosphere_counts_and_watertight() {
+        for subdivisions in 0..4 {
+            let mesh = icosphere(1.0, subdivisions);
+            assert_eq!(mesh.triangle_count(), 20 * 4usize.pow(subdivisions));
+            assert_every_vertex_used(&mesh);
+            assert_watertight(&mesh);
+            assert_unit_normals(&mesh);
+            assert_tangent_frames(&mesh);
+            // Every distinct position is on the sphere, seam copies are the only duplicates
+            let unique = welded(&mesh).into_iter().max().unwrap() + 1;
+            assert_eq!(unique, 10 * 4usize.pow(subdivisions) + 2);
+        }
+    }
+
+    #[test]
+    fn cylinder_cone_torus_capsule_are_watertight() {
+        let meshes = [
+            ( "cylinder", cylinder(0.5, 2.0, 12, 3) ),
+            ( "cone", cone(0.5, 1.0, 12, 2) ),
+            ( "torus", torus(1.0, 0.25, 16, 8) ),
+            ( "capsule", capsule(0.5, 1.0, 12, 4) ),
+        ];
+        for ( name, mesh ) in meshes {
+            assert!(mesh.triangle_count() > 0, "{}", name);
+            assert_watertight(&mesh);
+            assert_unit_normals(&mesh);
+            assert_tangent_frames(&mesh);
+        }
+        // Side: ( height_segments + 1 ) rings of segments + 1, caps: center + segments each
+        assert_eq!(cylinder(0.5, 2.0, 12, 3).vertex_count(), 4 * 13 + 2 * 13);
+        assert_eq!(torus(1.0, 0.25, 16, 8).vertex_count(), 9 * 17);
+        assert_every_vertex_used(&cylinder(0.5, 2.0, 12, 3));
+        assert_every_vertex_used(&torus(1.0, 0.25, 16, 8));
+    }
+
+    #[test]
+    fn cone_and_capsule_counts() {
+        // Side: ( height_segments + 1 ) rings, the tip row has one triangle per segment -> its seam copy is never
+        // referenced. Base cap: center + segments
+        let mesh = cone(0.5, 1.0, 12, 2);
+        assert_eq!(mesh.vertex_count(), 3 * 13 + 13);
+        assert_eq!(mesh.triangle_count(), 12 + 2 * 12 + 12);
+
+        // Two rows of rings + 1 per half sphere, both pole rows have one triangle per segment
+        let mesh = capsule(0.5, 1.0, 12, 4);
+        assert_eq!(mesh.vertex_count(), 2 * 5 * 13);
+        assert_eq!(mesh.triangle_count(), 2 * 12 + 7 * 2 * 12);
+    }
+}
@@ -0,0 +1,240 @@
+// Frame Capture -> screenshots and numbered frame sequences as PNG
+// Reads go into pixel buffer objects, the copy back to the CPU happens once the GPU is done
+use std::{collections::VecDeque, fs, path::{Path, PathBuf}, ptr::null_mut};
+
+use image::RgbaImage;
+
+use crate::{app::{app::App, runner::run_frames}, context::context::GlContext, logging::logging::APP, renderer::gl_state};
+
+// What to read -> framebuffer 0 is the window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureSource {
+    pub framebuffer: u32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl CaptureSource {
+    // Offscreen targets only exist headless, windows read framebuffer 0
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    pub fn of( framebuffer: &crate::renderer::framebuffer::Framebuffer ) -> Self {
+        Self { framebuffer: framebuffer.id, width: framebuffer.width, height: framebuffer.height }
+    }
+}
+
+// GL rows start at the bottom, images at the top -> same flip as `flipv` in load_image_into_cpu
+fn to_image( width: i32, height: i32, pixels: Vec<u8> ) -> RgbaImage {
+    let image = RgbaImage::from_raw(width as u32, height as u32, pixels).expect("buffer matches the capture size");
+    image::imageops::flip_vertical(&image)
+}
+
+fn bind_source( source: CaptureSource ) {
+    gl_state::bind_framebuffer(gl::READ_FRAMEBUFFER, source.framebuffer);
+    unsafe {
+        gl::ReadBuffer(if source.framebuffer == 0 { gl::BACK } else { gl::COLOR_ATTACHMENT0 });
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+    }
+}
+
+// Blocking read, fine for tests and single screenshots
+#[cfg(all(feature = "headless", target_os = "linux"))]
+pub fn read_pixels( source: CaptureSource ) -> RgbaImage {
+    let mut pixels = vec![ 0u8; (source.width * source.height * 4) as usize ];
+    bind_source(source);
+    unsafe {
+        gl::ReadPixels(0, 0, source.width, source.height, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut _);
+    }
+    to_image(source.width, source.height, pixels)
+}
+
+struct PendingCapture {
+    pbo: u32,
+    fence: gl::types::GLsync,
+    width: i32,
+    height: i32,
+    path: PathBuf,
+}
+
+pub struct FrameCapture {
+    pending: VecDeque<PendingCapture>,
+    // PBOs whose data was already saved
+    free: Vec<u32>,
+}
+
+impl FrameCapture {
+    pub fn new() -> Self {
+        Self { pending: VecDeque::new(), free: Vec::new() }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    // Starts the read, the PNG is written by a later poll() / finish()
+    pub fn capture_frame( &mut self, source: CaptureSource, path: &Path ) {
+        let pbo = self.free.pop().unwrap_or_else( || {
+            let mut pbo = 0;
+            unsafe { gl::GenBuffers(1, &mut pbo) };
+            pbo
+        });
+        let size = (source.width * source.height * 4) as isize;
+
+        bind_source(source);
+        gl_state::bind_buffer(gl::PIXEL_PACK_BUFFER, pbo);
+        let fence = unsafe {
+            gl::BufferData(gl::PIXEL_PACK_BUFFER, size, std::ptr::null(), gl::STREAM_READ);
+            // With a pack buffer bound the last argument is an offset, the call returns right away
+            gl::ReadPixels(0, 0, source.width, source.height, gl::RGBA, gl::UNSIGNED_BYTE, null_mut());
+            gl::FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0)
+        };
+        // A bound pack buffer would turn every later ReadPixels into a buffer write
+        gl_state::bind_buffer(gl::PIXEL_PACK_BUFFER, 0);
+
+        self.pending.push_back(PendingCapture { pbo, fence, width: source.width, height: source.height, path: path.to_path_buf() });
+    }
+
+    // Saves every capture the GPU finished, returns how many were written
+    pub fn poll( &mut self ) -> Result<usize, String> {
+        self.save_ready(0)
+    }
+
+    // Waits for and saves everything still pending
+    pub fn finish( &mut self ) -> Result<usize, String> {
+        self.save_ready(u64::MAX)
+    }
+
+    fn save_ready( &mut self, timeout_ns: u64 ) -> Result<usize, String> {
+        let mut saved = 0;
+        // Captures finish in order, so stop at the first one that isn't ready
+        while let Some(capture) = self.pending.front() {
+            let status = unsafe { gl::ClientWaitSync(capture.fence, gl::SYNC_FLUSH_COMMANDS_BIT, timeout_ns) };
+            if status == gl::TIMEOUT_EXPIRED {
+                break;
+            }
+            let capture = self.pending.pop_front().expect("front exists");
+            unsafe { gl::DeleteSync(capture.fence) };
+            if status == gl::WAIT_FAILED {
+                self.free.push(capture.pbo);
+                return Err(format!("{}: waiting for the GPU failed", capture.path.display()));
+            }
+
+            let size = (capture.width * capture.height * 4) as usize;
+            let mut pixels = vec![ 0u8; size ];
+            gl_state::bind_buffer(gl::PIXEL_PACK_BUFFER, capture.pbo);
+            let mapped = unsafe { gl::MapBufferRange(gl::PIXEL_PACK_BUFFER, 0, size as isize, gl::MAP_READ_BIT) as *const u8 };
+            if !mapped.is_null() {
+                unsafe {
+                    std::ptr::copy_nonoverlapping(mapped, pixels.as_mut_ptr(), size);
+                    gl::UnmapBuffer(gl::PIXEL_PACK_BUFFER);
+                }
+            }
+            gl_state::bind_buffer(gl::PIXEL_PACK_BUFFER, 0);
+            self.free.push(capture.pbo);
+            // Writing the zeroed buffer would save a black frame that looks like a rendering bug
+            if mapped.is_null() {
+                return Err(format!("{}: mapping the pixel buffer failed", capture.path.display()));
+            }
+
+            to_image(capture.width, capture.height, pixels)
+                .save(&capture.path)
+                .map_err( | err | format!("{}: {}", capture.path.display(), err) )?;
+            saved += 1;
+        }
+        Ok(saved)
+    }
+}
+
+// Needs the context that created the buffers to still be current
+impl Drop for FrameCapture {
+    fn drop( &mut self ) {
+        if self.pending() > 0 {
+            log::warn!(target: APP, "{} captures were dropped before they were saved", self.pending());
+        }
+        let mut buffers = std::mem::take(&mut self.free);
+        for capture in self.pending.drain(..) {
+            unsafe { gl::DeleteSync(capture.fence) };
+            buffers.push(capture.pbo);
+        }
+        unsafe { gl::DeleteBuffers(buffers.len() as i32, buffers.as_ptr()) };
+        for buffer in buffers {
+            gl_state::forget_buffer(buffer);
+        }
+    }
+}
+
+// Fixed simulated timestep -> the same frames every run, whatever the real frame rate
+pub struct Recorder {
+    pub directory: PathBuf,
+    pub timestep: f64,
+    pub frame: u32,
+    capture: FrameCapture,
+}
+
+impl Recorder {
+    pub fn new( directory: &Path, frames_per_second: f64 ) -> Result<Self, String> {
+        fs::create_dir_all(directory).map_err( | err | format!("{}: {}", directory.display(), err) )?;
+        Ok(Self { directory: directory.to_path_buf(), timestep: 1.0 / frames_per_second, frame: 0, capture: FrameCapture::new() })
+    }
+
+    // Simulated time of the frame about to be rendered
+    pub fn time(&self) -> f64 {
+        self.frame as f64 * self.timestep
+    }
+
+    pub fn frame_path( &self, frame: u32 ) -> PathBuf {
+        self.directory.join(format!("frame_{:05}.png", frame))
+    }
+
+    // Queues the current frame and saves finished older ones
+    pub fn record( &mut self, source: CaptureSource ) -> Result<(), String> {
+        let path = self.frame_path(self.frame);
+        self.capture.capture_frame(source, &path);
+        self.frame += 1;
+        self.capture.poll()?;
+        Ok(())
+    }
+
+    pub fn finish( &mut self ) -> Result<(), String> {
+        self.capture.finish().map( | _ | () )
+    }
+}
+
+// Renders `frames` frames of `app` at the recorder's timestep and writes them out
+pub fn record_app( context: &mut dyn GlContext, app: &mut dyn App, source: CaptureSource, recorder: &mut Recorder, frames: u32 ) -> Result<(), String> {
+    let timestep = recorder.timestep;
+    let result = run_frames(context, app, frames, timestep, | _ | recorder.record(source)).and_then( | _ | recorder.finish() );
+    app.shutdown();
+    result?;
+    log::info!(target: APP, "{} frames ( {:.2}s ) -> {}", recorder.frame, recorder.time(), recorder.directory.display());
+    Ok(())
+}
+
+#[cfg(all(test, feature = "headless", target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::context::headless::HeadlessContext;
+
+    #[test]
+    fn captured_frames_match_a_blocking_read() {
+        let context = HeadlessContext::new(8, 4).unwrap();
+        context.bind_target();
+        unsafe {
+            gl::ClearColor(0.2, 0.4, 0.6, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+        }
+        let source = CaptureSource::of(&context.target);
+        let path = std::env::temp_dir().join(format!("openglyt_capture_{}.png", std::process::id()));
+
+        let mut capture = FrameCapture::new();
+        capture.capture_frame(source, &path);
+        assert_eq!(capture.pending(), 1);
+        assert_eq!(capture.finish(), Ok(1));
+        assert_eq!(image::open(&path).unwrap().to_rgba8(), read_pixels(source));
+
+        // The pack buffer is unbound again and the cache knows it
+        assert_eq!(gl_state::verify(), Ok(()));
+        drop(capture);
+        assert_eq!(gl_state::verify(), Ok(()));
+        let _ = fs::remove_file(&path);
+    }
+}
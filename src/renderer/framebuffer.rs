@@ -0,0 +1,299 @@
+// Framebuffer -> offscreen render target, its color attachments are normal textures
+use std::ptr::null;
+
+use crate::{renderer::gl_state, textures::texture::Texture};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    // The headless target, and the fallback when a driver can't render to half floats
+    Rgba8,
+    Rgba16F,
+}
+
+impl ColorFormat {
+    // ( internal format, format, type ) for TexImage2D
+    pub fn gl_formats(&self) -> (u32, u32, u32) {
+        match self {
+            ColorFormat::Rgba8 => ( gl::RGBA8, gl::RGBA, gl::UNSIGNED_BYTE ),
+            ColorFormat::Rgba16F => ( gl::RGBA16F, gl::RGBA, gl::HALF_FLOAT ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthFormat {
+    Depth24,
+    // The headless target, stands in for a window's default framebuffer
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    Depth24Stencil8,
+}
+
+impl DepthFormat {
+    // ( internal format, attachment point )
+    pub fn gl_formats(&self) -> (u32, u32) {
+        match self {
+            DepthFormat::Depth24 => ( gl::DEPTH_COMPONENT24, gl::DEPTH_ATTACHMENT ),
+            #[cfg(all(feature = "headless", target_os = "linux"))]
+            DepthFormat::Depth24Stencil8 => ( gl::DEPTH24_STENCIL8, gl::DEPTH_STENCIL_ATTACHMENT ),
+        }
+    }
+}
+
+pub struct FramebufferBuilder {
+    width: i32,
+    height: i32,
+    colors: Vec<ColorFormat>,
+    depth: Option<DepthFormat>,
+}
+
+impl FramebufferBuilder {
+    pub fn new( width: i32, height: i32 ) -> Self {
+        Self { width, height, colors: Vec::new(), depth: None }
+    }
+
+    // Attachment N is `layout (location = N) out` in the fragment shader
+    pub fn color( mut self, format: ColorFormat ) -> Self {
+        self.colors.push(format);
+        self
+    }
+
+    // A renderbuffer -> depth tested against, never sampled
+    pub fn depth( mut self, format: DepthFormat ) -> Self {
+        self.depth = Some(format);
+        self
+    }
+
+    pub fn build(self) -> Result<Framebuffer, String> {
+        if self.width <= 0 || self.height <= 0 {
+            return Err(format!("Framebuffer size {}x{} must be positive", self.width, self.height));
+        }
+        let mut framebuffer = Framebuffer {
+            id: 0,
+            width: self.width,
+            height: self.height,
+            colors: Vec::new(),
+            depth: None,
+            color_formats: self.colors,
+            depth_format: self.depth,
+        };
+        unsafe { gl::GenFramebuffers(1, &mut framebuffer.id) };
+        // Nobody gets to own a half built target -> nobody would delete it
+        if let Err(err) = framebuffer.create_attachments() {
+            framebuffer.delete();
+            return Err(err);
+        }
+        Ok(framebuffer)
+    }
+}
+
+pub struct Framebuffer {
+    pub id: u32,
+    pub width: i32,
+    pub height: i32,
+    pub colors: Vec<Texture>,
+    // Renderbuffer
+    pub depth: Option<u32>,
+    // Kept so resize() can rebuild the same attachments
+    color_formats: Vec<ColorFormat>,
+    depth_format: Option<DepthFormat>,
+}
+
+fn status_name( status: u32 ) -> &'static str {
+    match status {
+        gl::FRAMEBUFFER_UNDEFINED => "FRAMEBUFFER_UNDEFINED ( no default framebuffer )",
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "INCOMPLETE_ATTACHMENT ( an attachment is missing storage or has a zero size )",
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "INCOMPLETE_MISSING_ATTACHMENT ( nothing is attached )",
+        gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "INCOMPLETE_DRAW_BUFFER ( a draw buffer has no attachment )",
+        gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "INCOMPLETE_READ_BUFFER ( the read buffer has no attachment )",
+        gl::FRAMEBUFFER_UNSUPPORTED => "UNSUPPORTED ( this combination of formats isn't supported by the driver )",
+        gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "INCOMPLETE_MULTISAMPLE ( attachments have different sample counts )",
+        gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "INCOMPLETE_LAYER_TARGETS ( layered and non layered attachments are mixed )",
+        _ => "unknown status",
+    }
+}
+
+impl Framebuffer {
+    // Whatever was bound before stays bound, building offscreen targets mid frame is fine
+    fn create_attachments( &mut self ) -> Result<(), String> {
+        let previous = gl_state::bound_framebuffers();
+        let result = self.attach();
+        restore_bindings(previous);
+        result
+    }
+
+    fn attach( &mut self ) -> Result<(), String> {
+        gl_state::bind_framebuffer(gl::FRAMEBUFFER, self.id);
+
+        for ( i, format ) in self.color_formats.iter().enumerate() {
+            let ( internal, pixel_format, pixel_type ) = format.gl_formats();
+            let texture = create_texture(self.width, self.height, internal, pixel_format, pixel_type);
+            unsafe { gl::FramebufferTexture2D(gl::FRAMEBUFFER, gl::COLOR_ATTACHMENT0 + i as u32, gl::TEXTURE_2D, texture.id, 0) };
+            self.colors.push(texture);
+        }
+
+        self.depth = self.depth_format.map( | format | {
+            let ( internal, attachment ) = format.gl_formats();
+            let mut renderbuffer = 0;
+            unsafe {
+                gl::GenRenderbuffers(1, &mut renderbuffer);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+                gl::RenderbufferStorage(gl::RENDERBUFFER, internal, self.width, self.height);
+                gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, attachment, gl::RENDERBUFFER, renderbuffer);
+            }
+            renderbuffer
+        });
+
+        unsafe {
+            if self.colors.is_empty() {
+                // Depth only
+                gl::DrawBuffer(gl::NONE);
+                gl::ReadBuffer(gl::NONE);
+            } else {
+                let buffers = (0..self.colors.len() as u32).map( | i | gl::COLOR_ATTACHMENT0 + i ).collect::<Vec<u32>>();
+                gl::DrawBuffers(buffers.len() as i32, buffers.as_ptr());
+            }
+        }
+        self.validate()
+    }
+
+    // Readable reason instead of a bare GLenum
+    pub fn validate(&self) -> Result<(), String> {
+        let previous = gl_state::bound_framebuffers();
+        gl_state::bind_framebuffer(gl::FRAMEBUFFER, self.id);
+        let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+        restore_bindings(previous);
+        if status == gl::FRAMEBUFFER_COMPLETE {
+            Ok(())
+        } else {
+            Err(format!(
+                "Framebuffer {} ( {}x{}, {} color, depth {:?} ) is incomplete: {}",
+                self.id, self.width, self.height, self.colors.len(), self.depth_format, status_name(status)
+            ))
+        }
+    }
+
+    // Renders into this framebuffer from now on, the viewport covers all of it
+    pub fn bind(&self) {
+        gl_state::bind_framebuffer(gl::FRAMEBUFFER, self.id);
+        gl_state::viewport(0, 0, self.width, self.height);
+    }
+
+    pub fn color_texture( &self, index: usize ) -> Option<&Texture> {
+        self.colors.get(index)
+    }
+
+    // Throws the attachments away and creates new ones -> old contents are lost
+    pub fn resize( &mut self, width: i32, height: i32 ) -> Result<(), String> {
+        if width == self.width && height == self.height {
+            return Ok(());
+        }
+        if width <= 0 || height <= 0 {
+            return Err(format!("Framebuffer size {}x{} must be positive", width, height));
+        }
+        self.delete_attachments();
+        self.width = width;
+        self.height = height;
+        self.create_attachments()
+    }
+
+    // Copies color, scaled to fill `target`. Depth only when both use the same depth format,
+    // GL fails the whole blit with INVALID_OPERATION otherwise
+    pub fn blit_to( &self, target: &Framebuffer, filter: u32 ) {
+        let mut mask = gl::COLOR_BUFFER_BIT;
+        if self.depth_format.is_some() && self.depth_format == target.depth_format {
+            mask |= gl::DEPTH_BUFFER_BIT;
+        }
+        blit(self.id, ( self.width, self.height ), target.id, ( target.width, target.height ), mask, filter);
+    }
+
+    fn delete_attachments( &mut self ) {
+        let textures = self.colors.drain(..).map( | texture | texture.id ).collect::<Vec<u32>>();
+        if let Some(renderbuffer) = self.depth.take() {
+            unsafe { gl::DeleteRenderbuffers(1, &renderbuffer) };
+        }
+        unsafe { gl::DeleteTextures(textures.len() as i32, textures.as_ptr()) };
+        // Only these ids may be stale in the cache, everything else is still bound as cached
+        for texture in textures {
+            gl_state::forget_texture(texture);
+        }
+    }
+
+    pub fn delete( &mut self ) {
+        self.delete_attachments();
+        unsafe { gl::DeleteFramebuffers(1, &self.id) };
+        gl_state::forget_framebuffer(self.id);
+        self.id = 0;
+    }
+}
+
+fn restore_bindings( ( draw, read ): (u32, u32) ) {
+    gl_state::bind_framebuffer(gl::DRAW_FRAMEBUFFER, draw);
+    gl_state::bind_framebuffer(gl::READ_FRAMEBUFFER, read);
+}
+
+// Depth / stencil blits must use NEAREST, GL rejects LINEAR for them. The bindings are restored afterwards
+pub fn blit( source: u32, source_size: (i32, i32), target: u32, target_size: (i32, i32), mask: u32, filter: u32 ) {
+    let filter = if mask != gl::COLOR_BUFFER_BIT { gl::NEAREST } else { filter };
+    let previous = gl_state::bound_framebuffers();
+    gl_state::bind_framebuffer(gl::READ_FRAMEBUFFER, source);
+    gl_state::bind_framebuffer(gl::DRAW_FRAMEBUFFER, target);
+    unsafe {
+        gl::BlitFramebuffer(
+            0, 0, source_size.0, source_size.1,
+            0, 0, target_size.0, target_size.1,
+            mask, filter
+        );
+    }
+    restore_bindings(previous);
+}
+
+fn create_texture( width: i32, height: i32, internal: u32, pixel_format: u32, pixel_type: u32 ) -> Texture {
+    let mut id = 0;
+    unsafe {
+        gl::GenTextures(1, &mut id);
+        gl_state::bind_texture(0, gl::TEXTURE_2D, id);
+        gl::TexImage2D(gl::TEXTURE_2D, 0, internal as i32, width, height, 0, pixel_format, pixel_type, null());
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+    }
+    Texture { id, target: gl::TEXTURE_2D }
+}
+
+#[cfg(all(test, feature = "headless", target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::context::{context::GlContext, headless::HeadlessContext};
+
+    #[test]
+    fn build_and_resize_keep_the_previous_binding() {
+        let context = HeadlessContext::new(4, 4).unwrap();
+        context.bind_target();
+        let target = context.target.id;
+
+        let mut framebuffer = FramebufferBuilder::new(8, 8).color(ColorFormat::Rgba8).build().unwrap();
+        assert_eq!(gl_state::bound_framebuffers(), ( target, target ));
+        framebuffer.resize(16, 16).unwrap();
+        assert_eq!(gl_state::bound_framebuffers(), ( target, target ));
+        assert_eq!(gl_state::verify(), Ok(()));
+        framebuffer.delete();
+    }
+
+    #[test]
+    fn blit_to_skips_depth_when_the_formats_differ() {
+        let _context = HeadlessContext::new(4, 4).unwrap();
+        let with_depth = | format | FramebufferBuilder::new(8, 8)
+            .color(ColorFormat::Rgba8)
+            .depth(format)
+            .build()
+            .unwrap();
+        let ( mut source, mut target ) = ( with_depth(DepthFormat::Depth24), with_depth(DepthFormat::Depth24Stencil8) );
+        unsafe { while gl::GetError() != gl::NO_ERROR {} }
+
+        source.blit_to(&target, gl::NEAREST);
+        assert_eq!(unsafe { gl::GetError() }, gl::NO_ERROR);
+        source.delete();
+        target.delete();
+    }
+}
@@ -0,0 +1,266 @@
+// GL Debug -> driver messages through KHR_debug, gl_check! where that's missing, and object labels
+// Labels show up in RenderDoc / apitrace and in the driver's own messages -> "wall.jpg" instead of "texture 3"
+use std::{ffi::{c_void, CStr}, ptr::null, slice, sync::atomic::{AtomicBool, AtomicU8, Ordering}};
+
+use log::Level;
+
+use crate::logging::logging::GL;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Notification,
+    Low,
+    Medium,
+    High,
+}
+
+const SEVERITIES: &[(&str, Severity)] = &[
+    ( "notification", Severity::Notification ),
+    ( "low", Severity::Low ),
+    ( "medium", Severity::Medium ),
+    ( "high", Severity::High ),
+];
+
+impl Severity {
+    pub fn from_gl( severity: u32 ) -> Self {
+        match severity {
+            gl::DEBUG_SEVERITY_HIGH => Severity::High,
+            gl::DEBUG_SEVERITY_MEDIUM => Severity::Medium,
+            gl::DEBUG_SEVERITY_LOW => Severity::Low,
+            _ => Severity::Notification,
+        }
+    }
+
+    pub fn parse( name: &str ) -> Result<Self, String> {
+        SEVERITIES.iter()
+            .find( | ( label, _ ) | label.eq_ignore_ascii_case(name) )
+            .map( | ( _, severity ) | *severity )
+            .ok_or_else( || {
+                let names = SEVERITIES.iter().map( | ( label, _ ) | *label ).collect::<Vec<&str>>();
+                format!("unknown severity '{}', expected one of: {}", name, names.join(", "))
+            })
+    }
+
+    // Notification -> Low -> Medium -> High -> Notification, for stepping through at runtime
+    pub fn next(self) -> Self {
+        let index = SEVERITIES.iter().position( | ( _, severity ) | *severity == self ).unwrap_or(0);
+        SEVERITIES[(index + 1) % SEVERITIES.len()].1
+    }
+}
+
+pub fn source_label( source: u32 ) -> &'static str {
+    match source {
+        gl::DEBUG_SOURCE_API => "api",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+        gl::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    }
+}
+
+pub fn type_label( kind: u32 ) -> &'static str {
+    match kind {
+        gl::DEBUG_TYPE_ERROR => "error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+        gl::DEBUG_TYPE_PORTABILITY => "portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "performance",
+        gl::DEBUG_TYPE_MARKER => "marker",
+        gl::DEBUG_TYPE_PUSH_GROUP => "push group",
+        gl::DEBUG_TYPE_POP_GROUP => "pop group",
+        _ => "other",
+    }
+}
+
+pub fn error_name( error: u32 ) -> &'static str {
+    match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        _ => "unknown GL error",
+    }
+}
+
+// The callback may come from a driver thread if the synchronous mode is ignored -> atomics, not the thread local cache
+static SUPPORTED: AtomicBool = AtomicBool::new(false);
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static MIN_SEVERITY: AtomicU8 = AtomicU8::new(Severity::Low as u8);
+
+// GL 4.3 has it built in, older contexts may still list the extension
+fn detect() -> bool {
+    if !gl::DebugMessageCallback::is_loaded() || !gl::ObjectLabel::is_loaded() {
+        return false;
+    }
+    let ( mut major, mut minor, mut count ) = ( 0, 0, 0 );
+    unsafe {
+        gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+        gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+        gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+    }
+    if ( major, minor ) >= ( 4, 3 ) {
+        return true;
+    }
+    (0..count.max(0) as u32).any( | i | {
+        let name = unsafe { gl::GetStringi(gl::EXTENSIONS, i) };
+        !name.is_null() && unsafe { CStr::from_ptr(name as *const _) }.to_bytes() == b"GL_KHR_debug"
+    })
+}
+
+// Once the context is current -> finds out what it supports, installs the callback when `enabled`
+// Returns whether the callback is on, without it gl_check! does the error checking
+pub fn setup( enabled: bool, min_severity: Severity ) -> bool {
+    let supported = detect();
+    SUPPORTED.store(supported, Ordering::Relaxed);
+    MIN_SEVERITY.store(min_severity as u8, Ordering::Relaxed);
+    let enabled = enabled && supported;
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if enabled {
+        unsafe {
+            gl::Enable(gl::DEBUG_OUTPUT);
+            // Messages arrive inside the call that caused them -> a breakpoint in the callback shows the culprit
+            gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+            gl::DebugMessageCallback(Some(callback), null());
+            // Everything on, the severity filter is ours so it can change at runtime
+            gl::DebugMessageControl(gl::DONT_CARE, gl::DONT_CARE, gl::DONT_CARE, 0, null(), gl::TRUE);
+        }
+    } else if supported {
+        // Debug contexts start with it on
+        unsafe { gl::Disable(gl::DEBUG_OUTPUT) };
+    }
+    enabled
+}
+
+// Renderer and version string of the current context, for the log
+pub fn context_info() -> String {
+    let text = | name: u32 | {
+        let value = unsafe { gl::GetString(name) };
+        if value.is_null() { "?".to_string() } else { unsafe { CStr::from_ptr(value as *const _) }.to_string_lossy().into_owned() }
+    };
+    format!("{} / {}", text(gl::RENDERER), text(gl::VERSION))
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_min_severity( severity: Severity ) {
+    MIN_SEVERITY.store(severity as u8, Ordering::Relaxed);
+}
+
+pub fn min_severity() -> Severity {
+    match MIN_SEVERITY.load(Ordering::Relaxed) {
+        0 => Severity::Notification,
+        1 => Severity::Low,
+        2 => Severity::Medium,
+        _ => Severity::High,
+    }
+}
+
+fn level_for( severity: Severity ) -> Level {
+    match severity {
+        Severity::High => Level::Error,
+        Severity::Medium => Level::Warn,
+        Severity::Low => Level::Info,
+        Severity::Notification => Level::Debug,
+    }
+}
+
+// Into the log under the "gl" target, the severity picks the level
+fn report( severity: Severity, message: &str ) {
+    log::log!(target: GL, level_for(severity), "{}", message);
+}
+
+extern "system" fn callback( source: u32, kind: u32, id: u32, severity: u32, length: i32, message: *const gl::types::GLchar, _user: *mut c_void ) {
+    // Unwinding into the driver is undefined behavior -> a panic in a log sink ends here
+    let _ = std::panic::catch_unwind( || {
+        let severity = Severity::from_gl(severity);
+        if severity < min_severity() || message.is_null() {
+            return;
+        }
+        // A negative length means NUL terminated
+        let bytes = if length < 0 {
+            unsafe { CStr::from_ptr(message) }.to_bytes()
+        } else {
+            unsafe { slice::from_raw_parts(message as *const u8, length as usize) }
+        };
+        let text = String::from_utf8_lossy(bytes);
+        report(severity, &format!("{} {} #{}: {}", source_label(source), type_label(kind), id, text.trim_end()));
+    });
+}
+
+// Debug builds without the callback -> reports every error GL queued up, with the call and where it was made
+// Returns false when there were errors
+pub fn check_errors( call: &str, file: &str, line: u32 ) -> bool {
+    if !cfg!(debug_assertions) || is_enabled() {
+        return true;
+    }
+    let mut clean = true;
+    loop {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            return clean;
+        }
+        clean = false;
+        report(Severity::High, &format!("{} -> {} at {}:{}", error_name(error), call, file, line));
+    }
+}
+
+// Runs a GL call, then check_errors() on it -> gl_check!(gl::DrawArrays(gl::TRIANGLES, 0, 3))
+macro_rules! gl_check {
+    ( $call:expr ) => {{
+        #[allow(unused_unsafe)]
+        let result = unsafe { $call };
+        $crate::renderer::gl_debug::check_errors(stringify!($call), file!(), line!());
+        result
+    }};
+}
+pub(crate) use gl_check;
+
+// Names a GL object for debuggers and driver messages, a no-op without KHR_debug
+// Buffers and vertex arrays only exist once bound, label them after the first bind
+pub fn label( identifier: u32, name: u32, label: &str ) {
+    if name == 0 || !SUPPORTED.load(Ordering::Relaxed) {
+        return;
+    }
+    unsafe { gl::ObjectLabel(identifier, name, label.len() as i32, label.as_ptr() as *const _) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn severity_names_parse_case_insensitive() {
+        assert_eq!(Severity::parse("medium"), Ok(Severity::Medium));
+        assert_eq!(Severity::parse("HIGH"), Ok(Severity::High));
+        assert_eq!(Severity::parse("Notification"), Ok(Severity::Notification));
+        let err = Severity::parse("loud").unwrap_err();
+        assert!(err.contains("'loud'") && err.contains("notification, low, medium, high"), "{}", err);
+    }
+
+    #[test]
+    fn severities_map_to_log_levels() {
+        assert_eq!(level_for(Severity::High), Level::Error);
+        assert_eq!(level_for(Severity::Medium), Level::Warn);
+        assert_eq!(level_for(Severity::Low), Level::Info);
+        assert_eq!(level_for(Severity::Notification), Level::Debug);
+        assert_eq!(Severity::from_gl(gl::DEBUG_SEVERITY_HIGH), Severity::High);
+        assert_eq!(Severity::from_gl(gl::DEBUG_SEVERITY_NOTIFICATION), Severity::Notification);
+    }
+
+    #[test]
+    fn next_cycles_through_every_severity() {
+        assert_eq!(Severity::Low.next(), Severity::Medium);
+        assert_eq!(Severity::High.next(), Severity::Notification);
+        let mut severity = Severity::Notification;
+        for _ in 0..SEVERITIES.len() {
+            severity = severity.next();
+        }
+        assert_eq!(severity, Severity::Notification);
+    }
+}
@@ -0,0 +1,461 @@
+// GL State Cache -> remembers what is bound so repeated binds become no-ops
+// Uniform values too, they live inside the program and survive switching to another one
+use std::{cell::RefCell, collections::HashMap};
+
+use glm::Mat4;
+
+use crate::logging::logging::GL;
+
+const MAX_TEXTURE_UNITS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UniformValue {
+    Int(i32),
+    Float(f32),
+    Vec4([f32; 4]),
+    Mat4(Mat4),
+}
+
+impl UniformValue {
+    // Uploads into the currently used program
+    fn upload( &self, location: i32 ) {
+        unsafe {
+            match self {
+                UniformValue::Int(value) => gl::Uniform1i(location, *value),
+                UniformValue::Float(value) => gl::Uniform1f(location, *value),
+                UniformValue::Vec4(v) => gl::Uniform4f(location, v[0], v[1], v[2], v[3]),
+                UniformValue::Mat4(m) => gl::UniformMatrix4fv(location, 1, gl::FALSE, m.as_array().as_ptr() as *const _),
+            }
+        }
+    }
+}
+
+// None means "unknown", the next call always goes through to GL
+#[derive(Default)]
+struct StateCache {
+    program: Option<u32>,
+    vertex_array: Option<u32>,
+    array_buffer: Option<u32>,
+    element_buffer: Option<u32>,
+    pixel_pack_buffer: Option<u32>,
+    active_unit: Option<u32>,
+    textures: [Option<(u32, u32)>; MAX_TEXTURE_UNITS],
+    blend: Option<bool>,
+    blend_func: Option<(u32, u32)>,
+    depth_test: Option<bool>,
+    depth_func: Option<u32>,
+    cull_face: Option<bool>,
+    cull_mode: Option<u32>,
+    viewport: Option<[i32; 4]>,
+    scissor_test: Option<bool>,
+    scissor: Option<[i32; 4]>,
+    clear_color: Option<[f32; 4]>,
+    draw_framebuffer: Option<u32>,
+    read_framebuffer: Option<u32>,
+    // ( program, location ) -> value, a missing entry is unknown
+    uniforms: HashMap<(u32, i32), UniformValue>,
+    // GL calls that were actually made / skipped
+    changes: u64,
+    skipped: u64,
+    debug: bool,
+}
+
+thread_local! {
+    // The GL context is current on one thread, so is its state
+    static CACHE: RefCell<StateCache> = RefCell::new(StateCache::default());
+}
+
+// Counts a redundant call, in debug mode also checks the cache was right to skip it
+fn skip() {
+    let debug = CACHE.with( | cache | {
+        let mut cache = cache.borrow_mut();
+        cache.skipped += 1;
+        cache.debug
+    });
+    if debug && let Err(errors) = verify() {
+        log::error!(target: GL, "GL state cache is out of sync -> {}", errors.join(", "));
+    }
+}
+
+// Runs `apply` only when `wanted` differs from the cached value
+fn set<T: PartialEq + Copy, F: FnOnce()>( field: fn(&mut StateCache) -> &mut Option<T>, wanted: T, apply: F ) {
+    let cached = CACHE.with( | cache | *field(&mut cache.borrow_mut()) );
+    if cached == Some(wanted) {
+        skip();
+        return;
+    }
+    apply();
+    CACHE.with( | cache | {
+        let mut cache = cache.borrow_mut();
+        *field(&mut cache) = Some(wanted);
+        cache.changes += 1;
+    });
+}
+
+pub fn use_program( program: u32 ) {
+    set(| c | &mut c.program, program, || unsafe { gl::UseProgram(program) });
+}
+
+pub fn bind_vertex_array( vao: u32 ) {
+    set(| c | &mut c.vertex_array, vao, || {
+        unsafe { gl::BindVertexArray(vao) };
+        // The element buffer binding is part of the VAO
+        CACHE.with( | cache | cache.borrow_mut().element_buffer = None );
+    });
+}
+
+pub fn bind_buffer( target: u32, buffer: u32 ) {
+    match target {
+        gl::ARRAY_BUFFER => set(| c | &mut c.array_buffer, buffer, || unsafe { gl::BindBuffer(target, buffer) }),
+        gl::ELEMENT_ARRAY_BUFFER => set(| c | &mut c.element_buffer, buffer, || unsafe { gl::BindBuffer(target, buffer) }),
+        gl::PIXEL_PACK_BUFFER => set(| c | &mut c.pixel_pack_buffer, buffer, || unsafe { gl::BindBuffer(target, buffer) }),
+        // Other targets aren't tracked
+        _ => unsafe { gl::BindBuffer(target, buffer) },
+    }
+}
+
+pub fn active_texture( unit: u32 ) {
+    set(| c | &mut c.active_unit, unit, || unsafe { gl::ActiveTexture(gl::TEXTURE0 + unit) });
+}
+
+// Binds `texture` to `target` on texture unit `unit`
+pub fn bind_texture( unit: u32, target: u32, texture: u32 ) {
+    let cached = CACHE.with( | cache | cache.borrow().textures.get(unit as usize).copied().flatten() );
+    if cached == Some(( target, texture )) {
+        skip();
+        return;
+    }
+    active_texture(unit);
+    unsafe { gl::BindTexture(target, texture) };
+    CACHE.with( | cache | {
+        let mut cache = cache.borrow_mut();
+        if let Some(slot) = cache.textures.get_mut(unit as usize) {
+            *slot = Some(( target, texture ));
+        }
+        cache.changes += 1;
+    });
+}
+
+fn toggle( capability: u32, enabled: bool ) {
+    unsafe {
+        if enabled { gl::Enable(capability) } else { gl::Disable(capability) }
+    }
+}
+
+pub fn set_blend( enabled: bool ) {
+    set(| c | &mut c.blend, enabled, || toggle(gl::BLEND, enabled));
+}
+
+pub fn blend_func( source: u32, destination: u32 ) {
+    set(| c | &mut c.blend_func, ( source, destination ), || unsafe { gl::BlendFunc(source, destination) });
+}
+
+pub fn set_depth_test( enabled: bool ) {
+    set(| c | &mut c.depth_test, enabled, || toggle(gl::DEPTH_TEST, enabled));
+}
+
+pub fn depth_func( function: u32 ) {
+    set(| c | &mut c.depth_func, function, || unsafe { gl::DepthFunc(function) });
+}
+
+pub fn set_cull_face( enabled: bool ) {
+    set(| c | &mut c.cull_face, enabled, || toggle(gl::CULL_FACE, enabled));
+}
+
+pub fn cull_face( mode: u32 ) {
+    set(| c | &mut c.cull_mode, mode, || unsafe { gl::CullFace(mode) });
+}
+
+pub fn viewport( x: i32, y: i32, width: i32, height: i32 ) {
+    set(| c | &mut c.viewport, [ x, y, width, height ], || unsafe { gl::Viewport(x, y, width, height) });
+}
+
+pub fn set_scissor_test( enabled: bool ) {
+    set(| c | &mut c.scissor_test, enabled, || toggle(gl::SCISSOR_TEST, enabled));
+}
+
+pub fn scissor( x: i32, y: i32, width: i32, height: i32 ) {
+    set(| c | &mut c.scissor, [ x, y, width, height ], || unsafe { gl::Scissor(x, y, width, height) });
+}
+
+pub fn clear_color( color: [f32; 4] ) {
+    set(| c | &mut c.clear_color, color, || unsafe { gl::ClearColor(color[0], color[1], color[2], color[3]) });
+}
+
+// Sets a uniform of the program in use, skipped when it already holds `value`
+// -1 ( unknown or optimized away ) is ignored like GL does
+pub fn uniform( location: i32, value: UniformValue ) {
+    if location < 0 {
+        return;
+    }
+    let Some(program) = CACHE.with( | cache | cache.borrow().program ) else {
+        // Nothing to key the value on, send it and remember nothing
+        value.upload(location);
+        return;
+    };
+    let cached = CACHE.with( | cache | cache.borrow().uniforms.get(&( program, location )).copied() );
+    if cached == Some(value) {
+        skip();
+        return;
+    }
+    value.upload(location);
+    CACHE.with( | cache | {
+        let mut cache = cache.borrow_mut();
+        cache.uniforms.insert(( program, location ), value);
+        cache.changes += 1;
+    });
+}
+
+// FRAMEBUFFER sets both the draw and the read binding
+pub fn bind_framebuffer( target: u32, framebuffer: u32 ) {
+    match target {
+        gl::DRAW_FRAMEBUFFER => set(| c | &mut c.draw_framebuffer, framebuffer, || unsafe { gl::BindFramebuffer(target, framebuffer) }),
+        gl::READ_FRAMEBUFFER => set(| c | &mut c.read_framebuffer, framebuffer, || unsafe { gl::BindFramebuffer(target, framebuffer) }),
+        _ => {
+            bind_framebuffer(gl::DRAW_FRAMEBUFFER, framebuffer);
+            bind_framebuffer(gl::READ_FRAMEBUFFER, framebuffer);
+        },
+    }
+}
+
+// Currently bound ( draw, read ) framebuffers, asks GL when the cache doesn't know
+pub fn bound_framebuffers() -> (u32, u32) {
+    let ( draw, read ) = CACHE.with( | cache | {
+        let cache = cache.borrow();
+        ( cache.draw_framebuffer, cache.read_framebuffer )
+    });
+    let draw = draw.unwrap_or_else( || get_integer(gl::DRAW_FRAMEBUFFER_BINDING) as u32 );
+    let read = read.unwrap_or_else( || get_integer(gl::READ_FRAMEBUFFER_BINDING) as u32 );
+    CACHE.with( | cache | {
+        let mut cache = cache.borrow_mut();
+        cache.draw_framebuffer = Some(draw);
+        cache.read_framebuffer = Some(read);
+    });
+    ( draw, read )
+}
+
+// Forget everything, e.g. after code that talks to GL directly or on a new context
+pub fn invalidate() {
+    CACHE.with( | cache | {
+        let mut cache = cache.borrow_mut();
+        let ( changes, skipped, debug ) = ( cache.changes, cache.skipped, cache.debug );
+        *cache = StateCache { changes, skipped, debug, ..StateCache::default() };
+    });
+}
+
+// After glDeleteTextures -> GL unbinds the texture from every unit, a new texture may reuse the id
+pub fn forget_texture( texture: u32 ) {
+    CACHE.with( | cache | {
+        for slot in cache.borrow_mut().textures.iter_mut() {
+            if slot.is_some_and( | ( _, id ) | id == texture ) {
+                *slot = None;
+            }
+        }
+    });
+}
+
+// After glDeleteBuffers -> GL unbinds a deleted buffer from every target
+pub fn forget_buffer( buffer: u32 ) {
+    CACHE.with( | cache | {
+        let cache = &mut *cache.borrow_mut();
+        for binding in [ &mut cache.array_buffer, &mut cache.element_buffer, &mut cache.pixel_pack_buffer ] {
+            if *binding == Some(buffer) {
+                *binding = Some(0);
+            }
+        }
+    });
+}
+
+// After glDeleteVertexArrays -> a deleted VAO that was bound falls back to 0, and its element buffer with it
+pub fn forget_vertex_array( vao: u32 ) {
+    CACHE.with( | cache | {
+        let cache = &mut *cache.borrow_mut();
+        if cache.vertex_array == Some(vao) {
+            cache.vertex_array = Some(0);
+            cache.element_buffer = None;
+        }
+    });
+}
+
+// After glDeleteFramebuffers -> a deleted framebuffer that was bound falls back to 0
+pub fn forget_framebuffer( framebuffer: u32 ) {
+    CACHE.with( | cache | {
+        let cache = &mut *cache.borrow_mut();
+        for binding in [ &mut cache.draw_framebuffer, &mut cache.read_framebuffer ] {
+            if *binding == Some(framebuffer) {
+                *binding = Some(0);
+            }
+        }
+    });
+}
+
+// ( GL calls made, GL calls skipped ) since the program started
+pub fn counters() -> (u64, u64) {
+    CACHE.with( | cache | {
+        let cache = cache.borrow();
+        ( cache.changes, cache.skipped )
+    })
+}
+
+// Debug mode -> every skipped call compares the whole cache against glGet*
+pub fn set_debug( enabled: bool ) {
+    CACHE.with( | cache | cache.borrow_mut().debug = enabled );
+}
+
+fn get_integer( name: u32 ) -> i32 {
+    let mut value = 0;
+    unsafe { gl::GetIntegerv(name, &mut value) };
+    value
+}
+
+fn is_enabled( capability: u32 ) -> bool {
+    unsafe { gl::IsEnabled(capability) == gl::TRUE }
+}
+
+// Compares every known value with what GL reports, returns the mismatches
+pub fn verify() -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    CACHE.with( | cache | {
+        let cache = cache.borrow();
+        let mut check = | name: &str, cached: Option<i64>, actual: i64 | {
+            if let Some(cached) = cached && cached != actual {
+                errors.push(format!("{}: cached {} but GL has {}", name, cached, actual));
+            }
+        };
+
+        check("program", cache.program.map(i64::from), get_integer(gl::CURRENT_PROGRAM) as i64);
+        check("vertex array", cache.vertex_array.map(i64::from), get_integer(gl::VERTEX_ARRAY_BINDING) as i64);
+        check("array buffer", cache.array_buffer.map(i64::from), get_integer(gl::ARRAY_BUFFER_BINDING) as i64);
+        check("element buffer", cache.element_buffer.map(i64::from), get_integer(gl::ELEMENT_ARRAY_BUFFER_BINDING) as i64);
+        check("pixel pack buffer", cache.pixel_pack_buffer.map(i64::from), get_integer(gl::PIXEL_PACK_BUFFER_BINDING) as i64);
+        check("blend", cache.blend.map(i64::from), is_enabled(gl::BLEND) as i64);
+        check("blend src", cache.blend_func.map( | ( s, _ ) | s as i64 ), get_integer(gl::BLEND_SRC_RGB) as i64);
+        check("blend dst", cache.blend_func.map( | ( _, d ) | d as i64 ), get_integer(gl::BLEND_DST_RGB) as i64);
+        check("draw framebuffer", cache.draw_framebuffer.map(i64::from), get_integer(gl::DRAW_FRAMEBUFFER_BINDING) as i64);
+        check("read framebuffer", cache.read_framebuffer.map(i64::from), get_integer(gl::READ_FRAMEBUFFER_BINDING) as i64);
+        check("depth test", cache.depth_test.map(i64::from), is_enabled(gl::DEPTH_TEST) as i64);
+        check("depth func", cache.depth_func.map(i64::from), get_integer(gl::DEPTH_FUNC) as i64);
+        check("cull face", cache.cull_face.map(i64::from), is_enabled(gl::CULL_FACE) as i64);
+        check("cull mode", cache.cull_mode.map(i64::from), get_integer(gl::CULL_FACE_MODE) as i64);
+        check("scissor test", cache.scissor_test.map(i64::from), is_enabled(gl::SCISSOR_TEST) as i64);
+
+        let active_unit = get_integer(gl::ACTIVE_TEXTURE) as i64 - gl::TEXTURE0 as i64;
+        check("active texture", cache.active_unit.map(i64::from), active_unit);
+
+        if let Some(cached) = cache.viewport {
+            let mut actual = [ 0; 4 ];
+            unsafe { gl::GetIntegerv(gl::VIEWPORT, actual.as_mut_ptr()) };
+            if cached != actual {
+                errors.push(format!("viewport: cached {:?} but GL has {:?}", cached, actual));
+            }
+        }
+        if let Some(cached) = cache.scissor {
+            let mut actual = [ 0; 4 ];
+            unsafe { gl::GetIntegerv(gl::SCISSOR_BOX, actual.as_mut_ptr()) };
+            if cached != actual {
+                errors.push(format!("scissor: cached {:?} but GL has {:?}", cached, actual));
+            }
+        }
+        if let Some(cached) = cache.clear_color {
+            let mut actual = [ 0.0; 4 ];
+            unsafe { gl::GetFloatv(gl::COLOR_CLEAR_VALUE, actual.as_mut_ptr()) };
+            if cached != actual {
+                errors.push(format!("clear color: cached {:?} but GL has {:?}", cached, actual));
+            }
+        }
+
+        // Querying other units needs ActiveTexture, restore it afterwards
+        for ( unit, binding ) in cache.textures.iter().enumerate() {
+            let Some(( target, texture )) = binding else { continue };
+            let query = match *target {
+                gl::TEXTURE_2D => gl::TEXTURE_BINDING_2D,
+                gl::TEXTURE_2D_ARRAY => gl::TEXTURE_BINDING_2D_ARRAY,
+                gl::TEXTURE_CUBE_MAP => gl::TEXTURE_BINDING_CUBE_MAP,
+                _ => continue,
+            };
+            unsafe { gl::ActiveTexture(gl::TEXTURE0 + unit as u32) };
+            let actual = get_integer(query);
+            if actual as u32 != *texture {
+                errors.push(format!("texture unit {}: cached {} but GL has {}", unit, texture, actual));
+            }
+        }
+        unsafe { gl::ActiveTexture(gl::TEXTURE0 + active_unit as u32) };
+    });
+
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    use crate::{context::headless::HeadlessContext, shaders::shader::Shader};
+
+    // No context needed -> the closure stands in for the GL call, the cache is per thread and so per test
+    #[test]
+    fn set_skips_what_the_cache_already_has() {
+        let calls = Cell::new(0);
+        let ( changes, skipped ) = counters();
+        set(| c | &mut c.depth_func, gl::LESS, || calls.set(calls.get() + 1));
+        set(| c | &mut c.depth_func, gl::LESS, || calls.set(calls.get() + 1));
+        set(| c | &mut c.depth_func, gl::GREATER, || calls.set(calls.get() + 1));
+        assert_eq!(calls.get(), 2);
+        assert_eq!(counters(), ( changes + 2, skipped + 1 ));
+
+        // Unknown again -> the next call goes through even with the same value, the counters survive
+        invalidate();
+        set(| c | &mut c.depth_func, gl::GREATER, || calls.set(calls.get() + 1));
+        assert_eq!(calls.get(), 3);
+        assert_eq!(counters(), ( changes + 3, skipped + 1 ));
+    }
+
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    #[test]
+    fn uniform_writes_are_cached_per_program_until_invalidated() {
+        let _context = HeadlessContext::new(4, 4).unwrap();
+        let shader = Shader::new(
+            "#version 330 core\nuniform vec4 color;\nvoid main() { gl_Position = color; }",
+            "#version 330 core\nout vec4 FragColor;\nvoid main() { FragColor = vec4(1.0); }",
+        );
+        let location = shader.uniform_location("color");
+        use_program(shader.id);
+
+        let ( changes, skipped ) = counters();
+        uniform(location, UniformValue::Vec4([ 1.0, 0.0, 0.0, 1.0 ]));
+        uniform(location, UniformValue::Vec4([ 1.0, 0.0, 0.0, 1.0 ]));
+        assert_eq!(counters(), ( changes + 1, skipped + 1 ));
+
+        // A new context ( or code that wrote the uniform by hand ) -> the value is sent again
+        invalidate();
+        use_program(shader.id);
+        let ( changes, _ ) = counters();
+        uniform(location, UniformValue::Vec4([ 1.0, 0.0, 0.0, 1.0 ]));
+        assert_eq!(counters().0, changes + 1);
+
+        let mut value = [ 0.0f32; 4 ];
+        unsafe { gl::GetUniformfv(shader.id, location, value.as_mut_ptr()) };
+        assert_eq!(value, [ 1.0, 0.0, 0.0, 1.0 ]);
+    }
+
+    #[cfg(all(feature = "headless", target_os = "linux"))]
+    #[test]
+    fn forgetting_a_texture_keeps_the_other_units() {
+        let _context = HeadlessContext::new(4, 4).unwrap();
+        let mut ids = [ 0u32; 2 ];
+        unsafe { gl::GenTextures(2, ids.as_mut_ptr()) };
+        bind_texture(0, gl::TEXTURE_2D, ids[0]);
+        bind_texture(1, gl::TEXTURE_2D, ids[1]);
+        bind_texture(2, gl::TEXTURE_2D, ids[0]);
+
+        forget_texture(ids[0]);
+        let ( changes, skipped ) = counters();
+        bind_texture(1, gl::TEXTURE_2D, ids[1]);
+        assert_eq!(counters(), ( changes, skipped + 1 ));
+        // Both units that held it bind again
+        bind_texture(0, gl::TEXTURE_2D, ids[0]);
+        bind_texture(2, gl::TEXTURE_2D, ids[0]);
+        assert_eq!(counters().1, skipped + 1);
+        unsafe { gl::DeleteTextures(2, ids.as_ptr()) };
+    }
+}
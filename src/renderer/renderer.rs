@@ -0,0 +1,159 @@
+// Renderer -> collects draw commands, sorts them to save state changes, then draws
+use std::{collections::HashMap, rc::Rc};
+
+use glm::{vec4, Mat4};
+
+use crate::{camera::frustum::Culler, materials::material::Material, mesh::mesh::Mesh, renderer::gl_state::{self, UniformValue}};
+
+pub struct DrawCommand {
+    pub mesh: Rc<Mesh>,
+    pub material: Rc<Material>,
+    pub transform: Mat4,
+    pub sort_key: u64,
+}
+
+// Bit layout ( most significant first ):
+//   opaque      -> 0 | shader 16 | texture 16 | depth 31 ( front to back )
+//   transparent -> 1 | depth 31 ( back to front ) | shader 16 | texture 16
+pub fn sort_key( transparent: bool, shader: u32, texture: u32, depth: f32 ) -> u64 {
+    // Depth from -1 ( near ) to 1 ( far ) -> 31 bit unsigned
+    let depth_bits = (((depth.clamp(-1.0, 1.0) + 1.0) / 2.0) * ((1u64 << 31) - 1) as f32) as u64;
+    let ( shader, texture ) = ( (shader & 0xFFFF) as u64, (texture & 0xFFFF) as u64 );
+    if transparent {
+        let back_to_front = ((1u64 << 31) - 1) - depth_bits;
+        1 << 63 | back_to_front << 32 | shader << 16 | texture
+    } else {
+        shader << 47 | texture << 31 | depth_bits
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FrameStats {
+    pub draw_calls: u32,
+    // GL state calls that actually reached the driver
+    pub state_changes: u32,
+    // Binds the state cache found redundant
+    pub skipped_changes: u32,
+    pub triangles: u32,
+    // Submitted but outside the camera, never queued
+    pub culled: u32,
+}
+
+pub struct Renderer {
+    pub view_projection: Mat4,
+    // Uniform names the shaders use for the per-object and camera matrices
+    pub model_uniform: String,
+    pub view_projection_uniform: String,
+    pub last_frame: FrameStats,
+    // Frustum of the current frame, meshes without bounds are always drawn
+    pub culler: Culler,
+    commands: Vec<DrawCommand>,
+    // program -> ( view projection location, model location )
+    locations: HashMap<u32, (i32, i32)>,
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        let view_projection = crate::scene::transform::identity();
+        Self {
+            culler: Culler::new(&view_projection),
+            view_projection,
+            model_uniform: "model".to_string(),
+            view_projection_uniform: "view_projection".to_string(),
+            last_frame: FrameStats::default(),
+            commands: Vec::new(),
+            locations: HashMap::new(),
+        }
+    }
+
+    pub fn begin_frame( &mut self, view_projection: Mat4 ) {
+        self.view_projection = view_projection;
+        self.culler.begin_frame(&view_projection);
+        self.commands.clear();
+    }
+
+    // Queues a draw, the sort key comes from the material and the object's depth
+    // false -> the mesh bounds are outside the frustum and nothing was queued
+    pub fn submit( &mut self, mesh: Rc<Mesh>, material: Rc<Material>, transform: Mat4 ) -> bool {
+        if let Some(bounds) = &mesh.bounds && !self.culler.test_aabb(bounds, &transform) {
+            return false;
+        }
+        let clip = self.view_projection * (transform * vec4(0.0, 0.0, 0.0, 1.0));
+        let depth = if clip.w.abs() > f32::EPSILON { clip.z / clip.w } else { clip.z };
+        let texture = material.texture_ids().first().map( | ( _, id ) | *id ).unwrap_or(0);
+        let sort_key = sort_key(material.transparent, material.shader.id, texture, depth);
+        self.commands.push(DrawCommand { mesh, material, transform, sort_key });
+        true
+    }
+
+    // Sorts and draws everything queued since begin_frame()
+    pub fn end_frame(&mut self) -> FrameStats {
+        self.commands.sort_by_key( | command | command.sort_key );
+
+        let mut stats = FrameStats { culled: self.culler.stats.culled, ..FrameStats::default() };
+        let ( changes_before, skipped_before ) = gl_state::counters();
+
+        for command in &self.commands {
+            let material = &command.material;
+
+            gl_state::set_blend(material.transparent);
+            if material.transparent {
+                gl_state::blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            }
+            material.bind();
+
+            let ( view_projection_location, model_location ) = *self.locations
+                .entry(material.shader.id)
+                .or_insert_with( || (
+                    material.shader.uniform_location(&self.view_projection_uniform),
+                    material.shader.uniform_location(&self.model_uniform),
+                ));
+            // The camera is the same for the whole frame -> the cache sends it once per program
+            gl_state::uniform(view_projection_location, UniformValue::Mat4(self.view_projection));
+            gl_state::uniform(model_location, UniformValue::Mat4(command.transform));
+
+            command.mesh.draw();
+            stats.draw_calls += 1;
+            stats.triangles += command.mesh.index_count as u32 / 3;
+        }
+
+        gl_state::set_blend(false);
+        let ( changes_after, skipped_after ) = gl_state::counters();
+        stats.state_changes = (changes_after - changes_before) as u32;
+        stats.skipped_changes = (skipped_after - skipped_before) as u32;
+
+        self.commands.clear();
+        self.last_frame = stats;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_key_orders_by_pass_then_shader_then_material() {
+        // Opaque before transparent, whatever the rest says
+        assert!(sort_key(false, 0xFFFF, 0xFFFF, 1.0) < sort_key(true, 0, 0, -1.0));
+        // Shader before texture
+        assert!(sort_key(false, 1, 9, -1.0) < sort_key(false, 2, 0, -1.0));
+        // Texture before depth
+        assert!(sort_key(false, 1, 1, 1.0) < sort_key(false, 1, 2, -1.0));
+        // Same state -> front to back
+        assert!(sort_key(false, 1, 1, -0.5) < sort_key(false, 1, 1, 0.5));
+    }
+
+    #[test]
+    fn transparent_keys_go_back_to_front_before_state() {
+        assert!(sort_key(true, 2, 2, 0.5) < sort_key(true, 1, 1, -0.5));
+        assert!(sort_key(true, 1, 1, 0.0) < sort_key(true, 2, 1, 0.0));
+        assert!(sort_key(true, 1, 1, 0.0) < sort_key(true, 1, 2, 0.0));
+    }
+
+    #[test]
+    fn sort_key_clamps_depth_outside_the_clip_range() {
+        assert_eq!(sort_key(false, 3, 4, -5.0), sort_key(false, 3, 4, -1.0));
+        assert_eq!(sort_key(false, 3, 4, 5.0), sort_key(false, 3, 4, 1.0));
+    }
+}
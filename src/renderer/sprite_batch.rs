@@ -0,0 +1,247 @@
+// Sprite Batch -> many textured quads per draw call, for 2D UI and sprites
+use std::{ptr::null, rc::Rc};
+
+use glm::{Mat4, Vec2};
+
+use crate::{renderer::{gl_debug, gl_state::{self, UniformValue}}, shaders::shader::Shader, textures::texture::Texture};
+
+// x, y, u, v, r, g, b, a
+const FLOATS_PER_VERTEX: usize = 8;
+const FLOATS_PER_SPRITE: usize = FLOATS_PER_VERTEX * 4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    // Center of the quad
+    pub position: Vec2,
+    pub size: Vec2,
+    // Radians, counter-clockwise around the center
+    pub rotation: f32,
+    // u0, v0, u1, v1 -> the part of the texture shown
+    pub uv: [f32; 4],
+    pub color: [f32; 4],
+}
+
+impl Sprite {
+    pub fn new( position: Vec2, size: Vec2 ) -> Self {
+        Self { position, size, rotation: 0.0, uv: [ 0.0, 0.0, 1.0, 1.0 ], color: [ 1.0, 1.0, 1.0, 1.0 ] }
+    }
+
+    pub fn with_rotation( mut self, rotation: f32 ) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_uv( mut self, uv: [f32; 4] ) -> Self {
+        self.uv = uv;
+        self
+    }
+
+    pub fn with_color( mut self, color: [f32; 4] ) -> Self {
+        self.color = color;
+        self
+    }
+
+    // Bottom left, bottom right, top right, top left
+    pub fn vertices(&self) -> [f32; FLOATS_PER_SPRITE] {
+        let ( sin, cos ) = self.rotation.sin_cos();
+        let ( half_w, half_h ) = ( self.size.x / 2.0, self.size.y / 2.0 );
+        let [ u0, v0, u1, v1 ] = self.uv;
+        let corners = [
+            ( -half_w, -half_h, u0, v0 ),
+            (  half_w, -half_h, u1, v0 ),
+            (  half_w,  half_h, u1, v1 ),
+            ( -half_w,  half_h, u0, v1 ),
+        ];
+
+        let mut vertices = [ 0.0; FLOATS_PER_SPRITE ];
+        for ( i, ( x, y, u, v ) ) in corners.into_iter().enumerate() {
+            let vertex = &mut vertices[i * FLOATS_PER_VERTEX..(i + 1) * FLOATS_PER_VERTEX];
+            vertex[0] = self.position.x + x * cos - y * sin;
+            vertex[1] = self.position.y + x * sin + y * cos;
+            vertex[2] = u;
+            vertex[3] = v;
+            vertex[4..8].copy_from_slice(&self.color);
+        }
+        vertices
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BatchStats {
+    pub sprites: u32,
+    pub draw_calls: u32,
+}
+
+pub struct SpriteBatch {
+    pub shader: Rc<Shader>,
+    pub view_projection: Mat4,
+    pub stats: BatchStats,
+    vao: u32,
+    vbo: u32,
+    // Never touched after new(), kept to be deleted
+    ebo: u32,
+    // Sprites per flush
+    capacity: usize,
+    // Looked up once, the shader never changes
+    view_projection_location: i32,
+    texture_location: i32,
+    vertices: Vec<f32>,
+    texture: Option<( u32, u32 )>,
+}
+
+impl SpriteBatch {
+    // The index buffer never changes and stays bound to the vao, only the vertex buffer is streamed
+    pub fn new( shader: Rc<Shader>, capacity: usize ) -> Self {
+        // 0 could never flush anything, and 4 vertices per sprite must fit the u32 indices
+        assert!(capacity > 0 && capacity <= (u32::MAX / 4) as usize, "sprite batch capacity {} out of range", capacity);
+        let mut indices: Vec<u32> = Vec::with_capacity(capacity * 6);
+        for sprite in 0..capacity as u32 {
+            let first = sprite * 4;
+            indices.extend_from_slice(&[ first, first + 1, first + 2, first, first + 2, first + 3 ]);
+        }
+
+        let ( mut vao, mut vbo, mut ebo ) = ( 0, 0, 0 );
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::GenBuffers(1, &mut ebo);
+        }
+        gl_state::bind_vertex_array(vao);
+        gl_state::bind_buffer(gl::ARRAY_BUFFER, vbo);
+        gl_state::bind_buffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+        unsafe {
+            gl::BufferData(gl::ARRAY_BUFFER, (capacity * FLOATS_PER_SPRITE * size_of::<f32>()) as isize, null(), gl::STREAM_DRAW);
+            gl::BufferData(gl::ELEMENT_ARRAY_BUFFER, size_of_val(indices.as_slice()) as isize, indices.as_ptr() as *const _, gl::STATIC_DRAW);
+
+            let stride = (FLOATS_PER_VERTEX * size_of::<f32>()) as i32;
+            // Same locations as the other shaders -> 0 aPos, 1 aColor, 2 aTexCoord
+            gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, stride, null());
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(1, 4, gl::FLOAT, gl::FALSE, stride, (4 * size_of::<f32>()) as *const _);
+            gl::EnableVertexAttribArray(1);
+            gl::VertexAttribPointer(2, 2, gl::FLOAT, gl::FALSE, stride, (2 * size_of::<f32>()) as *const _);
+            gl::EnableVertexAttribArray(2);
+        }
+        gl_state::bind_vertex_array(0);
+        gl_debug::label(gl::VERTEX_ARRAY, vao, "sprite batch");
+        gl_debug::label(gl::BUFFER, vbo, "sprite batch vertices");
+        gl_debug::label(gl::BUFFER, ebo, "sprite batch indices");
+
+        Self {
+            view_projection_location: shader.uniform_location("view_projection"),
+            texture_location: shader.uniform_location("sprite_texture"),
+            shader,
+            view_projection: crate::scene::transform::identity(),
+            stats: BatchStats::default(),
+            vao,
+            vbo,
+            ebo,
+            capacity,
+            vertices: Vec::with_capacity(capacity * FLOATS_PER_SPRITE),
+            texture: None,
+        }
+    }
+
+    pub fn begin( &mut self, view_projection: Mat4 ) {
+        self.view_projection = view_projection;
+        self.stats = BatchStats::default();
+        self.vertices.clear();
+    }
+
+    pub fn draw( &mut self, texture: &Texture, sprite: &Sprite ) {
+        let key = ( texture.target, texture.id );
+        if self.texture.is_some_and( | current | current != key ) {
+            self.flush();
+        }
+        if self.queued() == self.capacity {
+            self.flush();
+        }
+        self.texture = Some(key);
+        self.vertices.extend_from_slice(&sprite.vertices());
+        self.stats.sprites += 1;
+    }
+
+    pub fn queued(&self) -> usize {
+        self.vertices.len() / FLOATS_PER_SPRITE
+    }
+
+    pub fn end(&mut self) -> BatchStats {
+        self.flush();
+        self.texture = None;
+        self.stats
+    }
+
+    // Draws what is queued, the buffer is orphaned first so the GPU can keep reading the old one
+    pub fn flush(&mut self) {
+        let Some(( target, texture )) = self.texture else { return };
+        let sprites = self.queued();
+        if sprites == 0 {
+            return;
+        }
+
+        gl_state::use_program(self.shader.id);
+        gl_state::bind_texture(0, target, texture);
+        gl_state::set_blend(true);
+        gl_state::blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+        gl_state::bind_vertex_array(self.vao);
+        gl_state::bind_buffer(gl::ARRAY_BUFFER, self.vbo);
+        gl_state::uniform(self.view_projection_location, UniformValue::Mat4(self.view_projection));
+        gl_state::uniform(self.texture_location, UniformValue::Int(0));
+        unsafe {
+            gl::BufferData(gl::ARRAY_BUFFER, (self.capacity * FLOATS_PER_SPRITE * size_of::<f32>()) as isize, null(), gl::STREAM_DRAW);
+            gl::BufferSubData(gl::ARRAY_BUFFER, 0, size_of_val(self.vertices.as_slice()) as isize, self.vertices.as_ptr() as *const _);
+            gl::DrawElements(gl::TRIANGLES, (sprites * 6) as i32, gl::UNSIGNED_INT, null());
+        }
+        // Same as the Renderer -> whatever draws next starts with blending off
+        gl_state::set_blend(false);
+
+        self.vertices.clear();
+        self.stats.draw_calls += 1;
+    }
+}
+
+// Needs the context that created it to still be current, same as FrameCapture
+impl Drop for SpriteBatch {
+    fn drop( &mut self ) {
+        let buffers = [ self.vbo, self.ebo ];
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(buffers.len() as i32, buffers.as_ptr());
+        }
+        gl_state::forget_vertex_array(self.vao);
+        for buffer in buffers {
+            gl_state::forget_buffer(buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use glm::vec2;
+
+    #[test]
+    #[should_panic(expected = "capacity 0 out of range")]
+    fn empty_batches_are_rejected() {
+        // Panics before any GL call, so no context is needed
+        SpriteBatch::new(Rc::new(Shader { id: 0 }), 0);
+    }
+
+    #[test]
+    fn sprite_corners_rotate_around_the_center() {
+        let sprite = Sprite::new(vec2(10.0, 20.0), vec2(4.0, 2.0))
+            .with_rotation(std::f32::consts::FRAC_PI_2)
+            .with_uv([ 0.5, 0.0, 1.0, 0.5 ])
+            .with_color([ 1.0, 0.0, 0.0, 0.5 ]);
+        let vertices = sprite.vertices();
+        let corner = | i: usize | {
+            let v = &vertices[i * FLOATS_PER_VERTEX..(i + 1) * FLOATS_PER_VERTEX];
+            ( (v[0] * 1000.0).round() / 1000.0, (v[1] * 1000.0).round() / 1000.0, v[2], v[3] )
+        };
+        // Bottom left ( -2, -1 ) turned a quarter -> ( 1, -2 )
+        assert_eq!(corner(0), ( 11.0, 18.0, 0.5, 0.0 ));
+        assert_eq!(corner(2), ( 9.0, 22.0, 1.0, 0.5 ));
+        assert_eq!(&vertices[4..8], &[ 1.0, 0.0, 0.0, 0.5 ]);
+    }
+}
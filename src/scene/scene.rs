@@ -0,0 +1,193 @@
+// Scene Graph -> nodes with local TRS, world matrices are computed on demand
+use std::cell::Cell;
+
+use glm::Mat4;
+
+use crate::scene::transform::{identity, Transform};
+
+pub type NodeId = usize;
+
+pub struct Node {
+    pub name: String,
+    // Index into whatever mesh list the app keeps, None for pure group nodes
+    pub mesh: Option<usize>,
+    pub visible: bool,
+    local: Transform,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    world: Cell<Mat4>,
+    dirty: Cell<bool>,
+}
+
+#[derive(Default)]
+pub struct Scene {
+    nodes: Vec<Node>,
+    roots: Vec<NodeId>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_node( &mut self, name: &str, local: Transform, parent: Option<NodeId> ) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            mesh: None,
+            visible: true,
+            local,
+            parent,
+            children: Vec::new(),
+            world: Cell::new(identity()),
+            dirty: Cell::new(true),
+        });
+        match parent {
+            Some(parent) => self.nodes[parent].children.push(id),
+            None => self.roots.push(id),
+        }
+        id
+    }
+
+    pub fn add_mesh_node( &mut self, name: &str, mesh: usize, local: Transform, parent: Option<NodeId> ) -> NodeId {
+        let id = self.add_node(name, local, parent);
+        self.nodes[id].mesh = Some(mesh);
+        id
+    }
+
+    pub fn find( &self, name: &str ) -> Option<NodeId> {
+        self.nodes.iter().position( | node | node.name == name )
+    }
+
+    // Edit the transform in place, children pick the change up on their next world_matrix()
+    pub fn update_local<F: FnOnce(&mut Transform)>( &mut self, id: NodeId, update: F ) {
+        update(&mut self.nodes[id].local);
+        self.mark_dirty(id);
+    }
+
+    fn mark_dirty( &self, id: NodeId ) {
+        let mut stack = vec![ id ];
+        while let Some(current) = stack.pop() {
+            let node = &self.nodes[current];
+            // Already dirty -> the whole subtree is dirty too
+            if node.dirty.replace(true) && current != id {
+                continue;
+            }
+            stack.extend_from_slice(&node.children);
+        }
+    }
+
+    // Moves `id` under `new_parent` ( None -> becomes a root ), the local transform is kept
+    pub fn reparent( &mut self, id: NodeId, new_parent: Option<NodeId> ) -> Result<(), String> {
+        // Walking up from the new parent must never reach the node itself
+        let mut ancestor = new_parent;
+        while let Some(current) = ancestor {
+            if current == id {
+                return Err(format!("Cannot move '{}' under its own descendant", self.nodes[id].name));
+            }
+            ancestor = self.nodes[current].parent;
+        }
+
+        match self.nodes[id].parent {
+            Some(old_parent) => self.nodes[old_parent].children.retain( | child | *child != id ),
+            None => self.roots.retain( | root | *root != id ),
+        }
+        match new_parent {
+            Some(parent) => self.nodes[parent].children.push(id),
+            None => self.roots.push(id),
+        }
+        self.nodes[id].parent = new_parent;
+        self.mark_dirty(id);
+        Ok(())
+    }
+
+    // Parent world * local, only recomputed when something above changed
+    pub fn world_matrix( &self, id: NodeId ) -> Mat4 {
+        let node = &self.nodes[id];
+        if !node.dirty.get() {
+            return node.world.get();
+        }
+        let local = node.local.matrix();
+        let world = match node.parent {
+            Some(parent) => self.world_matrix(parent) * local,
+            None => local,
+        };
+        node.world.set(world);
+        node.dirty.set(false);
+        world
+    }
+
+    // Depth first, parents before children, siblings in insertion order.
+    // Hidden nodes hide their whole subtree.
+    pub fn draw_order(&self) -> Vec<NodeId> {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        let mut stack = self.roots.iter().rev().copied().collect::<Vec<NodeId>>();
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !node.visible {
+                continue;
+            }
+            order.push(id);
+            stack.extend(node.children.iter().rev());
+        }
+        order
+    }
+
+    // ( node, world matrix, mesh ) for every visible node that has a mesh
+    pub fn visible_meshes(&self) -> Vec<(NodeId, Mat4, usize)> {
+        self.draw_order()
+            .into_iter()
+            .filter_map( | id | self.nodes[id].mesh.map( | mesh | ( id, self.world_matrix(id), mesh ) ) )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use glm::{vec3, vec4};
+
+    use crate::scene::transform::Quat;
+
+    impl Scene {
+        fn roots(&self) -> &[NodeId] {
+            &self.roots
+        }
+
+        fn node_mut( &mut self, id: NodeId ) -> &mut Node {
+            &mut self.nodes[id]
+        }
+    }
+
+    fn origin_of( world: Mat4 ) -> [f32; 3] {
+        let origin = world * vec4(0.0, 0.0, 0.0, 1.0);
+        [ origin.x, origin.y, origin.z ].map( | v | (v * 1000.0).round() / 1000.0 )
+    }
+
+    #[test]
+    fn children_follow_their_parent() {
+        let mut scene = Scene::new();
+        let row = scene.add_node("row", Transform::from_translation(vec3(1.0, 0.0, 0.0)), None);
+        let shape = scene.add_mesh_node("shape", 0, Transform::from_translation(vec3(0.0, 2.0, 0.0)), Some(row));
+        assert_eq!(origin_of(scene.world_matrix(shape)), [ 1.0, 2.0, 0.0 ]);
+
+        // A quarter turn of the parent swings the child around it
+        scene.update_local(row, | local | local.rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2) );
+        assert_eq!(origin_of(scene.world_matrix(shape)), [ -1.0, 0.0, 0.0 ]);
+        assert_eq!(scene.visible_meshes().iter().map( | ( id, _, mesh ) | ( *id, *mesh ) ).collect::<Vec<_>>(), vec![ ( shape, 0 ) ]);
+
+        scene.node_mut(row).visible = false;
+        assert!(scene.visible_meshes().is_empty());
+    }
+
+    #[test]
+    fn reparenting_under_a_descendant_fails() {
+        let mut scene = Scene::new();
+        let a = scene.add_node("a", Transform::identity(), None);
+        let b = scene.add_node("b", Transform::identity(), Some(a));
+        assert!(scene.reparent(a, Some(b)).is_err());
+        assert!(scene.reparent(b, None).is_ok());
+        assert_eq!(scene.roots(), &[ a, b ]);
+    }
+}
@@ -0,0 +1,138 @@
+// Translation - Rotation - Scale with a quaternion rotation
+use std::ops::Mul;
+
+use glm::{mat4, vec3, Mat4, Vec3};
+
+// Unit quaternion ( x, y, z, w ), same order as glTF
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quat {
+    pub fn new( x: f32, y: f32, z: f32, w: f32 ) -> Self {
+        Self { x, y, z, w }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    // `angle` in radians around a ( normalized ) axis
+    pub fn from_axis_angle( axis: Vec3, angle: f32 ) -> Self {
+        let axis = glm::normalize(axis);
+        let ( sin, cos ) = (angle / 2.0).sin_cos();
+        Self::new(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    // Rotation around Z only -> enough for the 2D demos
+    pub fn from_rotation_z( angle: f32 ) -> Self {
+        Self::from_axis_angle(vec3(0.0, 0.0, 1.0), angle)
+    }
+
+    pub fn length(&self) -> f32 {
+        (self.x * self.x + self.y * self.y + self.z * self.z + self.w * self.w).sqrt()
+    }
+
+    pub fn normalize(&self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            return Self::identity();
+        }
+        Self::new(self.x / length, self.y / length, self.z / length, self.w / length)
+    }
+
+    // Shortest path interpolation, `t` from 0 to 1
+    pub fn slerp( &self, other: Quat, t: f32 ) -> Self {
+        let mut dot = self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w;
+        let mut other = other;
+        if dot < 0.0 {
+            dot = -dot;
+            other = Quat::new(-other.x, -other.y, -other.z, -other.w);
+        }
+        // Nearly the same rotation -> plain lerp avoids dividing by sin(0)
+        let ( a, b ) = if dot > 0.9995 {
+            ( 1.0 - t, t )
+        } else {
+            let angle = dot.acos();
+            let sin = angle.sin();
+            ( ((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin )
+        };
+        Quat::new(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        ).normalize()
+    }
+
+    pub fn to_mat4(self) -> Mat4 {
+        Transform { translation: vec3(0.0, 0.0, 0.0), rotation: self, scale: vec3(1.0, 1.0, 1.0) }.matrix()
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+
+    // Hamilton product -> apply `rhs` first, then `self`
+    fn mul( self, rhs: Quat ) -> Quat {
+        Quat::new(
+            self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    pub fn identity() -> Self {
+        Self {
+            translation: vec3(0.0, 0.0, 0.0),
+            rotation: Quat::identity(),
+            scale: vec3(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn from_translation( translation: Vec3 ) -> Self {
+        Self { translation, ..Self::identity() }
+    }
+
+    // Scale first, then rotate, then translate ( T * R * S )
+    pub fn matrix(&self) -> Mat4 {
+        let Quat { x, y, z, w } = self.rotation;
+        let Vec3 { x: sx, y: sy, z: sz } = self.scale;
+        let Vec3 { x: tx, y: ty, z: tz } = self.translation;
+        mat4(
+            (1.0 - 2.0 * (y * y + z * z)) * sx, (2.0 * (x * y + z * w)) * sx, (2.0 * (x * z - y * w)) * sx, 0.0,
+            (2.0 * (x * y - z * w)) * sy, (1.0 - 2.0 * (x * x + z * z)) * sy, (2.0 * (y * z + x * w)) * sy, 0.0,
+            (2.0 * (x * z + y * w)) * sz, (2.0 * (y * z - x * w)) * sz, (1.0 - 2.0 * (x * x + y * y)) * sz, 0.0,
+            tx, ty, tz, 1.0,
+        )
+    }
+}
+
+pub fn identity() -> Mat4 {
+    mat4(
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
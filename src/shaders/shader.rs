@@ -1,5 +1,9 @@
 // Implementing the Shaders
-use std::{ffi::CString, ptr::null_mut};
+use std::ffi::CString;
+
+use log::{debug, error};
+
+use crate::{logging::logging::SHADER, renderer::gl_debug::{self, gl_check}};
 
 pub struct Shader {
     pub id: gl::types::GLuint
@@ -20,24 +24,19 @@ impl Shader {
         Self::shader_source(vertex_shader, vertex_shader_src);
         Self::shader_source(fragment_shader, fragment_shader_src);
 
-        // If no Error then
         Self::compile_shader(vertex_shader);
-        println!("Vertex shader compile success!");
         Self::compile_shader(fragment_shader);
-        println!("Fragment shader compile success!");
 
-        // Chk for Error
-        Self::chk_for_any_err(vertex_shader);
-        println!("Vertex shader compile status - No Error!");
-        Self::chk_for_any_err(fragment_shader);
-        println!("Fragment shader compile status - No Error!");
+        // Chk for Error, both so a broken pair reports both logs
+        let vertex_ok = Self::chk_for_any_err(vertex_shader, "vertex");
+        let fragment_ok = Self::chk_for_any_err(fragment_shader, "fragment");
 
         let shader_program = unsafe {
             let shader_program_id = gl::CreateProgram();
             gl::AttachShader( shader_program_id, vertex_shader );
             gl::AttachShader( shader_program_id, fragment_shader );
 
-            gl::LinkProgram(shader_program_id);
+            gl_check!(gl::LinkProgram(shader_program_id));
             // gl::UseProgram(shader_program_id);
 
             gl::DeleteShader(vertex_shader);
@@ -45,7 +44,9 @@ impl Shader {
             shader_program_id
         };
 
-        // println!("Shader program -> {:?}",shader_program);
+        if vertex_ok && fragment_ok && Self::chk_link(shader_program) {
+            debug!(target: SHADER, "program {} compiled and linked", shader_program);
+        }
 
         Shader {
             id: shader_program
@@ -53,6 +54,19 @@ impl Shader {
 
     }
 
+    // Name of the program in debuggers and driver messages
+    pub fn label( &self, name: &str ) {
+        gl_debug::label(gl::PROGRAM, self.id, name);
+    }
+
+    // -1 when the uniform doesn't exist or was optimized away
+    pub fn uniform_location( &self, name: &str ) -> i32 {
+        let c_name = CString::new(name).expect("Invalid Uniform Provided");
+        unsafe {
+            gl::GetUniformLocation(self.id, c_name.as_ptr())
+        }
+    }
+
     fn shader_source( shader:u32,shader_src:&str ) {
 
         let c_str = CString::new(shader_src).expect("CString Error!");
@@ -72,17 +86,32 @@ impl Shader {
         }
     }
 
-    fn chk_for_any_err( shader:u32 ){
+    // Logs the compiler output when it failed, `kind` is "vertex" / "fragment"
+    fn chk_for_any_err( shader: u32, kind: &str ) -> bool {
         let mut success = 0;
         unsafe {
             gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
-            println!("Success -> {:?}", success);
         }
-        let mut log = [ 0; 512  ];
         if success == 0 {
-            unsafe { gl::GetShaderInfoLog(shader, 512, null_mut(), log.as_mut_ptr() )};
-            println!("Log :-> {:?}", log);
-            println!("Shader Error! {:?}", log );
+            let mut log = [ 0u8; 512 ];
+            let mut length = 0;
+            unsafe { gl::GetShaderInfoLog(shader, log.len() as i32, &mut length, log.as_mut_ptr() as *mut _) };
+            error!(target: SHADER, "{} shader failed to compile:\n{}", kind, String::from_utf8_lossy(&log[..length.max(0) as usize]).trim_end());
+        }
+        success != 0
+    }
+
+    fn chk_link( program: u32 ) -> bool {
+        let mut success = 0;
+        unsafe {
+            gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+        }
+        if success == 0 {
+            let mut log = [ 0u8; 512 ];
+            let mut length = 0;
+            unsafe { gl::GetProgramInfoLog(program, log.len() as i32, &mut length, log.as_mut_ptr() as *mut _) };
+            error!(target: SHADER, "program {} failed to link:\n{}", program, String::from_utf8_lossy(&log[..length.max(0) as usize]).trim_end());
         }
+        success != 0
     }
 }
\ No newline at end of file
@@ -1,25 +1,45 @@
+use std::ptr::null;
+
 use gl;
 
-use crate::lib::load_image::load_image_into_cpu;
+use crate::{
+    lib::load_image::{load_image_from_memory, load_image_into_cpu},
+    logging::logging::TEXTURE,
+    renderer::{gl_debug::{self, gl_check}, gl_state}
+};
 
 pub struct Texture {
-    pub id: gl::types::GLuint
+    pub id: gl::types::GLuint,
+    // TEXTURE_2D or TEXTURE_2D_ARRAY
+    pub target: gl::types::GLenum,
 }
 
 impl Texture {
-    pub fn new( image_path: &str ) -> Self {
-        let mut texture:u32 = 0;
-        unsafe {
+    // Err when the file is missing or can't be decoded, nothing is created on the GPU then
+    pub fn new( image_path: &str ) -> Result<Self, String> {
+        // load image
+        let ( width, height, image_data ) = load_image_into_cpu(image_path)?;
+        log::debug!(target: TEXTURE, "{} -> {}x{}", image_path, width, height);
+        // println!("Image Data -> {:?}",image_data );
+
+        let texture = Self::from_pixels(width, height, image_data);
+        texture.label(image_path);
+        Ok(texture)
+    }
 
-            // load image
-            let ( width, height, image_data ) = load_image_into_cpu(image_path);
-            println!("Width -> {:?}",width );
-            println!("Height -> {:?}",height );
-            // println!("Image Data -> {:?}",image_data );
+    // Encoded image bytes ( PNG, JPEG, ... ) e.g. embedded in a .glb file
+    pub fn from_memory( bytes: &[u8] ) -> Result<Self, String> {
+        let ( width, height, image_data ) = load_image_from_memory(bytes)?;
+        Ok(Self::from_pixels(width, height, image_data))
+    }
 
+    // Raw RGBA8 pixels, first row is the bottom of the image
+    pub fn from_pixels( width: i32, height: i32, image_data: Vec<u8> ) -> Self {
+        let mut texture:u32 = 0;
+        unsafe {
             // Generate A Textures
             gl::GenTextures(1, &mut texture);
-            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl_state::bind_texture(0, gl::TEXTURE_2D, texture);
 
             // Configure the S-T axis
             Self::config_s_t_axis();
@@ -31,14 +51,44 @@ impl Texture {
             Self::set_img_in_our_texture(width, height, image_data);
 
             // Generate MipMaps
-            gl::GenerateMipmap(gl::TEXTURE_2D);
+            gl_check!(gl::GenerateMipmap(gl::TEXTURE_2D));
+        }
+        Self {
+            id: texture,
+            target: gl::TEXTURE_2D,
+        }
+    }
+
+    // One RGBA8 image per layer, all of the same size -> sampled with `sampler2DArray`
+    pub fn array_from_pixels( width: i32, height: i32, layers: &[Vec<u8>] ) -> Self {
+        let mut texture:u32 = 0;
+        unsafe {
+            gl::GenTextures(1, &mut texture);
+            gl_state::bind_texture(0, gl::TEXTURE_2D_ARRAY, texture);
 
-            Self {
-                id: texture
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MIN_FILTER, gl::LINEAR_MIPMAP_LINEAR as i32);
+            gl::TexParameteri(gl::TEXTURE_2D_ARRAY, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+
+            gl::TexImage3D(gl::TEXTURE_2D_ARRAY, 0, gl::RGBA as i32, width, height, layers.len() as i32, 0, gl::RGBA, gl::UNSIGNED_BYTE, null());
+            for ( layer, pixels ) in layers.iter().enumerate() {
+                gl::TexSubImage3D(gl::TEXTURE_2D_ARRAY, 0, 0, 0, layer as i32, width, height, 1, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_ptr() as *const _);
             }
+            gl_check!(gl::GenerateMipmap(gl::TEXTURE_2D_ARRAY));
+        }
+
+        Self {
+            id: texture,
+            target: gl::TEXTURE_2D_ARRAY,
         }
     }
 
+    // Name in debuggers and driver messages, e.g. the file it came from
+    pub fn label( &self, name: &str ) {
+        gl_debug::label(gl::TEXTURE, self.id, name);
+    }
+
     pub fn min_mag_configs() {
         unsafe {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
@@ -0,0 +1,118 @@
+// Bounding Volumes -> used for frustum culling
+use glm::{vec3, vec4, Mat4, Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Aabb {
+    pub fn new( min: Vec3, max: Vec3 ) -> Self {
+        Self { min, max }
+    }
+
+    // Smallest box around every position, None for an empty mesh
+    pub fn from_points( points: &[[f32; 3]] ) -> Option<Self> {
+        let first = points.first()?;
+        let mut min = vec3(first[0], first[1], first[2]);
+        let mut max = min;
+        for point in points.iter().skip(1) {
+            min = vec3(min.x.min(point[0]), min.y.min(point[1]), min.z.min(point[2]));
+            max = vec3(max.x.max(point[0]), max.y.max(point[1]), max.z.max(point[2]));
+        }
+        Some(Self { min, max })
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn corners(&self) -> [Vec3; 8] {
+        let ( min, max ) = ( self.min, self.max );
+        [
+            vec3(min.x, min.y, min.z), vec3(max.x, min.y, min.z),
+            vec3(min.x, max.y, min.z), vec3(max.x, max.y, min.z),
+            vec3(min.x, min.y, max.z), vec3(max.x, min.y, max.z),
+            vec3(min.x, max.y, max.z), vec3(max.x, max.y, max.z),
+        ]
+    }
+
+    // Box around the 8 transformed corners ( still axis aligned in world space )
+    pub fn transformed( &self, model: &Mat4 ) -> Self {
+        let corners = self.corners()
+                        .iter()
+                        .map( | corner | {
+                            let world = *model * vec4(corner.x, corner.y, corner.z, 1.0);
+                            [ world.x, world.y, world.z ]
+                        })
+                        .collect::<Vec<[f32; 3]>>();
+        Self::from_points(&corners).expect("A box always has 8 corners")
+    }
+
+    pub fn union( &self, other: &Aabb ) -> Self {
+        Self {
+            min: vec3(self.min.x.min(other.min.x), self.min.y.min(other.min.y), self.min.z.min(other.min.z)),
+            max: vec3(self.max.x.max(other.max.x), self.max.y.max(other.max.y), self.max.z.max(other.max.z)),
+        }
+    }
+}
+
+impl BoundingSphere {
+    pub fn new( center: Vec3, radius: f32 ) -> Self {
+        Self { center, radius }
+    }
+
+    // Centered on the AABB of the points, radius reaches the farthest point
+    pub fn from_points( points: &[[f32; 3]] ) -> Option<Self> {
+        let center = Aabb::from_points(points)?.center();
+        let radius = points
+                        .iter()
+                        .map( | point | glm::distance(center, vec3(point[0], point[1], point[2])) )
+                        .fold(0.0, f32::max);
+        Some(Self::new(center, radius))
+    }
+
+    // Moves the center and grows the radius by the largest axis scale of `model`
+    pub fn transformed( &self, model: &Mat4 ) -> Self {
+        let center = *model * vec4(self.center.x, self.center.y, self.center.z, 1.0);
+        let scale_x = glm::length(vec3(model.c0.x, model.c0.y, model.c0.z));
+        let scale_y = glm::length(vec3(model.c1.x, model.c1.y, model.c1.z));
+        let scale_z = glm::length(vec3(model.c2.x, model.c2.y, model.c2.z));
+        Self::new(vec3(center.x, center.y, center.z), self.radius * scale_x.max(scale_y).max(scale_z))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::scene::transform::identity;
+
+    #[test]
+    fn boxes_and_spheres_follow_the_model_matrix() {
+        let unit = Aabb::from_points(&[ [ -1.0, -1.0, -1.0 ], [ 1.0, 1.0, 1.0 ], [ 0.0, 0.5, 0.0 ] ]).unwrap();
+        let model = glm::ext::scale(&glm::ext::translate(&identity(), vec3(3.0, 0.0, 0.0)), vec3(2.0, 1.0, 1.0));
+        let moved = unit.transformed(&model);
+        assert_eq!(moved, Aabb::new(vec3(1.0, -1.0, -1.0), vec3(5.0, 1.0, 1.0)));
+
+        let sphere = BoundingSphere::new(vec3(0.0, 0.0, 0.0), 1.0).transformed(&model);
+        assert_eq!(sphere, BoundingSphere::new(vec3(3.0, 0.0, 0.0), 2.0));
+    }
+
+    #[test]
+    fn empty_point_lists_have_no_bounds() {
+        assert_eq!(Aabb::from_points(&[]), None);
+        assert_eq!(BoundingSphere::from_points(&[]), None);
+    }
+}
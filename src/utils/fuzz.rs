@@ -0,0 +1,58 @@
+// Deterministic random input for the parser tests -> same seed, same documents, a failure reproduces every run
+
+// xorshift64*, plenty for picking characters and shapes
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new( seed: u64 ) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // 0..bound
+    pub fn below( &mut self, bound: usize ) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn chance( &mut self, one_in: usize ) -> bool {
+        self.below(one_in) == 0
+    }
+
+    pub fn pick<'a, T>( &mut self, items: &'a [T] ) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    // Mostly plain ascii, sometimes a quote, backslash, control character or something outside ascii
+    pub fn text( &mut self, max_length: usize ) -> String {
+        const SPECIAL: &[char] = &[ '"', '\'', '\\', '\n', '\t', '\r', '\u{0}', '\u{1f}', '#', '=', '[', '{', 'é', '€', '😀' ];
+        (0..self.below(max_length + 1))
+            .map( | _ | if self.chance(4) { *self.pick(SPECIAL) } else { (b' ' + self.below(95) as u8) as char } )
+            .collect()
+    }
+
+    // `source` with a few bytes deleted, duplicated or swapped for one of `alphabet`, cut at a random point
+    // half of the time. Lossy -> the parsers only ever see &str
+    pub fn mutate( &mut self, source: &str, alphabet: &[u8] ) -> String {
+        let mut bytes = source.as_bytes().to_vec();
+        for _ in 0..1 + self.below(4) {
+            let position = self.below(bytes.len() + 1);
+            match self.below(3) {
+                0 if position < bytes.len() => { bytes.remove(position); },
+                1 if position < bytes.len() => bytes.insert(position, bytes[position]),
+                _ => bytes.insert(position, *self.pick(alphabet)),
+            }
+        }
+        if self.chance(2) {
+            bytes.truncate(self.below(bytes.len() + 1));
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
@@ -0,0 +1,457 @@
+// Small JSON reader and writer -> enough for glTF, our own tooling files and the JSON lines log.
+// Hand rolled instead of serde_json: every reader walks a dynamic value anyway, nothing here would use derive,
+// and the depth limit and byte offsets in the errors are ours. The tests below throw generated and mangled
+// documents at it
+use std::{collections::BTreeMap, fmt};
+
+// Arrays / objects nested deeper than this are rejected instead of overflowing the stack
+pub const MAX_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+impl Json {
+    pub fn parse( source: &str ) -> Result<Json, String> {
+        let mut parser = Parser { bytes: source.as_bytes(), position: 0, depth: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.position != parser.bytes.len() {
+            return Err(parser.error("trailing characters after the JSON value"));
+        }
+        Ok(value)
+    }
+
+    // Missing keys and wrong types both give None
+    pub fn get( &self, key: &str ) -> Option<&Json> {
+        match self {
+            Json::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        self.as_f64().map( | number | number as f32 )
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_f64().filter( | number | *number >= 0.0 && number.fract() == 0.0 ).map( | number | number as usize )
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Json>> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    // Array of numbers with exactly N entries, e.g. a vec3 or a mat4
+    pub fn as_f32_array<const N: usize>(&self) -> Option<[f32; N]> {
+        let values = self.as_array()?;
+        if values.len() != N {
+            return None;
+        }
+        let mut result = [ 0.0; N ];
+        for ( slot, value ) in result.iter_mut().zip(values) {
+            *slot = value.as_f32()?;
+        }
+        Some(result)
+    }
+}
+
+// Compact, on one line -> `json.to_string()` is one JSON lines record
+impl fmt::Display for Json {
+    fn fmt( &self, f: &mut fmt::Formatter<'_> ) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            // JSON has no NaN / infinity
+            Json::Number(number) if !number.is_finite() => write!(f, "null"),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::String(text) => write_string(f, text),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for ( i, value ) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(map) => {
+                write!(f, "{{")?;
+                for ( i, ( key, value ) ) in map.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+fn write_string( f: &mut fmt::Formatter<'_>, text: &str ) -> fmt::Result {
+    write!(f, "\"")?;
+    for character in text.chars() {
+        match character {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            control if (control as u32) < 0x20 => write!(f, "\\u{:04x}", control as u32)?,
+            other => write!(f, "{}", other)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    // Arrays / objects we are inside of right now
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error( &self, message: &str ) -> String {
+        format!("JSON error at byte {}: {}", self.position, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn expect( &mut self, byte: u8 ) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.bytes.get(self.position) == Some(&byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    fn literal( &mut self, word: &str, value: Json ) -> Result<Json, String> {
+        if self.bytes[self.position..].starts_with(word.as_bytes()) {
+            self.position += word.len();
+            Ok(value)
+        } else {
+            Err(self.error("unknown literal"))
+        }
+    }
+
+    fn value( &mut self ) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.bytes.get(self.position) {
+            Some(b'{') => self.nested(Self::object),
+            Some(b'[') => self.nested(Self::array),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn nested( &mut self, parse: fn(&mut Self) -> Result<Json, String> ) -> Result<Json, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(&format!("nested deeper than {} levels", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn object( &mut self ) -> Result<Json, String> {
+        self.expect(b'{')?;
+        let mut map = BTreeMap::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.position) == Some(&b'}') {
+            self.position += 1;
+            return Ok(Json::Object(map));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(b':')?;
+            let value = self.value()?;
+            map.insert(key, value);
+            self.skip_whitespace();
+            match self.bytes.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(map));
+                },
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn array( &mut self ) -> Result<Json, String> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.bytes.get(self.position) == Some(&b']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+            match self.bytes.get(self.position) {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(values));
+                },
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn string( &mut self ) -> Result<String, String> {
+        if self.bytes.get(self.position) != Some(&b'"') {
+            return Err(self.error("expected a string"));
+        }
+        self.position += 1;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&byte) = self.bytes.get(self.position) else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&escaped) = self.bytes.get(self.position) else {
+                        return Err(self.error("unterminated escape"));
+                    };
+                    self.position += 1;
+                    match escaped {
+                        b'"' => bytes.push(b'"'),
+                        b'\\' => bytes.push(b'\\'),
+                        b'/' => bytes.push(b'/'),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0c),
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'u' => {
+                            let mut code = self.hex4()?;
+                            // Surrogate pair -> one character outside the BMP
+                            if (0xD800..0xDC00).contains(&code) && self.bytes[self.position..].starts_with(b"\\u") {
+                                self.position += 2;
+                                let low = self.hex4()?;
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                            }
+                            let character = char::from_u32(code).unwrap_or('\u{FFFD}');
+                            bytes.extend_from_slice(character.to_string().as_bytes());
+                        },
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                },
+                _ => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err( | _ | self.error("invalid UTF-8 in string") )
+    }
+
+    fn hex4( &mut self ) -> Result<u32, String> {
+        let digits = self.bytes.get(self.position..self.position + 4).ok_or_else( || self.error("short \\u escape") )?;
+        let text = std::str::from_utf8(digits).map_err( | _ | self.error("invalid \\u escape") )?;
+        let code = u32::from_str_radix(text, 16).map_err( | _ | self.error("invalid \\u escape") )?;
+        self.position += 4;
+        Ok(code)
+    }
+
+    fn number( &mut self ) -> Result<Json, String> {
+        let start = self.position;
+        while let Some(byte) = self.bytes.get(self.position) {
+            if byte.is_ascii_digit() || matches!(byte, b'-' | b'+' | b'.' | b'e' | b'E') {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.position]).unwrap_or("");
+        match text.parse::<f64>() {
+            // `1e999` -> infinity, which JSON can't write back
+            Ok(number) if number.is_finite() => Ok(Json::Number(number)),
+            Ok(_) => Err(self.error("number out of range")),
+            Err(_) => Err(self.error("invalid number")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fuzz::Rng;
+
+    fn nested_arrays( depth: usize ) -> String {
+        format!("{}{}", "[".repeat(depth), "]".repeat(depth))
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_parses() {
+        let mut value = &Json::parse(&nested_arrays(MAX_DEPTH)).unwrap();
+        let mut depth = 1;
+        while let Some(inner) = value.as_array().and_then( | values | values.first() ) {
+            value = inner;
+            depth += 1;
+        }
+        assert_eq!(depth, MAX_DEPTH);
+    }
+
+    #[test]
+    fn nesting_past_the_limit_is_an_error() {
+        let err = Json::parse(&nested_arrays(MAX_DEPTH + 1)).unwrap_err();
+        assert!(err.contains("nested deeper than"), "{}", err);
+        // Objects count too, and a huge input fails the same way instead of overflowing the stack
+        let objects = format!("{}1{}", "{\"a\":".repeat(MAX_DEPTH + 1), "}".repeat(MAX_DEPTH + 1));
+        assert!(Json::parse(&objects).is_err());
+        assert!(Json::parse(&nested_arrays(100_000)).is_err());
+    }
+
+    #[test]
+    fn depth_is_released_after_each_value() {
+        // Many shallow siblings never add up to a deep document
+        let siblings = format!("[{}]", vec![ nested_arrays(MAX_DEPTH - 1); 4 ].join(","));
+        assert!(Json::parse(&siblings).is_ok());
+    }
+
+    #[test]
+    fn scalars_and_containers() {
+        assert_eq!(Json::parse(" null ").unwrap(), Json::Null);
+        assert_eq!(Json::parse("true").unwrap().as_bool(), Some(true));
+        assert_eq!(Json::parse("-12.5e-1").unwrap().as_f64(), Some(-1.25));
+        let document = Json::parse(r#"{ "name": "box", "scale": [ 1, 2, 3 ], "nested": { "empty": [], "none": {} } }"#).unwrap();
+        assert_eq!(document.get("name").and_then(Json::as_str), Some("box"));
+        assert_eq!(document.get("scale").and_then(Json::as_f32_array::<3>), Some([ 1.0, 2.0, 3.0 ]));
+        assert_eq!(document.get("nested").and_then( | nested | nested.get("empty") ), Some(&Json::Array(Vec::new())));
+        assert_eq!(document.get("missing"), None);
+        // The last of a repeated key wins
+        assert_eq!(Json::parse(r#"{"a":1,"a":2}"#).unwrap().get("a"), Some(&Json::Number(2.0)));
+    }
+
+    #[test]
+    fn accessors_check_the_type_and_range() {
+        assert_eq!(Json::Number(3.0).as_usize(), Some(3));
+        assert_eq!(Json::Number(3.5).as_usize(), None);
+        assert_eq!(Json::Number(-1.0).as_usize(), None);
+        assert_eq!(Json::String("1".to_string()).as_f64(), None);
+        assert_eq!(Json::Number(1.0).get("a"), None);
+        // Wrong length or a non number inside -> None, not a partial array
+        assert_eq!(Json::parse("[1,2]").unwrap().as_f32_array::<3>(), None);
+        assert_eq!(Json::parse("[1,\"2\",3]").unwrap().as_f32_array::<3>(), None);
+    }
+
+    #[test]
+    fn strings_unescape() {
+        let text = Json::parse(r#""a\"b\\c\/d\b\f\n\r\t \u00e9 \ud83d\ude00""#).unwrap();
+        assert_eq!(text.as_str(), Some("a\"b\\c/d\u{8}\u{c}\n\r\t é 😀"));
+        // A lone surrogate has no character, it becomes the replacement character
+        assert_eq!(Json::parse(r#""\udc00""#).unwrap().as_str(), Some("\u{FFFD}"));
+    }
+
+    #[test]
+    fn malformed_documents_are_errors() {
+        for source in [
+            "", "   ", "[1,]", "{\"a\":1,}", "{a:1}", "[1 2]", "{\"a\" 1}", "\"open", "\"\\x\"", "\"\\u12\"", "\"\\uzzzz\"",
+            "tru", "nul", "-", "1.2.3", "01x", "[", "{", "]", "1 2", "{\"a\":}", "+1", "1e999", "[-1e400]",
+        ] {
+            assert!(Json::parse(source).is_err(), "{:?} parsed", source);
+        }
+        assert_eq!(Json::parse("[1,]").unwrap_err(), "JSON error at byte 3: unexpected character");
+        assert_eq!(Json::parse("{} x").unwrap_err(), "JSON error at byte 3: trailing characters after the JSON value");
+    }
+
+    #[test]
+    fn display_is_compact_and_escaped() {
+        let document = Json::parse(r#"{ "b": [ 1.5, null, true ], "a": "tab\there \u0001" }"#).unwrap();
+        // Keys come out sorted
+        assert_eq!(document.to_string(), r#"{"a":"tab\there \u0001","b":[1.5,null,true]}"#);
+        assert_eq!(Json::Number(f64::NAN).to_string(), "null");
+        assert_eq!(Json::Number(f64::INFINITY).to_string(), "null");
+    }
+
+    fn random_json( rng: &mut Rng, depth: usize ) -> Json {
+        match rng.below(if depth == 0 { 4 } else { 6 }) {
+            0 => Json::Null,
+            1 => Json::Bool(rng.chance(2)),
+            2 => {
+                // Whole numbers, fractions and the extremes of the exponent range
+                let number = match rng.below(3) {
+                    0 => rng.below(2001) as f64 - 1000.0,
+                    1 => (rng.next_u64() as i64) as f64 / 1e6,
+                    _ => f64::from_bits(rng.next_u64()),
+                };
+                Json::Number(if number.is_finite() { number } else { 0.0 })
+            },
+            3 => Json::String(rng.text(12)),
+            4 => Json::Array((0..rng.below(5)).map( | _ | random_json(rng, depth - 1) ).collect()),
+            _ => Json::Object((0..rng.below(5)).map( | _ | ( rng.text(6), random_json(rng, depth - 1) ) ).collect()),
+        }
+    }
+
+    #[test]
+    fn generated_documents_round_trip() {
+        let mut rng = Rng::new(0x6a73_6f6e);
+        for _ in 0..2000 {
+            let value = random_json(&mut rng, 4);
+            let text = value.to_string();
+            assert_eq!(Json::parse(&text).as_ref(), Ok(&value), "{}", text);
+        }
+    }
+
+    #[test]
+    fn mangled_documents_never_panic() {
+        let mut rng = Rng::new(0xbad_5eed);
+        for _ in 0..5000 {
+            let text = random_json(&mut rng, 3).to_string();
+            let mangled = rng.mutate(&text, b"[]{}\",:\\u0123456789.eE+-tfn ");
+            // Ok or Err are both fine, and whatever parses prints back to the same value
+            if let Ok(value) = Json::parse(&mangled) {
+                assert_eq!(Json::parse(&value.to_string()), Ok(value), "{:?}", mangled);
+            }
+        }
+    }
+}
@@ -0,0 +1,535 @@
+// Small TOML reader -> the subset our config needs
+//   [table] / [table.sub], key = value, dotted and quoted keys, # comments
+//   strings ( "basic" and 'literal' ), integers, floats, booleans and arrays ( may span lines )
+// Not supported: [[arrays of tables]], inline tables, multi-line strings, dates
+// Hand rolled instead of the toml crate -> Config::set wants a flat list of dotted keys in file order, each with
+// the line it came from for the error message, which a serde tree doesn't keep. The tests below cover the subset
+// with generated files and mangled copies of them
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Toml {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<Toml>),
+}
+
+// One value with its full dotted key, e.g. `window.width`, and the line it was set on
+#[derive(Debug, Clone, PartialEq)]
+pub struct TomlEntry {
+    pub key: String,
+    pub value: Toml,
+    pub line: usize,
+}
+
+impl Toml {
+    // Flat list of every key in file order, setting a key twice is an error
+    pub fn parse( source: &str ) -> Result<Vec<TomlEntry>, String> {
+        let mut parser = Parser { bytes: source.as_bytes(), position: 0, line: 1 };
+        let mut entries = Vec::new();
+        let mut seen = HashSet::new();
+        let mut table = Vec::new();
+
+        loop {
+            parser.skip_blank_lines();
+            let Some(&byte) = parser.bytes.get(parser.position) else {
+                break;
+            };
+            let line = parser.line;
+            if byte == b'[' {
+                parser.position += 1;
+                if parser.peek() == Some(b'[') {
+                    return Err(parser.error("arrays of tables are not supported"));
+                }
+                table = parser.key()?;
+                parser.skip_spaces();
+                parser.expect(b']')?;
+            } else {
+                let mut key = table.clone();
+                key.extend(parser.key()?);
+                parser.skip_spaces();
+                parser.expect(b'=')?;
+                parser.skip_spaces();
+                let value = parser.value()?;
+                let key = key.join(".");
+                if !seen.insert(key.clone()) {
+                    return Err(format!("TOML error on line {}: {} is set twice", line, key));
+                }
+                entries.push(TomlEntry { key, value, line });
+            }
+            parser.end_of_line()?;
+        }
+        Ok(entries)
+    }
+
+    // One value on its own, e.g. from the command line -> `800`, `"title"`, `[ 1.0, 0.5, 0.2 ]`
+    pub fn parse_value( source: &str ) -> Result<Toml, String> {
+        let mut parser = Parser { bytes: source.as_bytes(), position: 0, line: 1 };
+        parser.skip_spaces();
+        let value = parser.value()?;
+        parser.skip_spaces();
+        if parser.position != parser.bytes.len() {
+            return Err(parser.error("trailing characters after the value"));
+        }
+        Ok(value)
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Toml::String(_) => "a string",
+            Toml::Integer(_) => "an integer",
+            Toml::Float(_) => "a float",
+            Toml::Bool(_) => "a boolean",
+            Toml::Array(_) => "an array",
+        }
+    }
+
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            Toml::Integer(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Integers count too, `1` is a fine color channel
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Toml::Float(value) => Some(*value),
+            Toml::Integer(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Toml::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Toml::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Toml>> {
+        match self {
+            Toml::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error( &self, message: &str ) -> String {
+        format!("TOML error on line {}: {}", self.line, message)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.position += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some(b'#') {
+            while !matches!(self.peek(), None | Some(b'\n')) {
+                self.position += 1;
+            }
+        }
+    }
+
+    // Spaces, comments and newlines -> between entries and inside arrays
+    fn skip_blank_lines(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some(b'\n') => {
+                    self.position += 1;
+                    self.line += 1;
+                },
+                Some(b'\r') => self.position += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn end_of_line( &mut self ) -> Result<(), String> {
+        self.skip_spaces();
+        self.skip_comment();
+        if self.peek() == Some(b'\r') {
+            self.position += 1;
+        }
+        match self.peek() {
+            None => Ok(()),
+            Some(b'\n') => {
+                self.position += 1;
+                self.line += 1;
+                Ok(())
+            },
+            Some(_) => Err(self.error("expected the end of the line")),
+        }
+    }
+
+    fn expect( &mut self, byte: u8 ) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", byte as char)))
+        }
+    }
+
+    // `a.b."c d"` -> [ "a", "b", "c d" ]
+    fn key( &mut self ) -> Result<Vec<String>, String> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_spaces();
+            let part = match self.peek() {
+                Some(b'"') => self.basic_string()?,
+                Some(b'\'') => self.literal_string()?,
+                _ => {
+                    let start = self.position;
+                    while matches!(self.peek(), Some(byte) if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-') {
+                        self.position += 1;
+                    }
+                    if start == self.position {
+                        return Err(self.error("expected a key"));
+                    }
+                    String::from_utf8_lossy(&self.bytes[start..self.position]).into_owned()
+                },
+            };
+            parts.push(part);
+            self.skip_spaces();
+            if self.peek() != Some(b'.') {
+                return Ok(parts);
+            }
+            self.position += 1;
+        }
+    }
+
+    fn value( &mut self ) -> Result<Toml, String> {
+        match self.peek() {
+            Some(b'"') => Ok(Toml::String(self.basic_string()?)),
+            Some(b'\'') => Ok(Toml::String(self.literal_string()?)),
+            Some(b'[') => self.array(),
+            Some(b'{') => Err(self.error("inline tables are not supported")),
+            Some(b't' | b'f') => self.boolean(),
+            Some(b'+' | b'-' | b'0'..=b'9') => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn boolean( &mut self ) -> Result<Toml, String> {
+        for ( word, value ) in [ ( "true", true ), ( "false", false ) ] {
+            if self.bytes[self.position..].starts_with(word.as_bytes()) {
+                self.position += word.len();
+                return Ok(Toml::Bool(value));
+            }
+        }
+        Err(self.error("expected true or false"))
+    }
+
+    fn number( &mut self ) -> Result<Toml, String> {
+        let start = self.position;
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'-' | b'.' | b'_')) {
+            self.position += 1;
+        }
+        // `1_000` is allowed, the underscores are only for reading
+        let text = String::from_utf8_lossy(&self.bytes[start..self.position]).replace('_', "");
+        let is_float = text.contains([ '.', 'e', 'E' ]);
+        if is_float {
+            // `1e999` overflows to infinity, which nothing in the config can use
+            text.parse::<f64>().ok().filter( | number | number.is_finite() ).map(Toml::Float)
+                .ok_or_else( || self.error(&format!("invalid float '{}'", text)) )
+        } else {
+            text.parse::<i64>().map(Toml::Integer).map_err( | _ | self.error(&format!("invalid integer '{}'", text)) )
+        }
+    }
+
+    fn array( &mut self ) -> Result<Toml, String> {
+        self.expect(b'[')?;
+        let mut values = Vec::new();
+        loop {
+            self.skip_blank_lines();
+            if self.peek() == Some(b']') {
+                self.position += 1;
+                return Ok(Toml::Array(values));
+            }
+            values.push(self.value()?);
+            self.skip_blank_lines();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(Toml::Array(values));
+                },
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn literal_string( &mut self ) -> Result<String, String> {
+        self.expect(b'\'')?;
+        let start = self.position;
+        while !matches!(self.peek(), None | Some(b'\'' | b'\n')) {
+            self.position += 1;
+        }
+        if self.peek() != Some(b'\'') {
+            return Err(self.error("unterminated string"));
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.position]).map_err( | _ | self.error("invalid UTF-8 in string") )?;
+        self.position += 1;
+        Ok(text.to_string())
+    }
+
+    fn basic_string( &mut self ) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(byte) = self.peek() else {
+                return Err(self.error("unterminated string"));
+            };
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\n' => return Err(self.error("unterminated string")),
+                b'\\' => {
+                    let Some(escaped) = self.peek() else {
+                        return Err(self.error("unterminated escape"));
+                    };
+                    self.position += 1;
+                    match escaped {
+                        b'"' => bytes.push(b'"'),
+                        b'\\' => bytes.push(b'\\'),
+                        b'b' => bytes.push(0x08),
+                        b'f' => bytes.push(0x0c),
+                        b'n' => bytes.push(b'\n'),
+                        b'r' => bytes.push(b'\r'),
+                        b't' => bytes.push(b'\t'),
+                        b'u' | b'U' => {
+                            let length = if escaped == b'u' { 4 } else { 8 };
+                            let digits = self.bytes.get(self.position..self.position + length).ok_or_else( || self.error("short unicode escape") )?;
+                            let code = std::str::from_utf8(digits).ok()
+                                .and_then( | text | u32::from_str_radix(text, 16).ok() )
+                                .and_then(char::from_u32)
+                                .ok_or_else( || self.error("invalid unicode escape") )?;
+                            self.position += length;
+                            bytes.extend_from_slice(code.to_string().as_bytes());
+                        },
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                },
+                _ => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err( | _ | self.error("invalid UTF-8 in string") )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fuzz::Rng;
+
+    fn value_of<'a>( entries: &'a [TomlEntry], key: &str ) -> &'a Toml {
+        &entries.iter().find( | entry | entry.key == key ).unwrap_or_else( || panic!("no {}", key) ).value
+    }
+
+    #[test]
+    fn tables_prefix_their_keys() {
+        let entries = Toml::parse("top = 1\n[window]\nwidth = 800\n[log.targets]\ngl = \"debug\"\n\"quoted key\".x = true\n").unwrap();
+        let keys = entries.iter().map( | entry | ( entry.key.as_str(), entry.line ) ).collect::<Vec<_>>();
+        assert_eq!(keys, vec![ ( "top", 1 ), ( "window.width", 3 ), ( "log.targets.gl", 5 ), ( "log.targets.quoted key.x", 6 ) ]);
+        assert_eq!(value_of(&entries, "window.width"), &Toml::Integer(800));
+    }
+
+    #[test]
+    fn arrays_may_span_lines_and_hold_comments() {
+        let entries = Toml::parse("color = [\n  1.0, # red\n  0.5,\n  0,\n]\nempty = []\n").unwrap();
+        assert_eq!(value_of(&entries, "color"), &Toml::Array(vec![ Toml::Float(1.0), Toml::Float(0.5), Toml::Integer(0) ]));
+        assert_eq!(value_of(&entries, "empty"), &Toml::Array(Vec::new()));
+    }
+
+    #[test]
+    fn strings_unescape_basic_but_not_literal() {
+        let entries = Toml::parse(r#"basic = "a \"b\"\n\tc \\ \u00e9"
+literal = 'C:\path\n'"#).unwrap();
+        assert_eq!(value_of(&entries, "basic").as_str(), Some("a \"b\"\n\tc \\ é"));
+        assert_eq!(value_of(&entries, "literal").as_str(), Some(r"C:\path\n"));
+    }
+
+    #[test]
+    fn integers_count_as_floats() {
+        assert_eq!(Toml::Integer(1).as_float(), Some(1.0));
+        assert_eq!(Toml::parse_value("1_000").unwrap(), Toml::Integer(1000));
+        assert_eq!(Toml::parse_value("-2.5e1").unwrap().as_float(), Some(-25.0));
+        // ... but floats are not integers
+        assert_eq!(Toml::parse_value("2.0").unwrap().as_integer(), None);
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let entries = Toml::parse("# header\n\n  # indented\r\nvsync = false # trailing\n").unwrap();
+        assert_eq!(entries, vec![ TomlEntry { key: "vsync".to_string(), value: Toml::Bool(false), line: 4 } ]);
+        // A '#' inside a string is not a comment
+        assert_eq!(Toml::parse_value("\"#fff\"").unwrap().as_str(), Some("#fff"));
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        let error = | source: &str | Toml::parse(source).unwrap_err();
+        assert!(error("a = 1\nb = \n").starts_with("TOML error on line 2:"), "{}", error("a = 1\nb = \n"));
+        assert!(error("a = 1\n\n[t]\na = 2\nb = \"open\n").starts_with("TOML error on line 5:"));
+        assert_eq!(error("x = 1\n[t]\n\nx = 2\nx = 3\n"), "TOML error on line 5: t.x is set twice");
+        assert!(error("color = [\n1,\n2\n3 ]\n").starts_with("TOML error on line 4:"));
+        assert!(error("[[tables]]\n").contains("arrays of tables"));
+        assert!(error("a = 1 2\n").contains("end of the line"));
+    }
+
+    #[test]
+    fn keys_may_be_bare_quoted_or_dotted() {
+        let entries = Toml::parse("a-b_c1 = 1\n\"x.y\" = 2\n'lit' . inner = 3\n[ spaced . table ]\nk=4\n").unwrap();
+        let keys = entries.iter().map( | entry | entry.key.as_str() ).collect::<Vec<_>>();
+        assert_eq!(keys, vec![ "a-b_c1", "x.y", "lit.inner", "spaced.table.k" ]);
+        // Dotting into an existing key still counts as setting it twice only for the exact same key
+        assert!(Toml::parse("a.b = 1\na.c = 2\n").is_ok());
+        assert!(Toml::parse("a.b = 1\n[a]\nb = 2\n").is_err());
+    }
+
+    #[test]
+    fn numbers_and_booleans() {
+        let value = | source: &str | Toml::parse_value(source).unwrap();
+        assert_eq!(value("+7"), Toml::Integer(7));
+        assert_eq!(value("-0"), Toml::Integer(0));
+        assert_eq!(value("9223372036854775807"), Toml::Integer(i64::MAX));
+        assert_eq!(value("1e3"), Toml::Float(1000.0));
+        assert_eq!(value("  true  "), Toml::Bool(true));
+        assert_eq!(value("[ [ 1, 2 ], [ 'a' ], [] ]").as_array().map( | values | values.len() ), Some(3));
+        for source in [ "9223372036854775808", "0x10", "1.2.3", "1__0e", "1e999", "inf", "nan", "True", "yes", "truex", "1 2" ] {
+            assert!(Toml::parse_value(source).is_err(), "{:?} parsed", source);
+        }
+    }
+
+    #[test]
+    fn unicode_escapes_and_line_endings() {
+        let entries = Toml::parse("a = \"\\U0001F600 \\u20AC\"\r\nb = 'x'\r\n").unwrap();
+        assert_eq!(value_of(&entries, "a").as_str(), Some("😀 €"));
+        assert_eq!(entries[1].line, 2);
+        for source in [ "a = \"\\uD800\"\n", "a = \"\\u12\"\n", "a = \"\\q\"\n", "a = 'open\n", "a = \"\\" ] {
+            assert!(Toml::parse(source).is_err(), "{:?} parsed", source);
+        }
+    }
+
+    #[test]
+    fn unsupported_and_malformed_lines() {
+        let error = | source: &str | Toml::parse(source).unwrap_err();
+        assert!(error("a = { b = 1 }\n").contains("inline tables"));
+        assert!(error("[table\n").contains("expected ']'"));
+        assert!(error("= 1\n").contains("expected a key"));
+        assert!(error("a 1\n").contains("expected '='"));
+        assert_eq!(error("a = [ 1, 2\n"), "TOML error on line 2: expected ',' or ']'");
+        assert!(error("a = 1979-05-27\n").contains("invalid"));
+    }
+
+    // Inverse of the parser for the generated files, strings always come out as basic strings
+    fn write_value( value: &Toml ) -> String {
+        match value {
+            Toml::String(text) => {
+                let escaped = text.chars().map( | character | match character {
+                    '"' => "\\\"".to_string(),
+                    '\\' => "\\\\".to_string(),
+                    '\n' => "\\n".to_string(),
+                    control if (control as u32) < 0x20 || control == '\u{7f}' => format!("\\u{:04X}", control as u32),
+                    other => other.to_string(),
+                }).collect::<String>();
+                format!("\"{}\"", escaped)
+            },
+            Toml::Integer(number) => number.to_string(),
+            // Debug always keeps a '.' or an exponent -> reads back as a float
+            Toml::Float(number) => format!("{:?}", number),
+            Toml::Bool(flag) => flag.to_string(),
+            Toml::Array(values) => format!("[ {} ]", values.iter().map(write_value).collect::<Vec<String>>().join(", ")),
+        }
+    }
+
+    fn random_value( rng: &mut Rng, depth: usize ) -> Toml {
+        match rng.below(if depth == 0 { 4 } else { 5 }) {
+            0 => Toml::String(rng.text(10)),
+            1 => Toml::Integer(rng.next_u64() as i64 >> rng.below(64)),
+            2 => Toml::Float(rng.below(20001) as f64 / 8.0 - 1000.0),
+            3 => Toml::Bool(rng.chance(2)),
+            _ => Toml::Array((0..rng.below(4)).map( | _ | random_value(rng, depth - 1) ).collect()),
+        }
+    }
+
+    // Tables, comments and blank lines in between, returns the file and what it must parse to
+    fn random_file( rng: &mut Rng ) -> ( String, Vec<TomlEntry> ) {
+        let ( mut source, mut expected, mut table ) = ( String::new(), Vec::new(), String::new() );
+        let mut line = 1;
+        for index in 0..rng.below(8) {
+            if rng.chance(3) {
+                table = format!("t{}", index);
+                source += &format!("[{}]\n", table);
+                line += 1;
+            }
+            if rng.chance(4) {
+                source += "# comment = [ \"not a value\"\n\n";
+                line += 2;
+            }
+            let value = random_value(rng, 2);
+            let key = format!("k{}", index);
+            source += &format!("{} = {} # trailing\n", key, write_value(&value));
+            let key = if table.is_empty() { key } else { format!("{}.{}", table, key) };
+            expected.push(TomlEntry { key, value, line });
+            line += 1;
+        }
+        ( source, expected )
+    }
+
+    #[test]
+    fn generated_files_round_trip() {
+        let mut rng = Rng::new(0x746f_6d6c);
+        for _ in 0..2000 {
+            let ( source, expected ) = random_file(&mut rng);
+            assert_eq!(Toml::parse(&source).as_ref(), Ok(&expected), "{}", source);
+        }
+    }
+
+    #[test]
+    fn mangled_files_never_panic() {
+        let mut rng = Rng::new(0xbad_70e1);
+        for _ in 0..5000 {
+            let ( source, _ ) = random_file(&mut rng);
+            let mangled = rng.mutate(&source, b"[]=\"'.,#\\\n uU0123456789+-_eE{}tf");
+            // Errors always name a line. Whatever parses writes back as one quoted key per line and reads the same
+            match Toml::parse(&mangled) {
+                Ok(entries) => {
+                    let rewritten = entries.iter()
+                        .map( | entry | format!("{} = {}\n", write_value(&Toml::String(entry.key.clone())), write_value(&entry.value)) )
+                        .collect::<String>();
+                    let reparsed = Toml::parse(&rewritten).unwrap_or_else( | err | panic!("{:?} -> {:?} -> {}", mangled, rewritten, err) );
+                    let pairs = | entries: &[TomlEntry] | entries.iter().map( | entry | ( entry.key.clone(), entry.value.clone() ) ).collect::<Vec<_>>();
+                    assert_eq!(pairs(&reparsed), pairs(&entries), "{:?}", mangled);
+                },
+                Err(err) => assert!(err.starts_with("TOML error on line "), "{:?} -> {}", mangled, err),
+            }
+        }
+    }
+}